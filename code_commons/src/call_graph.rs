@@ -1,7 +1,11 @@
 // TODO: Consider splitting the file.
 
+use crate::defaults;
 use crate::CoderunNotifiable;
+use crate::recursion_folding::RecursionFoldingFilter;
 use std::{cell::RefCell, rc::Rc};
+#[cfg(feature = "call_timing")]
+use std::time::{Duration, Instant};
 
 type Link = Rc<RefCell<CallNode>>;
 
@@ -19,6 +23,12 @@ struct CallNode {
     /// (`while` and `for` do not return a value other than unit `()`).
     #[cfg(feature = "ret_val_logging")]
     ret_val: Option<String>,
+    /// The moment of the call or the loop body start (`None` if the call timing is off).
+    #[cfg(feature = "call_timing")]
+    start: Option<Instant>,
+    /// The wall-clock duration of the call or the loop body. `None` until the node has ended.
+    #[cfg(feature = "call_timing")]
+    duration: Option<Duration>,
     /// Nested calls made by this node (not locally defined functions/closures).
     children: Vec<Link>,
     /// How many times this call or loop body repeats during execution,
@@ -76,19 +86,32 @@ struct CallNode {
     /// // f() repeats 3 time(s).
     /// ```
     followed_by_flush: bool,
+    /// Number of calls in the chain of the same function (or closure) directly calling itself,
+    /// ending with this call. `1` for a non-recursive call (and for a loop body).
+    recursion_depth: usize,
+    /// Whether this call is recursion-folded, i.e. its `recursion_depth` exceeded the call graph's
+    /// recursion threshold by the moment of the call. Such a call is logged at the call depth of its parent
+    /// (rather than one level deeper), such that the deep recursion doesn't make the indent grow further.
+    recursion_folded: bool,
 }
 
 impl CallNode {
-    /// Creates a new call tree node.
-    fn new(kind: ItemKind) -> Self {
+    /// Creates a new call tree node (whose duration is measured if `timed`).
+    fn new(kind: ItemKind, #[cfg(feature = "call_timing")] timed: bool) -> Self {
         Self {
             kind: kind,
             #[cfg(feature = "ret_val_logging")]
             ret_val: None,
+            #[cfg(feature = "call_timing")]
+            start: timed.then(Instant::now),
+            #[cfg(feature = "call_timing")]
+            duration: None,
             children: Vec::new(),
             repeat_count: RepeatCount::new(),
             has_ended: false,
             followed_by_flush: false,
+            recursion_depth: 1,
+            recursion_folded: false,
         }
     }
     #[cfg(feature = "ret_val_logging")]
@@ -102,6 +125,14 @@ impl CallNode {
     fn get_ret_val(&self) -> &Option<String> {
         &self.ret_val
     }
+    /// Marks the node as ended (the function or closure has returned, or the loop body has ended).
+    fn end(&mut self) {
+        self.has_ended = true;
+        #[cfg(feature = "call_timing")]
+        {
+            self.duration = self.start.map(|start| start.elapsed());
+        }
+    }
 }
 
 /// Cached-node tracking state used to avoid duplicate logging.
@@ -114,14 +145,21 @@ impl CallNode {
 /// `node_being_cached` points to the current repeated call.
 ///
 /// When the repeat sequence ends, the repeat count is flushed and caching stops.
+///
+/// Similarly, if a sequence of siblings starts repeating (e.g. `a() b() a() b()`),
+/// the first occurrence of the sequence is logged and kept in the call graph,
+/// while the subsequent occurrences are cached (see `SequenceCachingInfo`).
 struct CachingInfo {
     /// Node used to compare the `node_being_cached` against.
     /// `None` for the _initial_ loop body specified by `node_being_cached`.
     model_node: Option<Link>,
-    /// Node currently being cached. `None` means caching is inactive.
+    /// Node currently being cached. `None` means caching is inactive (unless the `sequence` is being cached).
     node_being_cached: Option<Link>,
-    /// Call depth shared by `model_node` and `node_being_cached`.
+    /// Call depth shared by `model_node` and `node_being_cached` (or by the siblings of the `sequence`).
     call_depth: usize,
+    /// The periodic sequence caching state. `None` unless a repeated sequence of siblings is being cached.
+    /// Is mutually exclusive with the `model_node` and `node_being_cached`.
+    sequence: Option<SequenceCachingInfo>,
 }
 impl CachingInfo {
     /// Creates a new `CachingInfo`.
@@ -130,19 +168,47 @@ impl CachingInfo {
             model_node: None,
             node_being_cached: None,
             call_depth: 0,
+            sequence: None,
         }
     }
     /// Stops caching.
     fn clear(&mut self) {
         self.node_being_cached = None; // Stop caching.
         self.model_node = None; // For consistency. Not required.
+        self.sequence = None;
     }
     /// Returns `true` if caching is active.
     fn is_active(&self) -> bool {
-        self.node_being_cached.is_some()
+        self.node_being_cached.is_some() || self.sequence.is_some()
     }
 }
 
+/// Periodic sequence caching state.
+///
+/// If the parent's children end with a sequence of `len` siblings (the _model sequence_, already logged)
+/// and the subsequent siblings start repeating the model sequence, then the subsequent siblings are cached.
+/// Each time the whole model sequence is repeated, the sequence's repeat count is incremented
+/// and the repeated siblings are removed from the call graph.
+///
+/// ```ignore
+/// parent() {
+///     . . .
+///     a() { .. }  // The model sequence (`model_start` is the index of `a()` in the parent's list of children).
+///     b() { .. }  // The model sequence (`len` is 2).
+///     // (a(), b()) repeats 5 time(s).    // `repeat_count`. Not yet logged.
+///     a() { .. }  // The siblings being cached (the sequence repeat in progress).
+/// ```
+struct SequenceCachingInfo {
+    /// The parent (call or loop body) whose children are cached.
+    parent: Link,
+    /// The index of the model sequence's first node in the parent's list of children.
+    model_start: usize,
+    /// The length of the model sequence (at least 2).
+    len: usize,
+    /// How many times the model sequence repeats.
+    repeat_count: RepeatCount,
+}
+
 /// Repeat-count data type for calls and loop bodies.
 type RepeatCountType = usize;
 /// Maximum (saturation) value for repeat counts.
@@ -159,6 +225,7 @@ const REPEAT_COUNT_MAX: RepeatCountType = RepeatCountType::MAX;
 /// "at least" or "unknown" depending on which part saturated.
 //
 // TODO: Consider RepeatCountCategory -> RepeatCountInfo (since also contains a value).
+#[derive(Clone, Copy)]
 pub enum RepeatCountCategory {
     /// Neither `overall` nor `flushed` saturated. Display `overall - flushed`
     /// (e.g. `// Repeats 6 time(s).`).
@@ -219,15 +286,90 @@ impl ItemKind {
     }
 }
 
+/// Wall-clock duration statistics (count, total, min, max) of the repeats of a call-tree item.
+#[cfg(feature = "call_timing")]
+#[derive(Clone, Copy)]
+pub struct DurationStats {
+    /// Number of the durations taken into account (saturates at `u32::MAX`).
+    count: u32,
+    /// Sum of the durations.
+    total: Duration,
+    /// Minimum duration.
+    min: Duration,
+    /// Maximum duration.
+    max: Duration,
+}
+#[cfg(feature = "call_timing")]
+impl DurationStats {
+    /// Creates new empty statistics.
+    pub fn new() -> Self {
+        Self {
+            count: 0,
+            total: Duration::ZERO,
+            min: Duration::MAX,
+            max: Duration::ZERO,
+        }
+    }
+    /// Creates the statistics from the parts (e.g. previously obtained with the getters).
+    /// If the `count` is `0` then the other parts are ignored.
+    pub fn from_parts(count: u32, total: Duration, min: Duration, max: Duration) -> Self {
+        if count == 0 {
+            return Self::new();
+        }
+        Self { count, total, min, max }
+    }
+    /// Takes a duration into account.
+    pub fn add(&mut self, duration: Duration) {
+        self.count = self.count.saturating_add(1);
+        self.total = self.total.saturating_add(duration);
+        self.min = self.min.min(duration);
+        self.max = self.max.max(duration);
+    }
+    /// Returns `true` if no durations have been taken into account.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+    /// Returns the number of the durations taken into account.
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+    /// Returns the sum of the durations.
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+    /// Returns the minimum duration (`Duration::ZERO` if empty).
+    pub fn min(&self) -> Duration {
+        if self.is_empty() { Duration::ZERO } else { self.min }
+    }
+    /// Returns the average duration (`Duration::ZERO` if empty).
+    pub fn avg(&self) -> Duration {
+        if self.is_empty() { Duration::ZERO } else { self.total / self.count }
+    }
+    /// Returns the maximum duration.
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+}
+#[cfg(feature = "call_timing")]
+impl Default for DurationStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Call-tree item repeat count, contains:
 /// * `overall` — total repeats (saturates at `REPEAT_COUNT_MAX`),
-/// * `flushed` — last flushed `overall` value (<= `overall`).
+/// * `flushed` — last flushed `overall` value (<= `overall`),
+/// * (with the "call_timing" feature) the duration statistics of the repeats since the last flush.
 #[derive(Clone, Copy)]
 pub struct RepeatCount {
     /// Total repeat count used in subtree comparison.
     overall: RepeatCountType,
     /// Last flushed `overall` value. Displayed count is `overall - flushed`.
     flushed: RepeatCountType, // flushed <= overall
+    /// Duration statistics of the repeats since the last flush (`overall - flushed` repeats).
+    #[cfg(feature = "call_timing")]
+    non_flushed_timing: DurationStats,
 }
 impl RepeatCount {
     /// Creates a new repeat count with zeroed values.
@@ -235,6 +377,8 @@ impl RepeatCount {
         Self {
             overall: 0,
             flushed: 0,
+            #[cfg(feature = "call_timing")]
+            non_flushed_timing: DurationStats::new(),
         }
     }
     /// Returns the non-flushed repeat-count category and value for logging.
@@ -253,14 +397,28 @@ impl RepeatCount {
         self.overall == self.flushed && self.flushed < REPEAT_COUNT_MAX
     }
     /// Increments `overall` unless already saturated.
-    pub fn inc(&mut self) {
+    /// Takes into account the optional duration of the repeat.
+    pub fn inc(&mut self, #[cfg(feature = "call_timing")] duration: Option<Duration>) {
         if self.overall < REPEAT_COUNT_MAX {
             self.overall += 1
         }
+        #[cfg(feature = "call_timing")]
+        if let Some(duration) = duration {
+            self.non_flushed_timing.add(duration);
+        }
+    }
+    /// Returns the duration statistics of the repeats since the last flush.
+    #[cfg(feature = "call_timing")]
+    pub fn non_flushed_timing(&self) -> &DurationStats {
+        &self.non_flushed_timing
     }
     /// Marks repeat count as flushed by copying `overall` to `flushed`.
     pub fn mark_flushed(&mut self) {
-        self.flushed = self.overall
+        self.flushed = self.overall;
+        #[cfg(feature = "call_timing")]
+        {
+            self.non_flushed_timing = DurationStats::new();
+        }
     }
 }
 impl core::cmp::PartialEq for RepeatCount {
@@ -282,7 +440,7 @@ pub struct CallGraph {
     /// The link to a pseudoroot is always at the bottom of the call stack.
     /// The pseudoroot is never logged; its children have call depth 0.
     /// In other words the call depth of a call is `call_stack.len() - 1` when
-    /// the call is not yet on the call stack.
+    /// the call is not yet on the call stack (less the number of recursion-folded calls on the call stack).
     call_stack: Vec<Link>,
 
     /// Pointer to the current node representing the running call or loop body.
@@ -298,6 +456,34 @@ pub struct CallGraph {
 
     /// Logging sink notified about call-graph updates.
     coderun_notifiable: Rc<RefCell<dyn CoderunNotifiable>>,
+
+    /// Number of nodes currently in the call graph (the pseudoroot is not counted).
+    node_count: usize,
+    /// Optional maximum number of nodes in the call graph. `None` means unlimited.
+    ///
+    /// If a call or a loop body start is added when the `node_count` has reached the budget,
+    /// the repeat folding gets suspended (see `suspend_folding()`).
+    node_budget: Option<usize>,
+    /// Whether the repeat folding is suspended (upon reaching the node budget).
+    ///
+    /// If `true`, then the calls, returns, and loop body starts and ends are logged as they come
+    /// (plain streaming), without caching for repeat detection, and the call graph retains
+    /// only the nodes on the call stack (plus the latest child of each of them).
+    folding_suspended: bool,
+
+    /// Maximum length of a sibling sequence whose repeats are folded (e.g. `// (a(), b()) repeats 9 time(s).`).
+    /// Values less than 2 disable the periodic sequence folding.
+    max_sequence_len: usize,
+
+    /// Optional recursion depth beyond which the self-recursive calls are recursion-folded
+    /// (logged at the call depth of their parent). `None` disables the recursion folding.
+    recursion_threshold: Option<usize>,
+    /// Number of recursion-folded calls on the call stack.
+    folded_call_count: usize,
+
+    /// Whether the wall-clock durations of the calls and loop bodies are measured (and notified about).
+    #[cfg(feature = "call_timing")]
+    call_timing: bool,
 }
 
 impl CallGraph {
@@ -307,15 +493,62 @@ impl CallGraph {
             name: String::from(""),
             #[cfg(feature = "params_logging")]
             param_vals: None,
-        })));
+        },
+        #[cfg(feature = "call_timing")]
+        false,
+        )));
         Self {
             current_node: pseudoroot.clone(),
             call_stack: vec![pseudoroot],
             caching_info: CachingInfo::new(/*CacheKind::Call*/),
-            coderun_notifiable,
+            coderun_notifiable: Rc::new(RefCell::new(RecursionFoldingFilter::new(coderun_notifiable))),
+            node_count: 0,
+            node_budget: None,
+            folding_suspended: false,
+            max_sequence_len: defaults::MAX_SEQUENCE_LEN,
+            recursion_threshold: defaults::RECURSION_THRESHOLD,
+            folded_call_count: 0,
+            #[cfg(feature = "call_timing")]
+            call_timing: false,
         }
     }
 
+    /// Sets whether the wall-clock durations of the calls and loop bodies are measured
+    /// and notified about (off by default, such that the log stays deterministic).
+    /// Is applied to the subsequent calls and loop bodies.
+    #[cfg(feature = "call_timing")]
+    pub fn set_call_timing(&mut self, call_timing: bool) {
+        self.call_timing = call_timing;
+    }
+
+    /// Sets the recursion depth beyond which the chains of the same function (or closure) directly calling itself
+    /// are recursion-folded (`None`, the default, disables the recursion folding).
+    ///
+    /// A recursion-folded call is logged at the call depth of its parent, along with its recursion depth
+    /// (e.g. `fib() recursion depth 40 {`), such that the deep recursion doesn't make the indent grow further.
+    /// Of a chain of the recursion-folded calls only the innermost frame is notified about, the middle frames are hidden
+    /// (until they make a different call, see `RecursionFoldingFilter`).
+    /// Is applied to the subsequent calls.
+    pub fn set_recursion_threshold(&mut self, recursion_threshold: Option<usize>) {
+        self.recursion_threshold = recursion_threshold;
+    }
+
+    /// Sets the maximum length of a sibling sequence whose repeats are folded
+    /// (e.g. `a() b() a() b() a() b()` is logged as `a() b() // (a(), b()) repeats 2 time(s).`).
+    /// Values less than 2 disable the periodic sequence folding.
+    pub fn set_max_sequence_len(&mut self, max_sequence_len: usize) {
+        self.max_sequence_len = max_sequence_len;
+    }
+
+    /// Sets the maximum number of nodes in the call graph (`None` means unlimited).
+    ///
+    /// Upon reaching the budget the call graph flushes what it has cached, notifies about
+    /// the repeat folding suspension, and degrades to plain streaming (for the rest of the thread's life),
+    /// such that a runaway process doesn't exhaust the memory.
+    pub fn set_node_budget(&mut self, node_budget: Option<usize>) {
+        self.node_budget = node_budget;
+    }
+
     /// Adds a call as a child of the current node, updating the call tree.
     /// May start caching if the new call has the same name as the previous sibling.
     /// If caching is inactive, logs the new call immediately.
@@ -335,12 +568,31 @@ impl CallGraph {
         call_name: &str,
         #[cfg(feature = "params_logging")] param_vals: Option<String>,
     ) {
-        // Create the new_sibling node:
-        let new_sibling = Rc::new(RefCell::new(CallNode::new(ItemKind::Call {
+        // Node budget support:
+        if self.folding_is_suspended_upon_new_node() {
+            self.add_call_streaming(
+                call_name,
+                #[cfg(feature = "params_logging")]
+                param_vals,
+            );
+            return;
+        }
+        self.node_count += 1;
+
+        // Periodic sequence folding support:
+        let new_sibling_kind = ItemKind::Call {
             name: String::from(call_name),
             #[cfg(feature = "params_logging")]
-            param_vals: param_vals.clone(),
-        })));
+            param_vals: None, // Is not compared.
+        };
+        self.stop_sequence_caching_unless_continued(Some(&new_sibling_kind));
+
+        // Create the new_sibling node:
+        let new_sibling = self.new_call_node(
+            call_name,
+            #[cfg(feature = "params_logging")]
+            param_vals,
+        );
 
         // While the updates have not been done, prepeare the info for later use.
         let siblings_call_depth = self.call_depth();
@@ -407,38 +659,44 @@ impl CallGraph {
                         .non_flushed_is_empty()
                     {
                         self.coderun_notifiable.borrow_mut().notify_repeat_count(
-                            siblings_call_depth,
+                            Self::node_call_depth(&previous_sibling.borrow(), siblings_call_depth),
                             &previous_sibling.borrow().kind,
                             previous_sibling.borrow().repeat_count.non_flushed(),
+                            #[cfg(feature = "call_timing")]
+                            previous_sibling.borrow().repeat_count.non_flushed_timing(),
                         );
                         previous_sibling.borrow_mut().repeat_count.mark_flushed();
                     }
-                    // Log the call being added.
-                    self.coderun_notifiable.borrow_mut().notify_call(
-                        self.call_depth(),
-                        &call_name,
-                        #[cfg(feature = "params_logging")]
-                        &param_vals,
-                    );
+                    // Periodic sequence folding support:
+                    // If the new_sibling starts repeating a sequence of the previous siblings (e.g. `a() b() a()`)
+                    //      begin caching the sequence starting with the new_sibling,
+                    // otherwise
+                    //      log the call being added.
+                    let optional_model_start = if previous_sibling.borrow().followed_by_flush {
+                        None // After the flush log the call in full.
+                    } else {
+                        self.find_sequence_model_start(&parent.borrow().children, false)
+                    };
+                    if let Some((model_start, len)) = optional_model_start {
+                        self.caching_info = CachingInfo {
+                            model_node: None,
+                            node_being_cached: None,
+                            call_depth: siblings_call_depth,
+                            sequence: Some(SequenceCachingInfo {
+                                parent: parent.clone(),
+                                model_start,
+                                len,
+                                repeat_count: RepeatCount::new(),
+                            }),
+                        };
+                    } else {
+                        // Log the call being added.
+                        self.notify_call_of(&new_sibling.borrow(), siblings_call_depth);
+                    }
 
-                    // TODO: Test thoroughly (a shallow test shows in Task Manager that the allocated memory doesn't grow).
                     // // Endless logging support:
-                    // if new_sibling is pseudoroot's child
-                    //    (the pseudoroot only is on the call stack,
-                    //     the new_sibling is not yet there but is already among the pseudoroot's children in the call tree)
-                    // { Drop the previous sibling. }
-                    if siblings_call_depth == 0 {
-                        // Remove new_sibling from the pseudoroot's list of children:
-                        parent.borrow_mut().children.pop();
-                        // Remove previous sibling from the pseudoroot's list of children:
-                        parent.borrow_mut().children.pop();
-                        // Add new_sibling to the pseudoroot's list of children again:
-                        parent
-                            // self.current_node // parent
-                            .borrow_mut()
-                            .children
-                            .push(new_sibling.clone());
-                    }
+                    // // if new_sibling is pseudoroot's child then the previous sibling is dropped
+                    // // by `prune_pseudoroot_children()` below.
                 } else {
                     // Otherwise (the previous and the new sibling have the same name
                     //    && previous sibling (including the repeat count) wasn't followed by a flush)
@@ -447,16 +705,12 @@ impl CallGraph {
                         model_node: Some(previous_sibling.clone()),
                         node_being_cached: Some(new_sibling.clone()),
                         call_depth: siblings_call_depth,
+                        sequence: None,
                     };
                 }
             } else {
                 // (no previous sibling) Log the call.
-                self.coderun_notifiable.borrow_mut().notify_call(
-                    self.call_depth(),
-                    &call_name,
-                    #[cfg(feature = "params_logging")]
-                    &param_vals,
-                );
+                self.notify_call_of(&new_sibling.borrow(), siblings_call_depth);
             }
         } else {
             // Caching is active.
@@ -465,15 +719,19 @@ impl CallGraph {
             // and that loopbody is initial then flush
             // (without flushing the notifiable/decorator, so that it doesn't add `\n` after `f() {`)
             // and stop caching.
-            if self.caching_info.model_node.is_none() {
+            if self.caching_info.sequence.is_none() && self.caching_info.model_node.is_none() {
                 self.flush(false); // It also stops caching.
             }
+            // else (the non-initial loopbody, a call or a sibling sequence is being cached) continue caching.
         }
         // Add new_sibling to the call stack:
-        self.call_stack.push(new_sibling.clone()); // [..., parent] -> [..., parent, new_sibling]
+        self.push_call(new_sibling.clone()); // [..., parent] -> [..., parent, new_sibling]
 
         // Mark that the subsequent calls will be added as children to the new_sibling:
         self.current_node = new_sibling.clone();
+
+        // Endless logging support:
+        self.prune_pseudoroot_children();
     }
 
     /// Adds a return to the current function in the call graph
@@ -538,6 +796,18 @@ impl CallGraph {
         //
         // Handle the return in the call graph (call stack, etc.).
 
+        // Node budget support:
+        if self.folding_suspended {
+            self.add_ret_streaming(
+                #[cfg(feature = "ret_val_logging")]
+                ret_val,
+            );
+            return;
+        }
+
+        // Periodic sequence folding support (the parent of the sequence being cached returns):
+        self.stop_sequence_caching_unless_continued(None);
+
         // Impl:
         let returning_sibling = self.current_node.clone();
         #[cfg(feature = "ret_val_logging")]
@@ -546,7 +816,7 @@ impl CallGraph {
         let children_call_depth = self.call_depth();
 
         // `returning_sibling.has_ended = true`:
-        returning_sibling.borrow_mut().has_ended = true;
+        returning_sibling.borrow_mut().end();
 
         // If caching is not active {
         if !self.caching_is_active() {
@@ -555,9 +825,12 @@ impl CallGraph {
                 && !last_child.borrow().repeat_count.non_flushed_is_empty()
             {
                 self.coderun_notifiable.borrow_mut().notify_repeat_count(
-                    children_call_depth, // While the returning_sibling is still on the call stack, the call depth reflects the last_child's call_depth.
+                    // While the returning_sibling is still on the call stack, the call depth reflects the last_child's call_depth.
+                    Self::node_call_depth(&last_child.borrow(), children_call_depth),
                     &last_child.borrow().kind,
                     last_child.borrow().repeat_count.non_flushed(),
+                    #[cfg(feature = "call_timing")]
+                    last_child.borrow().repeat_count.non_flushed_timing(),
                 );
                 last_child.borrow_mut().repeat_count.mark_flushed();
             }
@@ -572,6 +845,8 @@ impl CallGraph {
                         has_nested_calls,
                         #[cfg(feature = "ret_val_logging")]
                         self.current_node.borrow().get_ret_val(),
+                        #[cfg(feature = "call_timing")]
+                        returning_sibling.borrow().duration,
                     );
                 }
                 ItemKind::Loopbody { .. } => {
@@ -597,9 +872,13 @@ impl CallGraph {
             // If the call subtree of the returning and previous sibling are equal {
             if Self::trees_are_equal(&previous_sibling, &returning_sibling, false) {
                 // The previous sibling's repeat count is incremented,  // TODO: Use imperative mood: "Increment the previous sibling's repeat count".
-                previous_sibling.borrow_mut().repeat_count.inc();
+                previous_sibling.borrow_mut().repeat_count.inc(
+                    #[cfg(feature = "call_timing")]
+                    returning_sibling.borrow().duration,
+                );
                 // and the returning_sibling's call subtree is removed from the call graph.
                 parent_or_pseudoroot.borrow_mut().children.pop();
+                self.node_count -= Self::subtree_node_count(&returning_sibling);
                 // If caching is active
                 if self.caching_is_active() {
                     // If the previous sibling is the caching model node then
@@ -626,7 +905,9 @@ impl CallGraph {
                     if let Some(model_node) = self.caching_info.model_node.as_ref()
                         && model_node.as_ptr() == previous_sibling.as_ptr()
                     {
-                        let siblings_call_depth = children_call_depth - 1;
+                        // `- 1`: The returning_sibling is still on the call stack (unless recursion-folded).
+                        let siblings_call_depth = children_call_depth - 1
+                            + returning_sibling.borrow().recursion_folded as usize;
                         // Log the previous_sibling's repeat count, if non-zero,
                         if !previous_sibling
                             .borrow()
@@ -634,9 +915,11 @@ impl CallGraph {
                             .non_flushed_is_empty()
                         {
                             self.coderun_notifiable.borrow_mut().notify_repeat_count(
-                                siblings_call_depth,
+                                Self::node_call_depth(&previous_sibling.borrow(), siblings_call_depth),
                                 &previous_sibling.borrow().kind,
                                 previous_sibling.borrow().repeat_count.non_flushed(),
+                                #[cfg(feature = "call_timing")]
+                                previous_sibling.borrow().repeat_count.non_flushed_timing(),
                             );
                             previous_sibling.borrow_mut().repeat_count.mark_flushed();
                         }
@@ -646,14 +929,9 @@ impl CallGraph {
                         // Stop caching.
                         self.caching_info.clear();
 
-                        // TODO: Test thoroughly (a shallow test shows in Task Manager that the allocated memory doesn't grow).
                         // // Endless logging support:
-                        // if returning_sibling is pseudoroot's child
-                        //   Drop the previous sibling.
-                        if call_stack_len == 2 {
-                            // pseudoroot and returning_sibling.
-                            parent_or_pseudoroot.borrow_mut().children.remove(0); // Previous sibling is always at index 0 in pseudoroot.
-                        }
+                        // // if returning_sibling is pseudoroot's child then the previous sibling is dropped
+                        // // by `prune_pseudoroot_children()` below.
                     }
                     // else (caching has started at a parent level or above) do nothing, continue caching.
                 }
@@ -664,9 +942,15 @@ impl CallGraph {
         //      do nothing. Continue caching, if active. The caching end cannot be detected upon return from the only child.
 
         // Handle the return in the call graph:
-        self.call_stack.pop(); // [..., parent, returning_sibling] -> [..., parent].
+        self.pop_call(); // [..., parent, returning_sibling] -> [..., parent].
         self.current_node = parent_or_pseudoroot;
 
+        // Periodic sequence folding support:
+        self.update_sequence_caching();
+
+        // Endless logging support:
+        self.prune_pseudoroot_children();
+
         // // V Current
         // // If caching is not active {
         // //      Log the repeat count, if non-zero, of the last_child, if present.
@@ -885,11 +1169,9 @@ impl CallGraph {
         //          flush its non-flushed repeat count (even though all subsequent loopbodies can get removed for being childless
         //          and a repeated call can follow).
         //
-        //      // TODO: Test.
         //      // Endless logging support:
-        //      If    optional_previous_sibling is pseudoroot's child
-        //         && optional_previous_sibling is a {call or previous loops' loopbody}
-        //              Remove optional_previous_sibling
+        //      // The optional_previous_sibling, if pseudoroot's child, is retained until the new loopbody
+        //      // gets a (direct or indirect) call (see `prune_pseudoroot_children()`).
         //
         //      // Begin caching the new loopbody node (if it ends up being childless then it will be removed):
         //      If it is the initial loopbody
@@ -939,10 +1221,22 @@ impl CallGraph {
         // Add the new loopbody node to the call graph, make it current.
 
         // Implementation.
+        // Node budget support:
+        if self.folding_is_suspended_upon_new_node() {
+            self.add_loopbody_start_streaming();
+            return;
+        }
+        self.node_count += 1;
+
+        // Periodic sequence folding support:
+        self.stop_sequence_caching_unless_continued(Some(&ItemKind::Loopbody {
+            ends_the_loop: false,
+        }));
+
         // If there's a sibling-level node, then memorize the info for flushing its repeat count
         // ([function or closure name,] repeat count, call depth, etc.).
         let siblings_call_depth = self.call_depth(); // The parent's children call depth.
-        let mut optional_previous_sibling = self
+        let optional_previous_sibling = self
             .current_node // parent
             .borrow()
             .children
@@ -952,7 +1246,10 @@ impl CallGraph {
         // Create the loopbody node,
         let new_loopbody_node = Rc::new(RefCell::new(CallNode::new(ItemKind::Loopbody {
             ends_the_loop: false,
-        })));
+        },
+        #[cfg(feature = "call_timing")]
+        self.call_timing,
+        )));
 
         // // add it to the call graph (by adding to the parent's list of children),
         // self.current_node // parent
@@ -978,32 +1275,20 @@ impl CallGraph {
                         .non_flushed_is_empty()
                     {
                         self.coderun_notifiable.borrow_mut().notify_repeat_count(
-                            siblings_call_depth,
+                            Self::node_call_depth(&previous_sibling.borrow(), siblings_call_depth),
                             &previous_sibling.borrow().kind,
                             previous_sibling.borrow().repeat_count.non_flushed(),
+                            #[cfg(feature = "call_timing")]
+                            previous_sibling.borrow().repeat_count.non_flushed_timing(),
                         );
                         previous_sibling.borrow_mut().repeat_count.mark_flushed();
                     }
                 }
-                // TODO: Test.
                 // // Endless logging support:
-                // If    optional_previous_sibling is pseudoroot's child
-                //    && optional_previous_sibling is a {call or previous loops' loopbody}
-                //         Remove optional_previous_sibling
-                if self.call_stack.len() == 1 {
-                    // pseudoroot only
-                    let remove_previous_sibling = match previous_sibling.borrow().kind {
-                        ItemKind::Call { .. } => true,
-                        ItemKind::Loopbody { ends_the_loop } => ends_the_loop,
-                    };
-                    if remove_previous_sibling {
-                        self.current_node // pseudoroot
-                            .borrow_mut()
-                            .children
-                            .pop();
-                        optional_previous_sibling = None;
-                    }
-                }
+                // // The optional_previous_sibling, if pseudoroot's child, is not removed here
+                // // since the new loopbody can end up childless and get removed,
+                // // after which the optional_previous_sibling can be followed by a repeated call.
+                // // It is removed by `prune_pseudoroot_children()` as soon as the new loopbody gets a call.
             }
 
             // Begin caching the newly-added loopbody node:
@@ -1036,6 +1321,7 @@ impl CallGraph {
                 },
                 node_being_cached: Some(new_loopbody_node.clone()),
                 call_depth: siblings_call_depth,
+                sequence: None,
             };
         }
         // Otherwise (caching is active)
@@ -1051,6 +1337,9 @@ impl CallGraph {
 
         // make it current (the subsequent calls will be added as children to the new node).
         self.current_node = new_loopbody_node.clone();
+
+        // Endless logging support:
+        self.prune_pseudoroot_children();
     }
 
     /// Adds the loop body end to the call graph.
@@ -1160,6 +1449,15 @@ impl CallGraph {
 
         // Implementation.
 
+        // Node budget support:
+        if self.folding_suspended {
+            self.add_loopbody_end_streaming();
+            return;
+        }
+
+        // Periodic sequence folding support (the parent of the sequence being cached ends):
+        self.stop_sequence_caching_unless_continued(None);
+
         let ending_loopbody = self.current_node.clone();
         let children_call_depth = self.call_depth();
 
@@ -1175,14 +1473,22 @@ impl CallGraph {
 
         let ending_loopbody_call_depth = self.call_depth();
 
+        //  Mark the loop body as `ended`.  // The thread context switch can happen immediately before or after the (loop body's) end.
+        //                                  // Upon `flush()`,
+        //                                  //  * if the loop body is not marked as `ended`, then the loop body's end is not logged;
+        //                                  //  * if marked as `ended`, then is logged.
+        ending_loopbody.borrow_mut().end();
+
         // If the ending loop body has no children {
         if ending_loopbody.borrow().children.is_empty() {
             // If caching is inactive { // The childless loop body's start has been flushed.
             if !self.caching_is_active() {
                 // Log the loop body's end.
-                self.coderun_notifiable
-                    .borrow_mut()
-                    .notify_loopbody_end(ending_loopbody_call_depth);
+                self.coderun_notifiable.borrow_mut().notify_loopbody_end(
+                    ending_loopbody_call_depth,
+                    #[cfg(feature = "call_timing")]
+                    ending_loopbody.borrow().duration,
+                );
             }
             // } else if the loop body is the `node_being_cached` {
             else if let Some(node_being_cached) = &self.caching_info.node_being_cached
@@ -1195,6 +1501,7 @@ impl CallGraph {
 
             // Remove the ending childless loop body from the call graph (from the parent's list of children,
             parent_or_pseudoroot.borrow_mut().children.pop();
+            self.node_count -= 1;
 
             // pop from the call stack, redirect `current` to parent;  // Is already done in the beginning of the function.
             // it is not in cache (since caching is either inactive, or stopped, or started at parent or earlier)).
@@ -1211,25 +1518,23 @@ impl CallGraph {
                 && !last_child.borrow().repeat_count.non_flushed_is_empty()
             {
                 self.coderun_notifiable.borrow_mut().notify_repeat_count(
-                    children_call_depth,
+                    Self::node_call_depth(&last_child.borrow(), children_call_depth),
                     &last_child.borrow().kind,
                     last_child.borrow().repeat_count.non_flushed(),
+                    #[cfg(feature = "call_timing")]
+                    last_child.borrow().repeat_count.non_flushed_timing(),
                 );
                 last_child.borrow_mut().repeat_count.mark_flushed();
             }
             // Log the loop body end.
-            self.coderun_notifiable
-                .borrow_mut()
-                .notify_loopbody_end(ending_loopbody_call_depth);
+            self.coderun_notifiable.borrow_mut().notify_loopbody_end(
+                ending_loopbody_call_depth,
+                #[cfg(feature = "call_timing")]
+                ending_loopbody.borrow().duration,
+            );
         }
         // Otherwise nothing, go on.
 
-        //  Mark the loop body as `ended`.  // The thread context switch can happen immediately before or after the (loop body's) end.
-        //                                  // Upon `flush()`,
-        //                                  //  * if the loop body is not marked as `ended`, then the loop body's end is not logged;
-        //                                  //  * if marked as `ended`, then is logged.
-        ending_loopbody.borrow_mut().has_ended = true;
-
         // // If there is a (current loop's) previous iteration (with optionally non-flushed repeat count)
         // If the previous sibling exists...
         let sibling_count = parent_or_pseudoroot.borrow().children.len();
@@ -1247,10 +1552,14 @@ impl CallGraph {
                 if Self::trees_are_equal(&ending_loopbody, &previous_sibling, false) {
                     // Remove the ending loop body from the call graph (now from the parent's list of children,
                     parent_or_pseudoroot.borrow_mut().children.pop();
+                    self.node_count -= Self::subtree_node_count(&ending_loopbody);
                     // later - from the call stack, current) - already done in the beginning of the function.
 
                     // Increment the repeat count of the previous iteration's loop body.
-                    previous_sibling.borrow_mut().repeat_count.inc();
+                    previous_sibling.borrow_mut().repeat_count.inc(
+                        #[cfg(feature = "call_timing")]
+                        ending_loopbody.borrow().duration,
+                    );
 
                     // If caching is inactive (the ending loop body is already logged separately above, but it has incremented the repeat count)
                     if !self.caching_is_active() {
@@ -1295,36 +1604,55 @@ impl CallGraph {
                                     ending_loopbody_call_depth, // Same call depth for the returning and previous siblings.
                                     &previous_sibling.borrow().kind,
                                     previous_sibling.borrow().repeat_count.non_flushed(),
+                                    #[cfg(feature = "call_timing")]
+                                    previous_sibling.borrow().repeat_count.non_flushed_timing(),
                                 );
                                 previous_sibling.borrow_mut().repeat_count.mark_flushed();
                             }
-                            // Log the subtree of the ending loop body.
-                            self.flush_tree(&ending_loopbody, ending_loopbody_call_depth);
 
-                            // Stop caching.
-                            self.caching_info.clear();
-
-                            // TODO: Test.
-                            // Endless logging support:
-                            // if a child of pseudoroot
-                            if self.call_stack.len() == 1 {
-                                // pseudoroot
-                                // Remove the previous_sibling from the call tree.
-                                parent_or_pseudoroot.borrow_mut().children.remove(0);
+                            // Periodic sequence folding support:
+                            // If the ending loop body starts repeating a sequence of the previous iterations
+                            //      (e.g. iterations `{a()} {b()} {a()}`)
+                            //      begin caching the sequence starting with the ending loop body,
+                            // otherwise {
+                            //      Log the subtree of the ending loop body.
+                            //      Stop caching.
+                            // }
+                            let optional_model_start = if previous_sibling.borrow().followed_by_flush {
+                                None // After the flush log the loop body in full.
+                            } else {
+                                self.find_sequence_model_start(
+                                    &parent_or_pseudoroot.borrow().children,
+                                    true,
+                                )
+                            };
+                            if let Some((model_start, len)) = optional_model_start {
+                                self.caching_info = CachingInfo {
+                                    model_node: None,
+                                    node_being_cached: None,
+                                    call_depth: ending_loopbody_call_depth,
+                                    sequence: Some(SequenceCachingInfo {
+                                        parent: parent_or_pseudoroot.clone(),
+                                        model_start,
+                                        len,
+                                        repeat_count: RepeatCount::new(),
+                                    }),
+                                };
+                            } else {
+                                // Log the subtree of the ending loop body.
+                                self.flush_tree(&ending_loopbody, ending_loopbody_call_depth);
+
+                                // Stop caching.
+                                self.caching_info.clear();
                             }
                         }
                         // else (caching has started at a parent level or above) Do nothing, continue caching.
-                    } else {
-                        // else (caching is inactive)
-                        // TODO: Test.
-                        // Endless logging support:
-                        // if a child of pseudoroot
-                        if self.call_stack.len() == 1 {
-                            // pseudoroot
-                            // Remove the previous_sibling from the call tree.
-                            parent_or_pseudoroot.borrow_mut().children.remove(0);
-                        }
                     }
+                    // else (caching is inactive) do nothing.
+
+                    // // Endless logging support:
+                    // // if a child of pseudoroot then the previous_sibling is removed from the call tree
+                    // // by `prune_pseudoroot_children()` below.
 
                     // {previous_sibling // if still alive after Endless logging support}.followed_by_flush doesn't matter any more. // stays as is.
                     // ending_loop_body.followed_by_flush is `false` by default. The ending_loop_body becomes the latest sibling.
@@ -1334,9 +1662,15 @@ impl CallGraph {
         // Otherwise // No previous sibling || it is a call || ends the (previous) loop. // No previous iteration of the current loop.
         //      Do nothing. The ending (initial) loop body is already logged (if caching hasn't started at parent or above)
         //      since it has a child, and stays in the call graph (in the parent's list of children).
-        //      The Endless logging support has been taken care of during the current loopbody start.
+        //      The Endless logging support has been taken care of upon the first call in the current loopbody.
 
         // Pop (the ending loop body) from the call stack, redirect `current` to parent: Is already done in the beginning of the function.
+
+        // Periodic sequence folding support:
+        self.update_sequence_caching();
+
+        // Endless logging support:
+        self.prune_pseudoroot_children();
     }
 
     /// Updates the call graph upon loop end.
@@ -1397,6 +1731,8 @@ impl CallGraph {
                         self.call_depth(), // The parent's children call depth.
                         &last_child_kind,
                         last_child_borrow_mut.repeat_count.non_flushed(),
+                        #[cfg(feature = "call_timing")]
+                        last_child_borrow_mut.repeat_count.non_flushed_timing(),
                     );
                     last_child_borrow_mut.repeat_count.mark_flushed();
                 }
@@ -1406,6 +1742,541 @@ impl CallGraph {
         //  Do nothing.
     }
 
+    /// Endless logging support.
+    ///
+    /// Removes from the pseudoroot's list of children the subtrees that can no longer participate
+    /// in the subtree comparison (`trees_are_equal()`), such that the pseudoroot retains at most
+    /// 2 latest children no matter how long the logging lasts:
+    /// * the latest child (the subsequent sibling, if any, can be compared to it),
+    /// * and its previous sibling, only if the latest child is still running and
+    ///   * either can be compared to the previous sibling upon return/end
+    ///     (a repeated call with the same name or a non-initial iteration of the same loop),
+    ///   * or can still end up childless and get removed (a loop body with no nested calls yet),
+    ///     after which the previous sibling becomes the latest one again.
+    ///
+    /// The pseudoroot itself never returns, thus its list of children never participates in the comparison.
+    /// For the same reason the caching model node, if among the pseudoroot's children, is always retained.
+    ///
+    /// If the periodic sequence folding is enabled, then the pseudoroot retains (instead of 2) up to
+    /// `max_sequence_len + 1` latest children (the latest child can start repeating a sequence of the previous ones).
+    /// While its children are being cached as a repeated sequence, the pseudoroot retains the model sequence,
+    /// the (at most 1) repeat being cached, and up to `max_sequence_len` children preceding the model sequence
+    /// (those can be a part of a sequence found after the sequence caching stops).
+    fn prune_pseudoroot_children(&mut self) {
+        let pseudoroot = self.call_stack[0].clone(); // The pseudoroot is always at the bottom of the call stack.
+        if let Some(sequence) = &mut self.caching_info.sequence
+            && sequence.parent.as_ptr() == pseudoroot.as_ptr()
+        {
+            // The model sequence and its repeat are retained until the sequence caching stops.
+            let pruned_count = sequence.model_start.saturating_sub(self.max_sequence_len);
+            sequence.model_start -= pruned_count;
+            for pruned_child in pseudoroot.borrow_mut().children.drain(..pruned_count) {
+                self.node_count -= Self::subtree_node_count(&pruned_child);
+            }
+            return;
+        }
+        let mut pseudoroot = pseudoroot.borrow_mut();
+        let child_count = pseudoroot.children.len();
+        if child_count < 2 {
+            return;
+        }
+        let retain_previous_sibling = {
+            let latest_child = pseudoroot.children[child_count - 1].borrow();
+            let previous_sibling = pseudoroot.children[child_count - 2].borrow();
+            !latest_child.has_ended
+                && (Self::kinds_are_comparable(&previous_sibling.kind, &latest_child.kind)
+                    || Self::may_end_up_childless(&latest_child))
+        };
+        let mut retained_count = if retain_previous_sibling { 2 } else { 1 };
+        // Periodic sequence folding support:
+        if self.max_sequence_len >= 2 {
+            retained_count = retained_count.max(self.max_sequence_len + 1).min(child_count);
+        }
+        for pruned_child in pseudoroot.children.drain(..child_count - retained_count) {
+            self.node_count -= Self::subtree_node_count(&pruned_child);
+        }
+    }
+
+    /// Returns the number of nodes in the subtree rooted at the argument (including the root).
+    fn subtree_node_count(node: &Link) -> usize {
+        1 + node
+            .borrow()
+            .children
+            .iter()
+            .map(Self::subtree_node_count)
+            .sum::<usize>()
+    }
+
+    /// Returns `true` if the subtree of a sibling of kind `latest` can be compared
+    /// to the subtree of the preceding sibling of kind `previous`, i.e. if
+    /// * both are calls with the same name,
+    /// * or both are loop bodies of the same loop (`previous` doesn't end the loop).
+    fn kinds_are_comparable(previous: &ItemKind, latest: &ItemKind) -> bool {
+        match (previous, latest) {
+            (ItemKind::Call { name: previous_name, .. }, ItemKind::Call { name: latest_name, .. }) => {
+                previous_name == latest_name
+            }
+            (ItemKind::Loopbody { ends_the_loop }, ItemKind::Loopbody { .. }) => !ends_the_loop,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the node is a running loop body that has no (direct or indirect) nested calls yet,
+    /// i.e. can still end up childless and get removed from the call tree.
+    fn may_end_up_childless(node: &CallNode) -> bool {
+        node.kind.is_loopbody()
+            && !node.has_ended
+            && node
+                .children
+                .iter()
+                .all(|child| Self::may_end_up_childless(&child.borrow()))
+    }
+
+    /// Periodic sequence folding support.
+    ///
+    /// Looks for a model sequence repeated by the latest of the `children` (the first sibling of a potential sequence repeat).
+    /// I.e. looks for the shortest sequence of `len` siblings (`2 <= len <= max_sequence_len`) immediately preceding
+    /// the latest child, such that the sequence's first sibling
+    /// * is similar to the latest child (e.g. a call with the same name)
+    ///   if `compare_subtrees` is `false` (the latest child has just been called),
+    /// * or is a loop body of the same loop with the subtree equal to that of the latest child
+    ///   if `compare_subtrees` is `true` (the latest child is an ending loop body).
+    ///
+    /// Returns the model sequence's start index in the list of children and the model sequence length, if found.
+    // ```ignore
+    // a() {}      // `children[model_start]`.
+    // b() {}      // `len` is 2.
+    // a() {       // The latest child.
+    // ```
+    fn find_sequence_model_start(
+        &self,
+        children: &[Link],
+        compare_subtrees: bool,
+    ) -> Option<(usize, usize)> {
+        let child_count = children.len();
+        let latest_child = children.last()?;
+        for len in 2..=self.max_sequence_len {
+            if child_count < len + 1 {
+                break;
+            }
+            let model_start = child_count - 1 - len;
+            let model_sequence = &children[model_start..child_count - 1];
+            let found = if compare_subtrees {
+                // All the siblings of the model sequence are the loop bodies of the current loop
+                model_sequence.iter().all(|sibling| {
+                    matches!(
+                        sibling.borrow().kind,
+                        ItemKind::Loopbody {
+                            ends_the_loop: false
+                        }
+                    )
+                })
+                // and the first one is equal to the latest child.
+                && Self::trees_are_equal(&model_sequence[0], latest_child, false)
+            } else {
+                Self::kinds_are_similar(&model_sequence[0].borrow().kind, &latest_child.borrow().kind)
+            };
+            if found {
+                return Some((model_start, len));
+            }
+        }
+        None
+    }
+
+    /// Returns `true` if the items of the two kinds can have equal subtrees, i.e. if
+    /// * both are calls with the same name,
+    /// * or both are loop bodies.
+    fn kinds_are_similar(a: &ItemKind, b: &ItemKind) -> bool {
+        match (a, b) {
+            (ItemKind::Call { name: a_name, .. }, ItemKind::Call { name: b_name, .. }) => a_name == b_name,
+            (ItemKind::Loopbody { .. }, ItemKind::Loopbody { .. }) => true,
+            _ => false,
+        }
+    }
+
+    /// Periodic sequence folding support.
+    ///
+    /// Is called before adding to the current node
+    /// * a child (a call or loop body) of the specified kind (`Some`),
+    /// * or the current node's return or end (`None`).
+    ///
+    /// If the current node is the parent of the sequence being cached, then stops the sequence caching
+    /// unless the new child can continue the sequence repeat (is similar to the corresponding sibling
+    /// of the model sequence or can be a repeat of the latest sibling).
+    fn stop_sequence_caching_unless_continued(&mut self, new_sibling_kind: Option<&ItemKind>) {
+        let Some(sequence) = &self.caching_info.sequence else {
+            return;
+        };
+        if sequence.parent.as_ptr() != self.current_node.as_ptr() {
+            return; // The new item is not the sibling of the sequence being cached.
+        }
+        if let Some(new_sibling_kind) = new_sibling_kind {
+            let parent = sequence.parent.borrow();
+            let repeat_start = sequence.model_start + sequence.len;
+            let repeat_len = parent.children.len() - repeat_start;
+            let next_model_sibling = &parent.children[sequence.model_start + repeat_len % sequence.len];
+            let continues = Self::kinds_are_similar(&next_model_sibling.borrow().kind, new_sibling_kind)
+                || parent.children.last().is_some_and(|latest_sibling| {
+                    Self::kinds_are_comparable(&latest_sibling.borrow().kind, new_sibling_kind)
+                });
+            if continues {
+                return;
+            }
+        }
+        self.stop_sequence_caching(false);
+    }
+
+    /// Periodic sequence folding support.
+    ///
+    /// Is called after a child of the current node has returned or ended. If the current node is
+    /// the parent of the sequence being cached, then compares the latest siblings to the corresponding siblings
+    /// of the model sequence:
+    /// * if the latest but one sibling (that can no longer change) differs from its model sibling
+    ///   (by subtree or repeat count), or the latest sibling differs (by subtree), then stops the sequence caching,
+    /// * otherwise, if the whole model sequence is repeated, then increments the sequence's repeat count
+    ///   and removes the repeated siblings from the call graph.
+    fn update_sequence_caching(&mut self) {
+        let Some(sequence) = &mut self.caching_info.sequence else {
+            return;
+        };
+        if sequence.parent.as_ptr() != self.current_node.as_ptr() {
+            return; // The returned or ended child is not the sibling of the sequence being cached.
+        }
+        let parent = sequence.parent.clone();
+        let model_start = sequence.model_start;
+        let repeat_start = model_start + sequence.len;
+        let mut repeat_len = parent.borrow().children.len() - repeat_start;
+
+        // If there is the latest but one sibling in the repeat
+        // (it is final, i.e. the latest sibling has not been folded into it)
+        if repeat_len >= 2 {
+            // then compare it, including the repeat count, to its model sibling.
+            let siblings_are_equal = {
+                let children = &parent.borrow().children;
+                Self::trees_are_equal(
+                    &children[model_start + repeat_len - 2],
+                    &children[repeat_start + repeat_len - 2],
+                    true,
+                )
+            };
+            if !siblings_are_equal {
+                self.stop_sequence_caching(false);
+                return;
+            }
+            // If the whole model sequence is repeated, then fold the repeat.
+            if repeat_len - 1 == sequence.len {
+                self.node_count -= Self::fold_sequence_repeat(sequence);
+                repeat_len = 1;
+            }
+        }
+
+        // Compare the latest sibling (whose repeat count can still change) to its model sibling.
+        let siblings_are_equal = {
+            let children = &parent.borrow().children;
+            Self::trees_are_equal(
+                &children[model_start + repeat_len - 1],
+                &children[repeat_start + repeat_len - 1],
+                false,
+            )
+        };
+        if !siblings_are_equal {
+            self.stop_sequence_caching(false);
+        }
+    }
+
+    /// Periodic sequence folding support.
+    ///
+    /// Removes from the call graph the first repeat of the model sequence (the siblings immediately following
+    /// the model sequence), increments the sequence's repeat count.
+    /// Returns the number of the removed nodes.
+    fn fold_sequence_repeat(sequence: &mut SequenceCachingInfo) -> usize {
+        let mut parent = sequence.parent.borrow_mut();
+        let repeat_start = sequence.model_start + sequence.len;
+        let repeat: Vec<Link> = parent
+            .children
+            .drain(repeat_start..repeat_start + sequence.len)
+            .collect();
+
+        // If the repeat's last loop body ends the loop then so does the model's last loop body.
+        if let Some(repeat_last) = repeat.last()
+            && let ItemKind::Loopbody { ends_the_loop } = repeat_last.borrow().kind
+            && let ItemKind::Loopbody {
+                ends_the_loop: model_ends_the_loop,
+            } = &mut parent.children[repeat_start - 1].borrow_mut().kind
+        {
+            *model_ends_the_loop = ends_the_loop;
+        }
+
+        sequence.repeat_count.inc(
+            #[cfg(feature = "call_timing")]
+            None, // The sequence repeats are not timed.
+        );
+        repeat.iter().map(Self::subtree_node_count).sum()
+    }
+
+    /// Periodic sequence folding support.
+    ///
+    /// Stops the sequence caching:
+    /// * folds the sequence repeat, if the siblings being cached repeat the whole model sequence,
+    /// * logs the sequence's repeat count, if non-zero,
+    /// * logs the subtrees of the siblings being cached,
+    /// * if `mark_as_followed_by_flush` is `true` (upon flush), marks the latest sibling
+    ///   (or its latest ended descendant) as `followed_by_flush = true`.
+    fn stop_sequence_caching(&mut self, mark_as_followed_by_flush: bool) {
+        let Some(mut sequence) = self.caching_info.sequence.take() else {
+            return;
+        };
+        let parent = sequence.parent.clone();
+        let call_depth = self.caching_info.call_depth;
+        let model_start = sequence.model_start;
+        let repeat_start = model_start + sequence.len;
+
+        // If the siblings being cached repeat the whole model sequence, then fold the repeat.
+        let whole_sequence_is_repeated = {
+            let children = &parent.borrow().children;
+            children.len() - repeat_start == sequence.len && {
+                let (model_last, repeat_last) = (&children[repeat_start - 1], &children[children.len() - 1]);
+                repeat_last.borrow().has_ended && Self::trees_are_equal(model_last, repeat_last, true)
+            }
+        };
+        if whole_sequence_is_repeated {
+            self.node_count -= Self::fold_sequence_repeat(&mut sequence);
+        }
+
+        // Log the sequence's repeat count, if non-zero.
+        if !sequence.repeat_count.non_flushed_is_empty() {
+            let kinds: Vec<ItemKind> = parent.borrow().children[model_start..repeat_start]
+                .iter()
+                .map(|sibling| sibling.borrow().kind.clone())
+                .collect();
+            self.coderun_notifiable
+                .borrow_mut()
+                .notify_sequence_repeat_count(call_depth, &kinds, sequence.repeat_count.non_flushed());
+        }
+
+        // Log the subtrees of the siblings being cached.
+        let repeat: Vec<Link> = parent.borrow().children[repeat_start..].to_vec();
+        for sibling in &repeat {
+            self.flush_tree(sibling, call_depth);
+        }
+
+        if mark_as_followed_by_flush
+            && let Some(latest_sibling) = parent.borrow().children.last()
+        {
+            Self::mark_as_followed_by_flush(latest_sibling.clone());
+        }
+
+        self.caching_info.clear();
+    }
+
+    /// Node budget support.
+    ///
+    /// Returns `true` if the repeat folding is suspended, suspending it first if the new node
+    /// (call or loop body) being added would exceed the node budget.
+    fn folding_is_suspended_upon_new_node(&mut self) -> bool {
+        if !self.folding_suspended
+            && let Some(node_budget) = self.node_budget
+            && self.node_count >= node_budget
+        {
+            self.suspend_folding();
+        }
+        self.folding_suspended
+    }
+
+    /// Node budget support.
+    ///
+    /// Suspends the repeat folding (for the rest of the thread's life):
+    /// * flushes the cached call-graph data (and non-flushed repeat counts),
+    /// * notifies about the suspension,
+    /// * releases the nodes that are no longer needed for plain streaming, i.e. retains
+    ///   only the nodes on the call stack and the latest child of each of them
+    ///   (the latest child tells upon return whether the call has nested calls).
+    ///
+    /// Since then the subsequent calls, returns, loop body starts and ends are handled by the `add_*_streaming()`
+    /// functions that log them as they come, without caching for repeat detection.
+    // Log State:
+    // parent {
+    //     ...
+    //     [// f() repeats 99 time(s).]    // Flushed.
+    //     [{ // Loop body start.]          // The cached initial loop body, flushed.
+    //     // FCL: repeat folding suspended (node budget reached)
+    //     new_sibling() {                  // The call (or loop body) whose addition has exceeded the budget.
+    fn suspend_folding(&mut self) {
+        // Flush what has been cached so far (without flushing the notifiable/decorator,
+        // the suspension notification takes care of the pending line end, if any).
+        self.flush(false);
+
+        self.coderun_notifiable
+            .borrow_mut()
+            .notify_folding_suspended(self.call_depth());
+
+        // Release the nodes:
+        for node in &self.call_stack {
+            let mut node = node.borrow_mut();
+            let child_count = node.children.len();
+            if child_count > 1 {
+                node.children.drain(..child_count - 1);
+            }
+        }
+        // The current node's latest child, if any, has ended. Its descendants are not needed any more.
+        if let Some(latest_child) = self.current_node.borrow().children.last() {
+            latest_child.borrow_mut().children.clear();
+        }
+
+        self.node_count = 0; // Is not tracked any more.
+        self.folding_suspended = true;
+    }
+
+    /// Node budget support. Adds a call to the call graph and logs it without caching for repeat detection.
+    /// The previous sibling, if any, is released.
+    fn add_call_streaming(
+        &mut self,
+        call_name: &str,
+        #[cfg(feature = "params_logging")] param_vals: Option<String>,
+    ) {
+        let new_sibling = self.new_call_node(
+            call_name,
+            #[cfg(feature = "params_logging")]
+            param_vals,
+        );
+        {
+            let mut parent = self.current_node.borrow_mut();
+            parent.children.clear(); // Release the previous sibling.
+            parent.children.push(new_sibling.clone());
+        }
+
+        // If the enclosing initial loop body (with optional intermediate enclosing initial loop bodies)
+        // is being cached
+        if self.caching_is_active() {
+            // then flush it (along with the new_sibling's call) and stop caching,
+            self.flush(false);
+        } else {
+            // otherwise log the call.
+            self.notify_call_of(&new_sibling.borrow(), self.call_depth());
+        }
+
+        self.push_call(new_sibling.clone()); // [..., parent] -> [..., parent, new_sibling]
+        self.current_node = new_sibling;
+    }
+
+    /// Node budget support. Adds a return to the call graph and logs it without comparing to the previous sibling.
+    /// The nested calls of the returning function are released.
+    fn add_ret_streaming(&mut self, #[cfg(feature = "ret_val_logging")] ret_val: Option<String>) {
+        let returning_sibling = self.current_node.clone();
+        let children_call_depth = self.call_depth();
+        {
+            let mut returning_sibling = returning_sibling.borrow_mut();
+            #[cfg(feature = "ret_val_logging")]
+            returning_sibling.set_ret_val(ret_val);
+            returning_sibling.end();
+
+            // Caching is inactive (the calls stop the initial loop body caching).
+            if let ItemKind::Call { name, .. } = &returning_sibling.kind {
+                let has_nested_calls = !returning_sibling.children.is_empty();
+                self.coderun_notifiable.borrow_mut().notify_return(
+                    children_call_depth - 1, // `- 1`: The returning_sibling is still on the call stack.
+                    name,
+                    has_nested_calls,
+                    #[cfg(feature = "ret_val_logging")]
+                    returning_sibling.get_ret_val(),
+                    #[cfg(feature = "call_timing")]
+                    returning_sibling.duration,
+                );
+            } else {
+                debug_assert!(
+                    false,
+                    "FCL Internal Error: `add_ret()` is called for a loop body node"
+                )
+            }
+            returning_sibling.children.clear(); // Release the nested calls.
+        }
+
+        self.pop_call(); // [..., parent, returning_sibling] -> [..., parent].
+        self.current_node = match self.call_stack.last() {
+            None => panic!("FCL Internal Error: Unexpected bottom of the call stack"),
+            Some(parent_or_pseudoroot) => parent_or_pseudoroot.clone(),
+        };
+    }
+
+    /// Node budget support. Adds a loop body start to the call graph.
+    /// The previous sibling, if any, is released.
+    ///
+    /// The initial loop body is still cached (until the first nested call, loop body end, or flush),
+    /// such that the childless loop bodies are not logged. The caching of the initial loop body
+    /// doesn't make the call graph grow, since the nested loop bodies are also initial.
+    fn add_loopbody_start_streaming(&mut self) {
+        let new_loopbody_node = Rc::new(RefCell::new(CallNode::new(ItemKind::Loopbody {
+            ends_the_loop: false,
+        },
+        #[cfg(feature = "call_timing")]
+        self.call_timing,
+        )));
+        {
+            let mut parent = self.current_node.borrow_mut();
+            parent.children.clear(); // Release the previous sibling.
+            parent.children.push(new_loopbody_node.clone());
+        }
+
+        // If caching is inactive, begin caching the new loop body as the initial one.
+        // Otherwise (an enclosing initial loop body is being cached) continue caching.
+        if !self.caching_is_active() {
+            self.caching_info = CachingInfo {
+                model_node: None,
+                node_being_cached: Some(new_loopbody_node.clone()),
+                call_depth: self.call_depth(),
+                sequence: None,
+            };
+        }
+
+        self.call_stack.push(new_loopbody_node.clone()); // [..., parent] -> [..., parent, new_loopbody_node]
+        self.current_node = new_loopbody_node;
+    }
+
+    /// Node budget support. Adds a loop body end to the call graph and logs it, if the loop body has been logged,
+    /// without comparing to the previous iteration. The childless loop body is removed.
+    fn add_loopbody_end_streaming(&mut self) {
+        let ending_loopbody = self.current_node.clone();
+
+        self.call_stack.pop(); // [.., parent_or_pseudoroot, ending_loopbody] -> [.., parent_or_pseudoroot].
+        let parent_or_pseudoroot = match self.call_stack.last() {
+            None => panic!("FCL Internal Error: Unexpected bottom of the call stack"),
+            Some(parent_or_pseudoroot) => parent_or_pseudoroot.clone(),
+        };
+        self.current_node = parent_or_pseudoroot.clone();
+        let ending_loopbody_call_depth = self.call_depth();
+        ending_loopbody.borrow_mut().end();
+
+        if ending_loopbody.borrow().children.is_empty() {
+            // If caching is inactive (the childless loop body's start has been flushed)
+            if !self.caching_is_active() {
+                // Log the loop body's end.
+                self.coderun_notifiable.borrow_mut().notify_loopbody_end(
+                    ending_loopbody_call_depth,
+                    #[cfg(feature = "call_timing")]
+                    ending_loopbody.borrow().duration,
+                );
+            } else if let Some(node_being_cached) = &self.caching_info.node_being_cached
+                && node_being_cached.as_ptr() == ending_loopbody.as_ptr()
+            {
+                // Stop caching.
+                self.caching_info.clear();
+            }
+            // else (caching started at an enclosing loop body) Do nothing, continue caching.
+
+            // Remove the ending childless loop body from the call graph.
+            parent_or_pseudoroot.borrow_mut().children.pop();
+            return;
+        }
+
+        // Has child(ren), has been logged (the first nested call has stopped caching).
+        self.coderun_notifiable.borrow_mut().notify_loopbody_end(
+            ending_loopbody_call_depth,
+            #[cfg(feature = "call_timing")]
+            ending_loopbody.borrow().duration,
+        );
+        ending_loopbody.borrow_mut().children.clear(); // Release the nested calls.
+    }
+
     /// Marks the specified node as `followed_by_flush = true` if it has ended,
     /// otherwise marks its latest descendant, if it has ended.
     fn mark_as_followed_by_flush(node: Link) {
@@ -1440,7 +2311,12 @@ impl CallGraph {
         // * the caching model_node - the previous sibling node (for non-loopbody caching case) -
         //   can have a non-flushed repeat count
         // * and the subsequent (current) sibling (with its children) is being added to the call graph (is being cached).
-        if let Some(caching_model_node) = self.caching_info.model_node.as_ref() {
+        if self.caching_info.sequence.is_some() {
+            // A repeated sequence of siblings is being cached.
+            // Log the sequence's repeat count, if non-zero, and the siblings being cached,
+            // mark the latest one (or its latest ended descendant) as `followed_by_flush = true`.
+            self.stop_sequence_caching(true);
+        } else if let Some(caching_model_node) = self.caching_info.model_node.as_ref() {
             // Log the caching_model_node's repeat count, if non-flushed,
             // Log the subtree of the node bing cached.
             // If the node bing cached has ended, then
@@ -1456,9 +2332,11 @@ impl CallGraph {
             {
                 // then flush the repeat count.
                 self.coderun_notifiable.borrow_mut().notify_repeat_count(
-                    self.caching_info.call_depth,
+                    Self::node_call_depth(&caching_model_node.borrow(), self.caching_info.call_depth),
                     &caching_model_node.borrow().kind, //name,
                     caching_model_node.borrow().repeat_count.non_flushed(),
+                    #[cfg(feature = "call_timing")]
+                    caching_model_node.borrow().repeat_count.non_flushed_timing(),
                 );
                 caching_model_node.borrow_mut().repeat_count.mark_flushed();
             }
@@ -1482,9 +2360,11 @@ impl CallGraph {
             if let Some(latest_sibling) = self.current_node.borrow().children.last() {
                 if !latest_sibling.borrow().repeat_count.non_flushed_is_empty() {
                     self.coderun_notifiable.borrow_mut().notify_repeat_count(
-                        self.caching_info.call_depth,
+                        Self::node_call_depth(&latest_sibling.borrow(), self.caching_info.call_depth),
                         &latest_sibling.borrow().kind,
                         latest_sibling.borrow().repeat_count.non_flushed(),
+                        #[cfg(feature = "call_timing")]
+                        latest_sibling.borrow().repeat_count.non_flushed_timing(),
                     );
                     latest_sibling.borrow_mut().repeat_count.mark_flushed();
                 }
@@ -1513,13 +2393,110 @@ impl CallGraph {
 
     /// Returns the call depth for children of the current node.
     /// `0` when only the pseudoroot is on the call stack.
+    /// The recursion-folded calls on the call stack do not increase the call depth.
     //
     // TODO: Consider call_depth -> children_call_depth or
     // current_children_call_depth (the call depth should be calculated either for a specified node or be "current").
     pub fn call_depth(&self) -> usize {
         let call_depth = self.call_stack.len();
         debug_assert!(call_depth >= 1); // At least pseudoroot.
-        call_depth - 1
+        debug_assert!(call_depth > self.folded_call_count);
+        call_depth - 1 - self.folded_call_count
+    }
+
+    /// Recursion folding support. Returns the call depth of a node, given the call depth of its siblings
+    /// that are not recursion-folded (a recursion-folded call is logged at the call depth of its parent).
+    fn node_call_depth(node: &CallNode, siblings_call_depth: usize) -> usize {
+        siblings_call_depth - node.recursion_folded as usize
+    }
+
+    /// Recursion folding support. Creates a call node (to be added as a child of the current node)
+    /// with the recursion depth deduced from the current node.
+    fn new_call_node(
+        &self,
+        call_name: &str,
+        #[cfg(feature = "params_logging")] param_vals: Option<String>,
+    ) -> Link {
+        let mut new_node = CallNode::new(ItemKind::Call {
+            name: String::from(call_name),
+            #[cfg(feature = "params_logging")]
+            param_vals,
+        },
+        #[cfg(feature = "call_timing")]
+        self.call_timing,
+        );
+        // If the parent is a call of the same function (the function calls itself directly)
+        // then the recursion goes one level deeper.
+        let parent = self.current_node.borrow();
+        if let ItemKind::Call { name, .. } = &parent.kind
+            && name == call_name
+        {
+            new_node.recursion_depth = parent.recursion_depth + 1;
+            new_node.recursion_folded = self
+                .recursion_threshold
+                .is_some_and(|recursion_threshold| new_node.recursion_depth > recursion_threshold);
+        }
+        Rc::new(RefCell::new(new_node))
+    }
+
+    /// Recursion folding support. Logs the call of a node, given the call depth of its siblings
+    /// that are not recursion-folded.
+    fn notify_call_of(&self, node: &CallNode, siblings_call_depth: usize) {
+        if let ItemKind::Call {
+            name,
+            #[cfg(feature = "params_logging")]
+            param_vals,
+        } = &node.kind
+        {
+            if node.recursion_folded {
+                self.coderun_notifiable.borrow_mut().notify_recursive_call(
+                    Self::node_call_depth(node, siblings_call_depth),
+                    name,
+                    #[cfg(feature = "params_logging")]
+                    param_vals,
+                    node.recursion_depth,
+                    #[cfg(feature = "call_timing")]
+                    node.start,
+                );
+            } else {
+                self.coderun_notifiable.borrow_mut().notify_call(
+                    siblings_call_depth,
+                    name,
+                    #[cfg(feature = "params_logging")]
+                    param_vals,
+                    #[cfg(feature = "call_timing")]
+                    node.start,
+                );
+            }
+        }
+    }
+
+    /// Pushes a call node to the call stack, keeping track of the recursion-folded calls.
+    fn push_call(&mut self, call_node: Link) {
+        if call_node.borrow().recursion_folded {
+            self.folded_call_count += 1;
+        }
+        self.call_stack.push(call_node);
+    }
+
+    /// Pops a call node from the call stack, keeping track of the recursion-folded calls.
+    fn pop_call(&mut self) {
+        if let Some(call_node) = self.call_stack.pop()
+            && call_node.borrow().recursion_folded
+        {
+            self.folded_call_count -= 1;
+        }
+    }
+
+    /// Returns the number of nodes currently in the call graph (the pseudoroot is not counted).
+    pub fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    /// Returns the number of the pseudoroot's children currently in the call graph
+    /// (the calls and loop bodies made with logging off in the long-living functions, see "Logging Endlessly").
+    pub fn pseudoroot_child_count(&self) -> usize {
+        self.call_stack[0].borrow().children.len() // The pseudoroot is always at the bottom of the call stack.
     }
 
     /// Returns `true` if caching is active.
@@ -1571,28 +2548,20 @@ impl CallGraph {
     }
 
     /// Logs the subtree rooted at an argument by calling the notifier callbacks.
-    fn flush_tree(&mut self, current_node: &Link, call_depth: usize) {
+    /// The call depth is the one of the root's siblings that are not recursion-folded.
+    fn flush_tree(&mut self, current_node: &Link, siblings_call_depth: usize) {
         let mut current_node = current_node.borrow_mut();
+        let call_depth = Self::node_call_depth(&current_node, siblings_call_depth);
         let item_children = &current_node.children;
 
         // Log `.. {`:
         match &current_node.kind {
-            ItemKind::Call {
-                name,
-                #[cfg(feature = "params_logging")]
-                param_vals,
-            } => {
-                self.coderun_notifiable.borrow_mut().notify_call(
-                    call_depth,
-                    name,
-                    #[cfg(feature = "params_logging")]
-                    param_vals,
-                );
-            }
-            ItemKind::Loopbody { .. } => self
-                .coderun_notifiable
-                .borrow_mut()
-                .notify_loopbody_start(call_depth),
+            ItemKind::Call { .. } => self.notify_call_of(&current_node, siblings_call_depth),
+            ItemKind::Loopbody { .. } => self.coderun_notifiable.borrow_mut().notify_loopbody_start(
+                call_depth,
+                #[cfg(feature = "call_timing")]
+                current_node.start,
+            ),
         }
 
         // Log the descendants recursively:
@@ -1613,12 +2582,18 @@ impl CallGraph {
                         has_nested_calls,
                         #[cfg(feature = "ret_val_logging")]
                         current_node.get_ret_val(),
+                        #[cfg(feature = "call_timing")]
+                        current_node.duration,
                     );
                 }
                 ItemKind::Loopbody { .. } => self
                     .coderun_notifiable
                     .borrow_mut()
-                    .notify_loopbody_end(call_depth),
+                    .notify_loopbody_end(
+                        call_depth,
+                        #[cfg(feature = "call_timing")]
+                        current_node.duration,
+                    ),
             }
 
             // The repeat count:
@@ -1627,6 +2602,8 @@ impl CallGraph {
                     call_depth,
                     &current_node.kind, //name,
                     current_node.repeat_count.non_flushed(),
+                    #[cfg(feature = "call_timing")]
+                    current_node.repeat_count.non_flushed_timing(),
                 );
                 current_node.repeat_count.mark_flushed();
             } // else (no non-flushed repeat count) do nothing.
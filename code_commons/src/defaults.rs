@@ -0,0 +1,8 @@
+//! The defaults of the call graph.
+
+/// The maximum length of a sibling sequence whose repeats are folded
+/// (the periodic sequence folding is disabled, such that the log of the existing programs doesn't change).
+pub(crate) const MAX_SEQUENCE_LEN: usize = 1;
+/// The recursion depth beyond which the self-recursive calls are recursion-folded
+/// (the recursion folding is disabled, such that the log of the existing programs doesn't change).
+pub(crate) const RECURSION_THRESHOLD: Option<usize> = None;
@@ -2,7 +2,13 @@
 
 // The code_commons crate is to be reused for various code-handling projects.
 mod call_graph;
+mod defaults;
+mod recursion_folding;
 pub use call_graph::{CallGraph, ItemKind, RepeatCountCategory};
+#[cfg(feature = "call_timing")]
+pub use call_graph::DurationStats;
+#[cfg(feature = "call_timing")]
+use std::time::{Duration, Instant};
 
 /// A trait to be implemented by the instances that need to be notified about the code run events
 /// (such as function or closure calls, returns, etc.).
@@ -14,17 +20,49 @@ pub trait CoderunNotifiable {
     /// * The call depth.
     /// * The function or closure name.
     /// * The optional string representation of the parameter names and values.
+    /// * The optional moment of the call.
     fn notify_call(&mut self, _call_depth: usize, _name: &str, 
         #[cfg(feature = "params_logging")]
-        _param_vals: &Option<String>
+        _param_vals: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
     ) {}
 
+    /// Notifies about a recursion-folded call, i.e. a call of a function or a closure by itself
+    /// (directly) deeper than the call graph's recursion threshold.
+    /// Such a call is notified about at the call depth of its parent (rather than one level deeper),
+    /// such that the deep recursion doesn't make the indent grow further.
+    /// By default is notified about as a regular call.
+    /// # Parameters
+    /// * The call depth (the same as the parent's one).
+    /// * The function or closure name.
+    /// * The optional string representation of the parameter names and values.
+    /// * The recursion depth (`2` for the first call of the function by itself, `3` for the next one, etc.).
+    /// * The optional moment of the call.
+    fn notify_recursive_call(&mut self, call_depth: usize, name: &str,
+        #[cfg(feature = "params_logging")]
+        param_vals: &Option<String>,
+        _recursion_depth: usize,
+        #[cfg(feature = "call_timing")]
+        start: Option<Instant>,
+    ) {
+        self.notify_call(
+            call_depth,
+            name,
+            #[cfg(feature = "params_logging")]
+            param_vals,
+            #[cfg(feature = "call_timing")]
+            start,
+        );
+    }
+
     /// Notifies about a function or a closure return.
     /// # Parameters
     /// * The call depth.
     /// * The function or closure name.
     /// * Flag telling if the call has nested calls.
     /// * The optional string representation of the return value.
+    /// * The optional wall-clock duration of the call.
     fn notify_return(
         &mut self,
         _call_depth: usize,
@@ -32,6 +70,8 @@ pub trait CoderunNotifiable {
         _has_nested_calls: bool,
         #[cfg(feature = "ret_val_logging")]
         _ret_val: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        _duration: Option<Duration>,
     ) {
     }
     /// Notifies about a repeat count.
@@ -39,11 +79,28 @@ pub trait CoderunNotifiable {
     /// * The call depth.
     /// * Call tree item info (function/closure or loop body, name, etc.).
     /// * Call tree item repeat count info.
+    /// * The wall-clock duration statistics of the repeats.
     fn notify_repeat_count(
         &mut self,
         _call_depth: usize,
         _kind: &ItemKind,
         _count: RepeatCountCategory,
+        #[cfg(feature = "call_timing")]
+        _timing: &DurationStats,
+    ) {
+    }
+
+    /// Notifies about a repeat count of a sequence of call tree items
+    /// (e.g. `// (a(), b()) repeats 5 time(s).`).
+    /// # Parameters
+    /// * The call depth.
+    /// * The info (function/closure or loop body, name, etc.) of each call tree item in the sequence.
+    /// * The sequence repeat count info.
+    fn notify_sequence_repeat_count(
+        &mut self,
+        _call_depth: usize,
+        _kinds: &[ItemKind],
+        _count: RepeatCountCategory,
     ) {
     }
 
@@ -54,11 +111,26 @@ pub trait CoderunNotifiable {
     /// Notifies about a loop body start.
     /// # Parameters
     /// * The call depth.
-    fn notify_loopbody_start(&mut self, _call_depth: usize);
+    /// * The optional moment of the loop body start.
+    fn notify_loopbody_start(&mut self, _call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    );
 
     /// Notifies about a loop body end.
     /// # Parameters
     /// * The call depth.
-    fn notify_loopbody_end(&mut self, _call_depth: usize) {}
+    /// * The optional wall-clock duration of the loop body.
+    fn notify_loopbody_end(&mut self, _call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        _duration: Option<Duration>,
+    ) {}
+
+    /// Notifies about the repeat folding suspension upon reaching the call graph's node budget.
+    /// Since then the calls, returns, and loop bodies are notified about as they come,
+    /// without folding the repeats.
+    /// # Parameters
+    /// * The call depth.
+    fn notify_folding_suspended(&mut self, _call_depth: usize) {}
 }
 
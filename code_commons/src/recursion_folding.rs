@@ -0,0 +1,232 @@
+use crate::{CoderunNotifiable, ItemKind, RepeatCountCategory};
+#[cfg(feature = "call_timing")]
+use crate::DurationStats;
+use std::{cell::RefCell, rc::Rc};
+#[cfg(feature = "call_timing")]
+use std::time::{Duration, Instant};
+
+/// A recursion-folded call not yet notified about.
+struct FoldedCall {
+    call_depth: usize,
+    name: String,
+    #[cfg(feature = "params_logging")]
+    param_vals: Option<String>,
+    recursion_depth: usize,
+    #[cfg(feature = "call_timing")]
+    start: Option<Instant>,
+}
+
+/// The state of a call or loop body notified about (or to be notified about) by the call graph
+/// and not yet returned or ended.
+enum Frame {
+    /// A call (not recursion-folded) or a loop body, notified about.
+    Notified,
+    /// A recursion-folded call with no nested items yet (whether it is the innermost frame of the folded chain
+    /// is not yet known).
+    Pending(FoldedCall),
+    /// A recursion-folded call whose nested items so far are the recursion-folded calls
+    /// (a middle frame of the folded chain), neither its call nor return is notified about.
+    Hidden(FoldedCall),
+}
+
+/// Recursion folding support.
+///
+/// The filter between the call graph and its decorator that makes the deep recursion compact:
+/// of a chain of the recursion-folded calls only the innermost frame is notified about
+/// (e.g. `fib() recursion depth 40 {`), the middle frames (and their returns) are hidden.
+///
+/// A recursion-folded call is not notified about until it is known whether it is the innermost frame:
+/// * if its first nested item is a recursion-folded call too, then it is a middle frame and is hidden;
+/// * otherwise (its first nested item is a different call or a loop body, or it returns with no nested items,
+///   or the log is flushed), it is notified about before that item (or return, or flush).
+///
+/// A hidden frame is notified about (and then its return too) once it gets a nested item
+/// other than a recursion-folded call (or its repeats), such that the item is logged with its parent.
+pub(crate) struct RecursionFoldingFilter {
+    /// The notifiable being filtered for (the decorator).
+    notifiable: Rc<RefCell<dyn CoderunNotifiable>>,
+    /// The calls and loop bodies notified about (or pending, or hidden) and not yet returned or ended,
+    /// from the outermost to the innermost.
+    frames: Vec<Frame>,
+}
+
+impl RecursionFoldingFilter {
+    /// Creates a new `RecursionFoldingFilter` for the `notifiable`.
+    pub(crate) fn new(notifiable: Rc<RefCell<dyn CoderunNotifiable>>) -> Self {
+        Self {
+            notifiable,
+            frames: Vec::new(),
+        }
+    }
+    /// Notifies about the innermost pending recursion-folded call, if any, that is the innermost frame
+    /// of the folded chain (since a different item follows it).
+    fn notify_pending_call(&mut self) {
+        let call = match self.frames.last_mut() {
+            Some(frame @ Frame::Pending(_)) => frame_call(frame),
+            _ => return,
+        };
+        self.notify_folded_call(call);
+    }
+    /// Notifies about the innermost pending or hidden recursion-folded call, if any,
+    /// since a nested item other than a recursion-folded call follows it.
+    fn notify_parent_call(&mut self) {
+        let call = match self.frames.last_mut() {
+            Some(frame @ (Frame::Pending(_) | Frame::Hidden(_))) => frame_call(frame),
+            _ => return,
+        };
+        self.notify_folded_call(call);
+    }
+    /// Notifies the parent about the nested items unless they all are the repeats of the hidden parent's
+    /// recursion-folded calls (which keep the parent hidden).
+    fn notify_parent_of_repeats(&mut self, kinds: &[ItemKind]) {
+        if let Some(Frame::Hidden(parent)) = self.frames.last()
+            && kinds
+                .iter()
+                .all(|kind| matches!(kind, ItemKind::Call { name, .. } if *name == parent.name))
+        {
+            return;
+        }
+        self.notify_parent_call();
+    }
+    /// Notifies about the recursion-folded call.
+    fn notify_folded_call(&mut self, call: FoldedCall) {
+        self.notifiable.borrow_mut().notify_recursive_call(
+            call.call_depth,
+            &call.name,
+            #[cfg(feature = "params_logging")]
+            &call.param_vals,
+            call.recursion_depth,
+            #[cfg(feature = "call_timing")]
+            call.start,
+        );
+    }
+}
+
+/// Takes the recursion-folded call of the pending or hidden frame, marking the frame as notified about.
+fn frame_call(frame: &mut Frame) -> FoldedCall {
+    match std::mem::replace(frame, Frame::Notified) {
+        Frame::Pending(call) | Frame::Hidden(call) => call,
+        Frame::Notified => unreachable!("The frame is expected to be pending or hidden"),
+    }
+}
+
+impl CoderunNotifiable for RecursionFoldingFilter {
+    fn notify_call(&mut self, call_depth: usize, name: &str,
+        #[cfg(feature = "params_logging")]
+        param_vals: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        start: Option<Instant>,
+    ) {
+        self.notify_parent_call();
+        self.frames.push(Frame::Notified);
+        self.notifiable.borrow_mut().notify_call(
+            call_depth,
+            name,
+            #[cfg(feature = "params_logging")]
+            param_vals,
+            #[cfg(feature = "call_timing")]
+            start,
+        );
+    }
+    fn notify_recursive_call(&mut self, call_depth: usize, name: &str,
+        #[cfg(feature = "params_logging")]
+        param_vals: &Option<String>,
+        recursion_depth: usize,
+        #[cfg(feature = "call_timing")]
+        start: Option<Instant>,
+    ) {
+        // The pending parent (calling itself) is a middle frame of the folded chain:
+        if let Some(frame @ Frame::Pending(_)) = self.frames.last_mut() {
+            *frame = Frame::Hidden(frame_call(frame));
+        }
+        self.frames.push(Frame::Pending(FoldedCall {
+            call_depth,
+            name: String::from(name),
+            #[cfg(feature = "params_logging")]
+            param_vals: param_vals.clone(),
+            recursion_depth,
+            #[cfg(feature = "call_timing")]
+            start,
+        }));
+    }
+    fn notify_return(
+        &mut self,
+        call_depth: usize,
+        name: &str,
+        has_nested_calls: bool,
+        #[cfg(feature = "ret_val_logging")]
+        ret_val: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        duration: Option<Duration>,
+    ) {
+        // The pending call with no nested items is the innermost frame of the folded chain:
+        self.notify_pending_call();
+        if let Some(Frame::Hidden(_)) = self.frames.pop() {
+            return;
+        }
+        self.notifiable.borrow_mut().notify_return(
+            call_depth,
+            name,
+            has_nested_calls,
+            #[cfg(feature = "ret_val_logging")]
+            ret_val,
+            #[cfg(feature = "call_timing")]
+            duration,
+        );
+    }
+    fn notify_repeat_count(
+        &mut self,
+        call_depth: usize,
+        kind: &ItemKind,
+        count: RepeatCountCategory,
+        #[cfg(feature = "call_timing")]
+        timing: &DurationStats,
+    ) {
+        self.notify_parent_of_repeats(std::slice::from_ref(kind));
+        self.notifiable.borrow_mut().notify_repeat_count(
+            call_depth,
+            kind,
+            count,
+            #[cfg(feature = "call_timing")]
+            timing,
+        );
+    }
+    fn notify_sequence_repeat_count(&mut self, call_depth: usize, kinds: &[ItemKind], count: RepeatCountCategory) {
+        self.notify_parent_of_repeats(kinds);
+        self.notifiable
+            .borrow_mut()
+            .notify_sequence_repeat_count(call_depth, kinds, count);
+    }
+    fn notify_flush(&mut self) {
+        self.notify_pending_call();
+        self.notifiable.borrow_mut().notify_flush();
+    }
+    fn notify_loopbody_start(&mut self, call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        start: Option<Instant>,
+    ) {
+        self.notify_parent_call();
+        self.frames.push(Frame::Notified);
+        self.notifiable.borrow_mut().notify_loopbody_start(
+            call_depth,
+            #[cfg(feature = "call_timing")]
+            start,
+        );
+    }
+    fn notify_loopbody_end(&mut self, call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        duration: Option<Duration>,
+    ) {
+        self.notify_pending_call();
+        self.frames.pop();
+        self.notifiable.borrow_mut().notify_loopbody_end(
+            call_depth,
+            #[cfg(feature = "call_timing")]
+            duration,
+        );
+    }
+    fn notify_folding_suspended(&mut self, call_depth: usize) {
+        self.notify_parent_call();
+        self.notifiable.borrow_mut().notify_folding_suspended(call_depth);
+    }
+}
@@ -1,11 +1,23 @@
+pub mod binary_trace;
+pub mod builder;
 pub mod call_log_infra;
+pub mod config;
 pub mod decorators;
+mod defaults;
 #[cfg(feature = "multithreaded")]
 pub mod multithreaded;
 #[cfg(feature = "std_output_sync")]
 mod output_sync;
+#[cfg(feature = "parallel_logging")]
+pub mod parallel;
+pub mod ring_buffer_writer;
+pub mod rotating_file_writer;
+pub mod settings;
 #[cfg(feature = "single_threaded")]
 pub mod singlethreaded;
+pub mod thread_log_file;
+#[cfg(unix)]
+pub mod unix_socket_writer;
 
 use call_log_infra::instances::THREAD_LOGGER;
 
@@ -59,6 +71,84 @@ macro_rules! set_thread_indent {
     };
 }
 
+/// Sets the maximum number of nodes in the call graph of the invoking thread (`None` means unlimited, the default).
+/// Upon reaching the budget the repeat folding is suspended for the invoking thread,
+/// the log gets the `// FCL: repeat folding suspended (node budget reached)` note,
+/// and the subsequent calls are logged as they come.
+/// #### Examples
+/// ```rs
+/// fcl::set_node_budget!(Some(100_000));
+/// ```
+#[macro_export]
+macro_rules! set_node_budget {
+    ($expr:expr) => {
+        fcl::common::call_log_infra::instances::THREAD_LOGGER.with(|logger| {
+            use fcl::common::CallLogger;
+            let logger = fcl::extra_borrow_mut!(logger);
+            logger.borrow_mut().set_node_budget($expr)
+        })
+    };
+}
+
+/// Sets the maximum length of a sibling sequence whose repeats are folded in the log of the invoking thread
+/// (1 by default). Values less than 2 disable the periodic sequence folding.
+/// #### Examples
+/// ```rs
+/// fcl::set_max_sequence_len!(2); // `a() b() a() b()` -> `a() b() // (a(), b()) repeats 1 time(s).`
+/// fcl::set_max_sequence_len!(1); // Disable the periodic sequence folding.
+/// ```
+#[macro_export]
+macro_rules! set_max_sequence_len {
+    ($expr:expr) => {
+        fcl::common::call_log_infra::instances::THREAD_LOGGER.with(|logger| {
+            use fcl::common::CallLogger;
+            let logger = fcl::extra_borrow_mut!(logger);
+            logger.borrow_mut().set_max_sequence_len($expr)
+        })
+    };
+}
+
+/// Sets the recursion depth beyond which the chains of a function (or closure) directly calling itself
+/// are recursion-folded in the log of the invoking thread (`None` by default, i.e. the recursion folding is disabled).
+/// The recursion-folded calls are logged at the indent of their parent, along with the recursion depth
+/// (e.g. `fib() recursion depth 40 {`), such that the deep recursion doesn't make the indent grow further.
+/// #### Examples
+/// ```rs
+/// fcl::set_recursion_threshold!(Some(8));
+/// fcl::set_recursion_threshold!(None); // Disable the recursion folding (the default).
+/// ```
+#[macro_export]
+macro_rules! set_recursion_threshold {
+    ($expr:expr) => {
+        fcl::common::call_log_infra::instances::THREAD_LOGGER.with(|logger| {
+            use fcl::common::CallLogger;
+            let logger = fcl::extra_borrow_mut!(logger);
+            logger.borrow_mut().set_recursion_threshold($expr)
+        })
+    };
+}
+
+/// Enables or disables logging the wall-clock durations of the calls and loop bodies
+/// (along with the repeats' duration statistics) in the log of the invoking thread.
+/// Is off by default, since the durations make the log non-deterministic.
+/// Is applied to the subsequent calls and loop bodies.
+/// Without the "call_timing" feature does nothing.
+/// #### Examples
+/// ```rs
+/// fcl::set_call_timing!(true); // E.g. `} // f(), 1.2ms.`
+/// ```
+#[cfg(feature = "call_timing")]
+#[macro_export]
+macro_rules! set_call_timing {
+    ($expr:expr) => {
+        fcl::common::call_log_infra::instances::THREAD_LOGGER.with(|logger| {
+            use fcl::common::CallLogger;
+            let logger = fcl::extra_borrow_mut!(logger);
+            logger.borrow_mut().set_call_timing($expr)
+        })
+    };
+}
+
 /// Temporarily enables or disables the call logging for the invoking thread.
 /// #### Examples
 /// ```rs
@@ -182,6 +272,35 @@ pub trait CallLogger {
     /// to visually separate the spawned thread's log from the `main()` thread's log.
     fn set_thread_indent(&mut self, _thread_indent: String) {}
 
+    /// Sets the maximum number of nodes in the calling thread's call graph (`None` means unlimited).
+    ///
+    /// Upon reaching the budget the repeat folding for the calling thread is suspended,
+    /// and the log degrades to plain streaming instead of exhausting the memory
+    /// (e.g. by a long-running function that never returns and keeps growing its subtree).
+    fn set_node_budget(&mut self, _node_budget: Option<usize>) {}
+
+    /// Sets the maximum length of a sibling sequence whose repeats are folded in the calling thread's log
+    /// (e.g. `a() b() a() b()` is logged as `a() b() // (a(), b()) repeats 1 time(s).`).
+    /// Values less than 2 disable the periodic sequence folding.
+    fn set_max_sequence_len(&mut self, _max_sequence_len: usize) {}
+
+    /// Sets the recursion depth beyond which the chains of a function (or closure) directly calling itself
+    /// are logged at a constant indent in the calling thread's log (`None` disables the recursion folding).
+    fn set_recursion_threshold(&mut self, _recursion_threshold: Option<usize>) {}
+
+    /// Sets whether the wall-clock durations of the calls and loop bodies are logged in the calling thread's log
+    /// (off by default).
+    #[cfg(feature = "call_timing")]
+    fn set_call_timing(&mut self, _call_timing: bool) {}
+
+    /// Notifies the calling thread's log decorator about the start of logging the thread's calls,
+    /// e.g. for logging the thread start header. Is called before the thread's first logged call.
+    fn log_thread_start(&mut self) {}
+
+    /// Notifies the calling thread's log decorator about the end of logging the thread's calls,
+    /// e.g. for logging the thread end summary line. Is called upon the thread's exit or panic.
+    fn log_thread_end(&mut self) {}
+
     /// For the calling thread updates the call graph with a function or closure call
     /// and potentially logs that call.
     /// # Parameters
@@ -211,6 +330,9 @@ pub trait CallLogger {
     /// Flushes the data cached in the call graph upon certain condition, such as
     /// the instrumented user's code own output or panic hook output.
     fn maybe_flush(&mut self);
+    /// Flushes the data cached in the call graph and the buffered std output,
+    /// and dumps the FCL's ring buffer writer, if any (see `fcl::dump_ring()`).
+    fn dump_ring(&mut self) {}
 
     /// Updates the call graph of the calling thread with a loop body start and potentially logs that start.
     fn log_loopbody_start(&mut self);
@@ -0,0 +1,258 @@
+//! Decoding the binary trace logged by the `BinaryTraceDecorator` (see also the `fcl_decode` binary).
+
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+use code_commons::{ItemKind, RepeatCountCategory};
+#[cfg(feature = "call_timing")]
+use code_commons::DurationStats;
+
+use crate::common::decorators::{
+    BINARY_TRACE_START, BINARY_TRACE_TAGS, BinaryTraceTag, LogDecorator,
+};
+
+/// The reader of the fields of a binary trace record.
+struct BinaryTraceRecord<'a> {
+    /// The rest of the record.
+    bytes: &'a [u8],
+}
+
+/// Returns the error about the invalid binary trace.
+fn invalid_binary_trace(description: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Invalid FCL binary trace: {}", description))
+}
+
+impl<'a> BinaryTraceRecord<'a> {
+    /// Takes the next `length` bytes.
+    fn bytes(&mut self, length: usize) -> std::io::Result<&'a [u8]> {
+        if self.bytes.len() < length {
+            return Err(invalid_binary_trace("truncated record"));
+        }
+        let (bytes, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(bytes)
+    }
+    /// Takes the next `u8`.
+    fn u8(&mut self) -> std::io::Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+    /// Takes the next `u32`.
+    fn u32(&mut self) -> std::io::Result<u32> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap_or_default()))
+    }
+    /// Takes the next `u64`.
+    fn u64(&mut self) -> std::io::Result<u64> {
+        Ok(u64::from_le_bytes(self.bytes(8)?.try_into().unwrap_or_default()))
+    }
+    /// Takes the next string.
+    fn string(&mut self) -> std::io::Result<String> {
+        let length = self.u32()? as usize;
+        String::from_utf8(self.bytes(length)?.to_vec()).map_err(|_| invalid_binary_trace("non-UTF-8 string"))
+    }
+    /// Takes the next optional string.
+    fn optional_string(&mut self) -> std::io::Result<Option<String>> {
+        Ok(if self.u8()? == 0 { None } else { Some(self.string()?) })
+    }
+    /// Takes the next optional duration.
+    fn optional_duration(&mut self) -> std::io::Result<Option<std::time::Duration>> {
+        Ok(if self.u8()? == 0 { None } else { Some(std::time::Duration::from_nanos(self.u64()?)) })
+    }
+    /// Takes the next repeat count.
+    fn repeat_count(&mut self) -> std::io::Result<RepeatCountCategory> {
+        let category = self.u8()?;
+        let count = self.u64()? as usize;
+        match category {
+            0 => Ok(RepeatCountCategory::Exact(count)),
+            1 => Ok(RepeatCountCategory::AtLeast(count)),
+            2 => Ok(RepeatCountCategory::Unknown),
+            _ => Err(invalid_binary_trace("unknown repeat count category")),
+        }
+    }
+    /// Takes the next item (of the repeat count).
+    fn item(&mut self, names: &BTreeMap<u32, String>) -> std::io::Result<ItemKind> {
+        match self.u8()? {
+            0 => {
+                let name = binary_trace_name(names, self.u32()?)?;
+                let _params = self.optional_string()?;
+                Ok(ItemKind::Call {
+                    name,
+                    #[cfg(feature = "params_logging")]
+                    param_vals: _params,
+                })
+            }
+            1 => Ok(ItemKind::Loopbody { ends_the_loop: self.u8()? != 0 }),
+            _ => Err(invalid_binary_trace("unknown item kind")),
+        }
+    }
+}
+
+/// Returns the interned name of the `name_id`.
+fn binary_trace_name(names: &BTreeMap<u32, String>, name_id: u32) -> std::io::Result<String> {
+    names.get(&name_id).cloned().ok_or_else(|| invalid_binary_trace("unknown name ID"))
+}
+
+/// The state of a thread while decoding the binary trace.
+struct BinaryTraceThread {
+    /// The decorator the thread's records are decoded to.
+    decorator: Rc<RefCell<dyn LogDecorator>>,
+    /// The thread's interned names by the name IDs.
+    names: BTreeMap<u32, String>,
+}
+
+/// Decodes the binary trace (logged by the `BinaryTraceDecorator`) reading it from the `reader`,
+/// and notifies the decorators about the decoded calls, returns, etc.
+///
+/// The decorator of each thread is created by `new_decorator` upon the thread's first record.
+/// Its parameters are the number of the thread ID and the thread name (empty if the thread is unnamed).
+///
+/// The parameters, return values, and durations are decoded into the notifications only if the corresponding
+/// features are on.
+/// #### Examples
+/// ```rs
+/// use fcl::common::{binary_trace::decode_binary_trace, decorators::CodeLikeDecorator};
+/// let mut trace = std::fs::File::open("trace.fclb")?;
+/// decode_binary_trace(&mut trace, |_thread_id, _thread_name| {
+///     Rc::new(RefCell::new(CodeLikeDecorator::new(None, None)))
+/// })?;
+/// ```
+pub fn decode_binary_trace(
+    reader: &mut dyn std::io::Read,
+    mut new_decorator: impl FnMut(u64, &str) -> Rc<RefCell<dyn LogDecorator>>,
+) -> std::io::Result<()> {
+    let mut trace = Vec::new();
+    reader.read_to_end(&mut trace)?;
+    let mut trace = trace.as_slice();
+    let mut threads: BTreeMap<u64, BinaryTraceThread> = BTreeMap::new();
+    while !trace.is_empty() {
+        // The trace start (the output could have been shared by multiple traces).
+        if trace.starts_with(&BINARY_TRACE_START[..4]) {
+            if trace.get(4) != BINARY_TRACE_START.get(4) {
+                return Err(invalid_binary_trace("unsupported format version"));
+            }
+            trace = &trace[BINARY_TRACE_START.len()..];
+            continue;
+        }
+        let mut length_reader = BinaryTraceRecord { bytes: trace };
+        let length = length_reader.u32()? as usize;
+        let mut record = BinaryTraceRecord { bytes: length_reader.bytes(length)? };
+        trace = length_reader.bytes;
+
+        let tag = *BINARY_TRACE_TAGS
+            .get(record.u8()? as usize)
+            .ok_or_else(|| invalid_binary_trace("unknown record tag"))?;
+        let thread_id = record.u64()?;
+        if tag == BinaryTraceTag::Thread {
+            let thread_name = record.string()?;
+            threads.insert(thread_id, BinaryTraceThread {
+                decorator: new_decorator(thread_id, &thread_name),
+                names: BTreeMap::new(),
+            });
+            continue;
+        }
+        let thread = threads
+            .get_mut(&thread_id)
+            .ok_or_else(|| invalid_binary_trace("record of an unknown thread"))?;
+        if tag == BinaryTraceTag::Name {
+            let name_id = record.u32()?;
+            thread.names.insert(name_id, record.string()?);
+            continue;
+        }
+        let mut decorator = thread.decorator.borrow_mut();
+        match tag {
+            BinaryTraceTag::Thread | BinaryTraceTag::Name => {} // Handled above.
+            BinaryTraceTag::Call => {
+                let call_depth = record.u32()? as usize;
+                let name = binary_trace_name(&thread.names, record.u32()?)?;
+                let _params = record.optional_string()?;
+                let recursion_depth = record.u32()? as usize;
+                if recursion_depth == 0 {
+                    decorator.notify_call(
+                        call_depth,
+                        &name,
+                        #[cfg(feature = "params_logging")]
+                        &_params,
+                        #[cfg(feature = "call_timing")]
+                        None, // The moment of the call is not recorded in the trace.
+                    );
+                } else {
+                    decorator.notify_recursive_call(
+                        call_depth,
+                        &name,
+                        #[cfg(feature = "params_logging")]
+                        &_params,
+                        recursion_depth,
+                        #[cfg(feature = "call_timing")]
+                        None, // The moment of the call is not recorded in the trace.
+                    );
+                }
+            }
+            BinaryTraceTag::Return => {
+                let call_depth = record.u32()? as usize;
+                let name = binary_trace_name(&thread.names, record.u32()?)?;
+                let has_nested_calls = record.u8()? != 0;
+                let _ret_val = record.optional_string()?;
+                let _duration = record.optional_duration()?;
+                decorator.notify_return(
+                    call_depth,
+                    &name,
+                    has_nested_calls,
+                    #[cfg(feature = "ret_val_logging")]
+                    &_ret_val,
+                    #[cfg(feature = "call_timing")]
+                    _duration,
+                );
+            }
+            BinaryTraceTag::RepeatCount => {
+                let call_depth = record.u32()? as usize;
+                let kind = record.item(&thread.names)?;
+                let count = record.repeat_count()?;
+                let _timing = if record.u8()? == 0 {
+                    None
+                } else {
+                    Some((
+                        record.u32()?,
+                        std::time::Duration::from_nanos(record.u64()?),
+                        std::time::Duration::from_nanos(record.u64()?),
+                        std::time::Duration::from_nanos(record.u64()?),
+                    ))
+                };
+                #[cfg(feature = "call_timing")]
+                let timing = _timing.map_or_else(DurationStats::new, |(count, total, min, max)| {
+                    DurationStats::from_parts(count, total, min, max)
+                });
+                decorator.notify_repeat_count(
+                    call_depth,
+                    &kind,
+                    count,
+                    #[cfg(feature = "call_timing")]
+                    &timing,
+                );
+            }
+            BinaryTraceTag::SequenceRepeatCount => {
+                let call_depth = record.u32()? as usize;
+                let item_count = record.u32()?;
+                let kinds = (0..item_count)
+                    .map(|_| record.item(&thread.names))
+                    .collect::<std::io::Result<Vec<ItemKind>>>()?;
+                let count = record.repeat_count()?;
+                decorator.notify_sequence_repeat_count(call_depth, &kinds, count);
+            }
+            BinaryTraceTag::Flush => decorator.notify_flush(),
+            BinaryTraceTag::LoopbodyStart => decorator.notify_loopbody_start(
+                record.u32()? as usize,
+                #[cfg(feature = "call_timing")]
+                None, // The moment of the loop body start is not recorded in the trace.
+            ),
+            BinaryTraceTag::LoopbodyEnd => {
+                let call_depth = record.u32()? as usize;
+                let _duration = record.optional_duration()?;
+                decorator.notify_loopbody_end(
+                    call_depth,
+                    #[cfg(feature = "call_timing")]
+                    _duration,
+                );
+            }
+            BinaryTraceTag::FoldingSuspended => decorator.notify_folding_suspended(record.u32()? as usize),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,158 @@
+#[cfg(feature = "log_merger")]
+use crate::common::call_log_infra::{FclWriter, create_thread_shared_writer, set_pending_fcl_writer};
+use crate::common::decorators::{DecoratorFactory, DecoratorKind};
+use crate::common::settings::settings;
+
+/// Marks the logging infrastructure as created (upon the first `THREAD_DECORATOR` creation),
+/// such that the subsequent `Builder::init()` fails.
+pub(crate) fn set_infra_is_created() {
+    settings().infra_is_created = true;
+}
+
+/// Returns whether the logging infrastructure has been created (upon the first logged call),
+/// i.e. whether it is too late to configure it.
+pub fn infra_is_created() -> bool {
+    settings().infra_is_created
+}
+
+/// The builder of the logging infrastructure configuration.
+///
+/// Collects the settings and applies them all at once with `init()`, that must be called before the first logged call
+/// (e.g. at the beginning of a non-loggable `main()`), such that the `CALL_LOGGER_ARBITER`,
+/// the `THREAD_SHARED_WRITER`, and every thread's decorator are created consistently.
+/// The settings not specified keep the defaults (or the values set with the individual `set_*()` functions).
+/// The builder takes precedence over the configuration file and environment variables
+/// (see `fcl::common::config::Config`).
+/// #### Examples
+/// ```rs
+/// use fcl::common::call_log_infra::FclWriter;
+/// use fcl::common::decorators::DecoratorKind;
+/// fn main() {
+///     fcl::Builder::new()
+///         .writer(FclWriter::Stderr)
+///         .decorator_kind(DecoratorKind::TreeLike)
+///         .thread_indent_step(" ".repeat(80))
+///         .init()
+///         .unwrap();
+///     // . . . (Logged calls.)
+/// }
+/// ```
+#[derive(Default)]
+pub struct Builder {
+    /// The writer used for logging by all the threads.
+    #[cfg(feature = "log_merger")]
+    writer: Option<FclWriter>,
+    /// The kind of the log decorator created for each thread.
+    decorator_kind: Option<DecoratorKind>,
+    /// The function creating the log decorator for each thread.
+    decorator_factory: Option<DecoratorFactory>,
+    /// The function indent step of the `CodeLikeDecorator` created for each thread.
+    indent_step: Option<String>,
+    /// The thread indent step.
+    thread_indent_step: Option<String>,
+    /// Whether the thread start header and thread end summary lines are logged.
+    thread_headers: Option<bool>,
+    /// Whether the panic hook synchronizing the panic report with the log is set.
+    panic_sync: Option<bool>,
+    /// Whether the user's standard output is synchronized with the log.
+    #[cfg(feature = "std_output_sync")]
+    std_output_sync: Option<bool>,
+}
+
+impl Builder {
+    /// Creates a new `Builder` with no settings specified.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Specifies the writer used for logging by all the threads (see `THREAD_SHARED_WRITER`).
+    #[cfg(feature = "log_merger")]
+    pub fn writer(mut self, writer: FclWriter) -> Self {
+        self.writer = Some(writer);
+        self
+    }
+    /// Specifies the kind of the log decorator created for each thread.
+    pub fn decorator_kind(mut self, decorator_kind: DecoratorKind) -> Self {
+        self.decorator_kind = Some(decorator_kind);
+        self
+    }
+    /// Specifies the function creating the log decorator for each thread
+    /// (takes precedence over the decorator kind, see `fcl::common::settings::set_decorator_factory()`).
+    pub fn decorator_factory(mut self, decorator_factory: DecoratorFactory) -> Self {
+        self.decorator_factory = Some(decorator_factory);
+        self
+    }
+    /// Specifies the function indent step of the `CodeLikeDecorator` created for each thread.
+    pub fn indent_step(mut self, indent_step: impl Into<String>) -> Self {
+        self.indent_step = Some(indent_step.into());
+        self
+    }
+    /// Specifies the thread indent step - the string of white-spaces separating the logs of the different threads.
+    pub fn thread_indent_step(mut self, thread_indent_step: impl Into<String>) -> Self {
+        self.thread_indent_step = Some(thread_indent_step.into());
+        self
+    }
+    /// Specifies whether the thread start header and thread end summary lines are logged
+    /// (see `fcl::common::settings::set_thread_headers()`).
+    pub fn thread_headers(mut self, is_on: bool) -> Self {
+        self.thread_headers = Some(is_on);
+        self
+    }
+    /// Specifies whether the FCL's panic hook synchronizing the panic report with the log is set
+    /// (see `fcl::common::settings::set_panic_sync_is_on()`).
+    pub fn panic_sync(mut self, is_on: bool) -> Self {
+        self.panic_sync = Some(is_on);
+        self
+    }
+    /// Specifies whether the user's `stdout` and `stderr` output is synchronized with the log
+    /// (see `fcl::common::settings::set_std_output_sync_is_on()`).
+    #[cfg(feature = "std_output_sync")]
+    pub fn std_output_sync(mut self, is_on: bool) -> Self {
+        self.std_output_sync = Some(is_on);
+        self
+    }
+    /// Applies the settings specified.
+    ///
+    /// Fails (and applies nothing) if the logging infrastructure has already been created
+    /// (upon the first logged call) by any thread.
+    ///
+    /// The check and the application are made under the settings lock, such that the logging infrastructure
+    /// creation by a different thread doesn't interleave with them.
+    pub fn init(self) -> Result<(), String> {
+        let mut settings = settings();
+        if settings.infra_is_created {
+            return Err(String::from(
+                "The FCL logging infrastructure is already created, `fcl::Builder::init()` must precede the first logged call",
+            ));
+        }
+        #[cfg(feature = "log_merger")]
+        if let Some(writer) = self.writer {
+            set_pending_fcl_writer(writer);
+            // The writer is held by the calling thread until the `THREAD_SHARED_WRITER` creation,
+            // whereas the first logged call can be made by a different thread:
+            create_thread_shared_writer();
+        }
+        if let Some(decorator_kind) = self.decorator_kind {
+            settings.decorator_kind = decorator_kind;
+        }
+        if let Some(decorator_factory) = self.decorator_factory {
+            settings.decorator_factory = Some(decorator_factory);
+        }
+        if let Some(indent_step) = self.indent_step {
+            settings.indent_step = Some(indent_step);
+        }
+        if let Some(thread_indent_step) = self.thread_indent_step {
+            settings.thread_indent_step = Some(thread_indent_step);
+        }
+        if let Some(is_on) = self.thread_headers {
+            settings.thread_headers = is_on;
+        }
+        if let Some(is_on) = self.panic_sync {
+            settings.panic_sync_is_on = is_on;
+        }
+        #[cfg(feature = "std_output_sync")]
+        if let Some(is_on) = self.std_output_sync {
+            settings.std_output_sync_is_on = is_on;
+        }
+        Ok(())
+    }
+}
@@ -2,6 +2,9 @@ use std::cell::LazyCell;
 use std::{cell::RefCell, collections::HashMap, io::Write, rc::Rc, sync::LazyLock, thread};
 
 use crate::common::CallLogger;
+use crate::common::settings::{panic_sync_is_on, thread_indent_step};
+#[cfg(feature = "std_output_sync")]
+use crate::common::settings::std_output_sync_is_on;
 use crate::common::decorators::{LogDecorator, ThreadSpecific};
 use code_commons::{CallGraph, CoderunNotifiable};
 
@@ -10,7 +13,10 @@ use crate::common::output_sync::StdOutputRedirector;
 #[cfg(feature = "std_output_sync")]
 use writer::{ThreadSharedWriterPtr, WriterKind};
 
-#[cfg(feature = "log_merger")]
+#[cfg(any(
+    all(feature = "log_merger", not(feature = "parallel_logging")),
+    feature = "std_output_sync"
+))]
 use writer::{WriterAdapter};
 
 #[cfg(any(feature = "log_merger", feature = "std_output_sync"))]
@@ -18,6 +24,15 @@ use writer::THREAD_SHARED_WRITER;
 
 #[cfg(any(feature = "log_merger", feature = "std_output_sync"))]
 mod writer;
+#[cfg(any(feature = "log_merger", feature = "std_output_sync"))]
+pub use writer::FclWriter;
+#[cfg(feature = "log_merger")]
+pub(crate) use writer::{create_thread_shared_writer, set_pending_fcl_writer};
+
+#[cfg(feature = "parallel_logging")]
+use crate::common::parallel::{ParallelInfraAdapter, ParallelInfraPtr, UpdateLogPtr, merge_chunks};
+#[cfg(feature = "parallel_logging")]
+use std::sync::TryLockError;
 
 /// A macro containing the error message in case of an unexpected absence of a logger for the corresponding thread.
 macro_rules! NO_LOGGER_ERR_STR {
@@ -57,6 +72,15 @@ pub struct CallLogInfra {
     thread_specifics: Rc<RefCell<dyn ThreadSpecific>>,
     /// The thread's call graph.
     call_graph: CallGraph,
+    /// The patterns of the function and closure names that are not logged along with the calls they make
+    /// (see `crate::common::config::name_matches()`).
+    exclude: Vec<String>,
+    /// The depth of the calls inside the excluded call being executed (`0` if none is being executed).
+    excluded_depth: usize,
+    /// The thread that has created the instance (reported to the thread specifics upon the thread start and end).
+    thread: thread::Thread,
+    /// The number of the thread's logged calls (reported to the thread specifics upon the thread end).
+    call_count: usize,
 }
 
 impl CallLogInfra {
@@ -68,8 +92,17 @@ impl CallLogInfra {
             logging_is_on: Vec::with_capacity(4),
             thread_specifics,
             call_graph: CallGraph::new(coderun_notifiable),
+            exclude: Vec::new(),
+            excluded_depth: 0,
+            thread: thread::current(),
+            call_count: 0,
         }
     }
+    /// Sets the patterns of the function and closure names that are not logged along with the calls they make
+    /// (e.g. `"*::closure*"` excludes all the closures).
+    pub fn set_exclude(&mut self, exclude: Vec<String>) {
+        self.exclude = exclude;
+    }
 }
 
 impl CallLogger for CallLogInfra {
@@ -92,12 +125,46 @@ impl CallLogger for CallLogInfra {
             .borrow_mut()
             .set_thread_indent(thread_indent);
     }
+    fn set_node_budget(&mut self, node_budget: Option<usize>) {
+        self.call_graph.set_node_budget(node_budget);
+    }
+    fn set_max_sequence_len(&mut self, max_sequence_len: usize) {
+        self.call_graph.set_max_sequence_len(max_sequence_len);
+    }
+    fn set_recursion_threshold(&mut self, recursion_threshold: Option<usize>) {
+        self.call_graph.set_recursion_threshold(recursion_threshold);
+    }
+    #[cfg(feature = "call_timing")]
+    fn set_call_timing(&mut self, call_timing: bool) {
+        self.call_graph.set_call_timing(call_timing);
+    }
+    fn log_thread_start(&mut self) {
+        self.thread_specifics
+            .borrow_mut()
+            .notify_thread_start(&self.thread);
+    }
+    fn log_thread_end(&mut self) {
+        self.thread_specifics
+            .borrow_mut()
+            .notify_thread_end(&self.thread, self.call_count);
+    }
 
     fn log_call(
         &mut self,
         name: &str,
         #[cfg(feature = "params_logging")] param_vals: Option<String>,
     ) {
+        // Skip the excluded call and the calls it makes:
+        if self.excluded_depth != 0
+            || self
+                .exclude
+                .iter()
+                .any(|pattern| crate::common::config::name_matches(pattern, name))
+        {
+            self.excluded_depth += 1;
+            return;
+        }
+        self.call_count += 1;
         self.call_graph.add_call(
             name,
             #[cfg(feature = "params_logging")]
@@ -105,6 +172,10 @@ impl CallLogger for CallLogInfra {
         );
     }
     fn log_ret(&mut self, #[cfg(feature = "ret_val_logging")] ret_val: Option<String>) {
+        if self.excluded_depth != 0 {
+            self.excluded_depth -= 1;
+            return;
+        }
         self.call_graph.add_ret(
             #[cfg(feature = "ret_val_logging")]
             ret_val,
@@ -116,16 +187,34 @@ impl CallLogger for CallLogInfra {
     }
     fn maybe_flush(&mut self) {}
     fn log_loopbody_start(&mut self) {
-        self.call_graph.add_loopbody_start()
+        if self.excluded_depth == 0 {
+            self.call_graph.add_loopbody_start()
+        }
     }
     fn log_loopbody_end(&mut self) {
-        self.call_graph.add_loopbody_end()
+        if self.excluded_depth == 0 {
+            self.call_graph.add_loopbody_end()
+        }
     }
     fn log_loop_end(&mut self) {
-        self.call_graph.add_loop_end()
+        if self.excluded_depth == 0 {
+            self.call_graph.add_loop_end()
+        }
     }
 }
 
+/// Whose cache is flushed before merging the logs of the threads (with the "parallel_logging" feature).
+#[cfg(feature = "parallel_logging")]
+#[derive(Clone, Copy, PartialEq)]
+enum CacheFlush {
+    /// No thread's cache is flushed.
+    None,
+    /// The calling thread's cache is flushed (e.g. before its std output).
+    CurrentThread,
+    /// The cache of all the threads is flushed.
+    AllThreads,
+}
+
 /// The ID used for thread indentation mechanism.
 type ThreadIndentId = usize;
 
@@ -172,9 +261,7 @@ impl ThreadIndents {
     fn new(thread_indent_step: Option<String>) -> Self {
         Self {
             indents_taken: vec![false, false, false, false],
-            thread_indent_step: thread_indent_step.unwrap_or(String::from(
-                "                                                  ",
-            )), // 50 spaces // TODO: Consider extracting all the defaults to a separate file.
+            thread_indent_step: thread_indent_step.unwrap_or(String::from(crate::common::defaults::THREAD_INDENT_STEP)),
         }
     }
     /// Generates and returns the thread's indent by using the thread indent step `index` times.
@@ -241,6 +328,14 @@ pub struct CallLoggerArbiter {
     last_fcl_update_thread: Option<thread::ThreadId>,
     /// Containter of the thread indents by thread indent ID.
     thread_indents: ThreadIndents,
+    /// The infrastructures and update logs of the threads updating their call graphs in parallel,
+    /// whose logs are merged by the arbiter.
+    #[cfg(feature = "parallel_logging")]
+    thread_infras: HashMap<thread::ThreadId, (ParallelInfraPtr, UpdateLogPtr)>,
+    /// The update logs of the removed threads, whose output is held back by the merge
+    /// until the updates in progress by the other threads are complete.
+    #[cfg(feature = "parallel_logging")]
+    removed_update_logs: Vec<UpdateLogPtr>,
 
     /// Container of the output synchronization resources.
     #[cfg(feature = "std_output_sync")]
@@ -257,7 +352,11 @@ impl CallLoggerArbiter {
         return Self {
             thread_loggers: HashMap::new(),
             last_fcl_update_thread: None,
-            thread_indents: ThreadIndents::new(None),
+            thread_indents: ThreadIndents::new(thread_indent_step()),
+            #[cfg(feature = "parallel_logging")]
+            thread_infras: HashMap::new(),
+            #[cfg(feature = "parallel_logging")]
+            removed_update_logs: Vec::new(),
 
             #[cfg(feature = "std_output_sync")]
             output_sync: OutputSync {
@@ -269,16 +368,39 @@ impl CallLoggerArbiter {
         };
     }
 
+    /// For the calling thread adds the infrastructure updated by the thread in parallel with the other threads
+    /// (see `add_thread_logger()`), and the thread's update log merged by the arbiter.
+    #[cfg(feature = "parallel_logging")]
+    pub(crate) fn add_parallel_thread_logger(&mut self, infra: ParallelInfraPtr, update_log: UpdateLogPtr) {
+        self.add_thread_logger(Box::new(ParallelInfraAdapter::new(infra.clone())));
+        self.thread_infras.insert(thread::current().id(), (infra, update_log));
+    }
     /// For the calling thread
     /// * adds to the collection of thread loggers
     /// the thread logger passed as an argument,
-    /// * assigns the thread indent to be used by that logger.
+    /// * assigns the thread indent to be used by that logger,
+    /// * notifies the logger about the thread start (e.g. for logging the thread start header).
     pub fn add_thread_logger(&mut self, mut thread_logger: Box<dyn CallLogger>) {
+        // Flush the previous thread's cached FCL updates and buffered std output before the thread start header
+        // (the threads updating in parallel have their output ordered by the update IDs):
+        #[cfg(not(feature = "parallel_logging"))]
+        self.sync_fcl_and_std_output(false);
+
         let (thread_indent_id, thread_indent) = self.thread_indents.check_out();
+        // The thread logging to its own file doesn't need the indent separating it from the other threads:
+        #[cfg(not(feature = "log_merger"))]
+        let thread_indent = if crate::common::thread_log_file::thread_has_log_file() {
+            String::new()
+        } else {
+            thread_indent
+        };
         thread_logger.set_thread_indent(thread_indent);
+        thread_logger.log_thread_start();
+        let current_thread_id = thread::current().id();
+        self.last_fcl_update_thread = Some(current_thread_id);
         if self
             .thread_loggers
-            .insert(thread::current().id(), (thread_logger, thread_indent_id))
+            .insert(current_thread_id, (thread_logger, thread_indent_id))
             .is_some()
         {
             // TODO: Consider outputting to stderr, flushing stderr, and terminating
@@ -292,6 +414,7 @@ impl CallLoggerArbiter {
     }
     /// For the calling thread
     /// * flushes the possible trailing repeat count and standard output,
+    /// * notifies the thread logger about the thread end (e.g. for logging the thread end summary line),
     /// * removes the thread logger, which results in deallocation of the thread-local data,
     /// * vacates the thread indent ID.
     ///
@@ -308,6 +431,13 @@ impl CallLoggerArbiter {
             // Flush the possible trailing repeat count and standard output.
             self.sync_fcl_and_std_output(true);
 
+            // Log the thread end summary line (if enabled):
+            if let Some((logger, ..)) = self.get_thread_logger(current_thread_id) {
+                logger.log_thread_end();
+            }
+            #[cfg(feature = "parallel_logging")]
+            self.merge_thread_logs();
+
             if self.thread_loggers.remove(&current_thread_id).is_none() {
                 // The current function is the exception regarding the invocation of the macro below
                 // since this function is called by the panic hook.
@@ -315,6 +445,23 @@ impl CallLoggerArbiter {
                 LOGGER_LACK_PANIC!();
             }
             self.thread_indents.check_in(thread_indent_id);
+
+            // Ignore the subsequent updates by the thread (e.g. the misleading returns during the stack unwinding):
+            #[cfg(feature = "parallel_logging")]
+            if let Some((infra, update_log)) = self.thread_infras.remove(&current_thread_id) {
+                // The panicking thread can hold its own mutex (if panicking amid an update):
+                match infra.try_lock() {
+                    Ok(mut infra) => infra.set_removed(),
+                    Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner().set_removed(),
+                    Err(TryLockError::WouldBlock) => {}
+                }
+                // The output held back by the merge is merged later:
+                self.removed_update_logs.push(update_log);
+            }
+
+            // Close the thread's own log file, if any:
+            #[cfg(not(feature = "log_merger"))]
+            crate::common::thread_log_file::close_thread_log_file();
         } // else (no logger) The logger for the current thread is  
         // assumed having been removed in the FCL's panic hook, and later this function is called during the
         // thread-local data destruction in the unwinding panic runtime (after the FCL's panic hook).
@@ -368,6 +515,23 @@ impl CallLoggerArbiter {
         // has been logged as a warning in the `set_stdx_sync()` above.
         // Analyze and document below.
 
+        // If the FCL logs to the rotating files, the ring buffer, or the Unix domain socket
+        // then the user's std output is flushed to the same destination:
+        if matches!(
+            writer_kind,
+            WriterKind::RotatingFile | WriterKind::RingBuffer | WriterKind::UnixSocket
+        ) {
+            for redirector in [
+                &mut self.output_sync.stderr_redirector,
+                &mut self.output_sync.stdout_redirector,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                redirector.set_destination(Box::new(WriterAdapter::new(thread_shared_writer.clone())));
+            }
+        }
+
         // TODO: Document in details (in mdBook) that the FCL logs to where it was told to
         // (even if it's stderr or stdout), whereas the user's stderr and stdout
         // are redirected to a buffer (from where the output is flushed to where the FCL logs).
@@ -421,6 +585,7 @@ impl CallLoggerArbiter {
     /// * flushes
     ///   * the FCL's cache
     ///   * and the redirected stdoutput, if any;
+    /// * dumps the FCL's ring buffer writer, if any;
     /// * removes the thread logger for the panicking thread;
     /// * if it is the `main()` thread that is panicking, then reverts the stdandard output redirection.
     ///
@@ -431,6 +596,7 @@ impl CallLoggerArbiter {
     /// If successful then logs the panic info to the `THREAD_SHARED_WRITER`,  
     /// else logs the panic info to the `stdout` and `stderr` even though they both are likely
     /// redirected to a buffer that will hardly be flushed.
+    /// Then dumps the FCL's ring buffer writer, if any, unless it is busy.
     ///
     /// Then the hook calls the default panic hook that
     /// * logs the panic to the `stderr`
@@ -493,11 +659,18 @@ impl CallLoggerArbiter {
                     }
                     #[cfg(not(feature = "log_merger"))]
                     report_to_std(panic_hook_info); // TODO: Test.
+
+                    // Dump the ring buffer (without flushing the FCL's cache that is unavailable):
+                    if let Some(ring) = crate::common::ring_buffer_writer::fcl_ring() {
+                        let _ignore_error = ring.dump_to_destination(&mut std::io::stderr());
+                    }
                 }
             }
             if let Some(mut arbiter) = arbiter {
                 // Flush the panicking thread's FCL cache and standard output redirected to the buffers:
                 arbiter.sync_fcl_and_std_output(true);
+                // Dump the ring buffer (the last calls before the panic), if the FCL logs to it:
+                arbiter.dump_fcl_ring();
                 // Remove the panicking thread's logger to release the thread's heap data
                 // and to prevent subsequent misleading returns logging after the panic report
                 // in case of the unwinding panic runtime
@@ -587,6 +760,7 @@ impl CallLoggerArbiter {
         self.thread_loggers.get_mut(&thread_id)
     }
 
+    #[cfg(not(feature = "parallel_logging"))]
     fn flush_the_logger(&mut self, thread_id: thread::ThreadId) {
         if let Some((logger, ..)) = self.get_thread_logger(thread_id) {
             logger.flush();
@@ -595,9 +769,124 @@ impl CallLoggerArbiter {
         }
     }
 
+    /// Dumps the FCL's ring buffer writer, if any, to its dump file or to the original `stderr`
+    /// (bypassing the `stderr` redirection, if any, since the redirected output lands in the ring buffer).
+    fn dump_fcl_ring(&mut self) {
+        let Some(ring) = crate::common::ring_buffer_writer::fcl_ring() else {
+            return;
+        };
+        #[cfg(feature = "std_output_sync")]
+        let dump_result = match &mut self.output_sync.stderr_redirector {
+            Some(redirector) => ring.dump_to_destination(redirector.get_original_writer()),
+            None => ring.dump_to_destination(&mut std::io::stderr()),
+        };
+        #[cfg(not(feature = "std_output_sync"))]
+        let dump_result = ring.dump_to_destination(&mut std::io::stderr());
+        if let Err(error) = dump_result {
+            eprintln!("Warning: Failed to dump the FCL ring buffer: '{}'", error);
+        }
+    }
+
     /// Synchronizes the FCL's cache and the user's std output.
-    // #[cfg(not(feature = "minimal_writer"))]
+    ///
+    /// With the "parallel_logging" feature merges the logs of all the threads
+    /// after flushing the cache of all the threads (if `full_flush`) or of the calling thread.
     fn sync_fcl_and_std_output(&mut self, full_flush: bool) {
+        #[cfg(feature = "parallel_logging")]
+        self.merge_thread_logs_and_std_output(if full_flush {
+            CacheFlush::AllThreads
+        } else {
+            CacheFlush::CurrentThread
+        });
+        #[cfg(not(feature = "parallel_logging"))]
+        self.sync_last_thread_and_std_output(full_flush);
+    }
+
+    /// Merges the logs of the threads updating their call graphs in parallel (without flushing their cache).
+    ///
+    /// Is called by a thread whose output not yet written to the log has grown large.
+    #[cfg(feature = "parallel_logging")]
+    pub(crate) fn merge_thread_logs(&mut self) {
+        self.merge_thread_logs_and_std_output(CacheFlush::None);
+    }
+
+    /// Writes the buffered std output and then the output of all the threads not yet written to the log,
+    /// in the order of the IDs of the call graph updates that produced it.
+    #[cfg(feature = "parallel_logging")]
+    fn merge_thread_logs_and_std_output(&mut self, cache_flush: CacheFlush) {
+        // The buffered std output has been printed before the updates not yet merged
+        // (the user's `[e]print[ln]!()` in the loggable code merges the logs before the output):
+        #[cfg(feature = "std_output_sync")]
+        {
+            if let Some(redirector) = &mut self.output_sync.stderr_redirector {
+                redirector.flush()
+            }
+            if let Some(redirector) = &mut self.output_sync.stdout_redirector {
+                redirector.flush()
+            }
+        }
+
+        // Flush the cache of the threads whose infrastructure is not busy. The thread amid an update
+        // (e.g. the panicking one, or the one waiting for the gatekeeper) is skipped, its cache is flushed later:
+        let current_thread_id = thread::current().id();
+        for (thread_id, (infra, _update_log)) in &self.thread_infras {
+            if cache_flush == CacheFlush::AllThreads
+                || (cache_flush == CacheFlush::CurrentThread && *thread_id == current_thread_id)
+            {
+                match infra.try_lock() {
+                    Ok(mut infra) => infra.flush_cache(),
+                    Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner().flush_cache(),
+                    Err(TryLockError::WouldBlock) => {}
+                }
+            }
+        }
+
+        // Lock the update logs of all the threads (each held by its thread only briefly), such that no update starts.
+        // The updates with the IDs lower than the lowest ID of the updates in progress are complete,
+        // the subsequent updates will get the higher IDs. The rest of the output is merged later:
+        let mut update_logs = Vec::with_capacity(self.thread_infras.len());
+        for (thread_id, (_infra, update_log)) in &self.thread_infras {
+            let update_log = if *thread_id == current_thread_id {
+                // The panicking thread can hold its own update log's mutex (if panicking amid writing to it):
+                match update_log.try_lock() {
+                    Ok(update_log) => update_log,
+                    Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+                    Err(TryLockError::WouldBlock) => return,
+                }
+            } else {
+                crate::common::parallel::lock(update_log)
+            };
+            update_logs.push(update_log);
+        }
+        for update_log in &self.removed_update_logs {
+            update_logs.push(crate::common::parallel::lock(update_log));
+        }
+        let first_incomplete_update_id = update_logs
+            .iter()
+            .filter_map(|update_log| update_log.update_in_progress())
+            .min()
+            .unwrap_or(u64::MAX);
+        let log = merge_chunks(
+            update_logs
+                .iter_mut()
+                .map(|update_log| update_log.take_chunks_before(first_incomplete_update_id))
+                .collect(),
+        );
+        drop(update_logs);
+        self.removed_update_logs
+            .retain(|update_log| !crate::common::parallel::lock(update_log).is_empty());
+
+        if !log.is_empty() {
+            let mut writer = unsafe { (*THREAD_SHARED_WRITER).borrow_mut() };
+            let _ignore_error = writer.write_all(&log).and_then(|()| writer.flush());
+        }
+    }
+
+    /// Synchronizes the FCL's cache and the user's std output
+    /// by flushing the cache of the previous thread that was updating its log (if different from the calling one).
+    // #[cfg(not(feature = "minimal_writer"))]
+    #[cfg(not(feature = "parallel_logging"))]
+    fn sync_last_thread_and_std_output(&mut self, full_flush: bool) {
         // {Previous thread}'s activity, if any, ended with
         // * either FCL updates (cached or flushed), in which case there's no buffered std output,
         // * or buffered std output {TODO: in which case there's no cached FCL updates, since the std output flushes the cache?}.
@@ -682,11 +971,11 @@ impl CallLoggerArbiter {
                         //     LOGGER_LACK_PANIC!();
                         // }
                     }
-                    // Flush the buffered stderr output (to the original stderr):
+                    // Flush the buffered stderr output (to the original stderr or the destination set):
                     if !stderr_buf_content.is_empty() {
                         if let Some(redirector) = &mut self.output_sync.stderr_redirector {
                             let _ignore_error = redirector
-                                .get_original_writer()
+                                .get_destination_writer()
                                 .write_all(stderr_buf_content.as_bytes());
                             // An error upon redirector flush means that the program (instrumented with this FCL)
                             // has done something with this (redirected) std output handle
@@ -697,11 +986,11 @@ impl CallLoggerArbiter {
                         }
                     }
 
-                    // Flush the buffered stdout output (to the original stdout):
+                    // Flush the buffered stdout output (to the original stdout or the destination set):
                     if !stdout_buf_content.is_empty() {
                         if let Some(redirector) = &mut self.output_sync.stdout_redirector {
                             let _ignore_error = redirector
-                                .get_original_writer()
+                                .get_destination_writer()
                                 .write_all(stdout_buf_content.as_bytes());
                             // An error upon redirector flush means that the program (instrumented with this FCL)
                             // has done something with this (redirected) std output handle
@@ -780,6 +1069,35 @@ impl CallLogger for CallLoggerArbiter {
             LOGGER_LACK_PANIC!();
         }
     }
+    fn set_node_budget(&mut self, node_budget: Option<usize>) {
+        if let Some((logger, ..)) = self.get_thread_logger(thread::current().id()) {
+            logger.set_node_budget(node_budget);
+        } else {
+            LOGGER_LACK_PANIC!();
+        }
+    }
+    fn set_max_sequence_len(&mut self, max_sequence_len: usize) {
+        if let Some((logger, ..)) = self.get_thread_logger(thread::current().id()) {
+            logger.set_max_sequence_len(max_sequence_len);
+        } else {
+            LOGGER_LACK_PANIC!();
+        }
+    }
+    fn set_recursion_threshold(&mut self, recursion_threshold: Option<usize>) {
+        if let Some((logger, ..)) = self.get_thread_logger(thread::current().id()) {
+            logger.set_recursion_threshold(recursion_threshold);
+        } else {
+            LOGGER_LACK_PANIC!();
+        }
+    }
+    #[cfg(feature = "call_timing")]
+    fn set_call_timing(&mut self, call_timing: bool) {
+        if let Some((logger, ..)) = self.get_thread_logger(thread::current().id()) {
+            logger.set_call_timing(call_timing);
+        } else {
+            LOGGER_LACK_PANIC!();
+        }
+    }
 
     fn log_call(
         &mut self,
@@ -834,6 +1152,10 @@ impl CallLogger for CallLoggerArbiter {
         // #[cfg(not(feature = "minimal_writer"))]
         self.sync_fcl_and_std_output(true);
     }
+    fn dump_ring(&mut self) {
+        self.sync_fcl_and_std_output(true);
+        self.dump_fcl_ring();
+    }
     fn log_loopbody_start(&mut self) {
         // #[cfg(not(feature = "minimal_writer"))]
         self.sync_fcl_and_std_output(false);
@@ -894,9 +1216,13 @@ pub static mut CALL_LOGGER_ARBITER: LazyLock<Rc<RefCell<CallLoggerArbiter>>> =
                     },
                 );
                 #[cfg(feature = "std_output_sync")]
-                arbiter.set_std_output_sync();
+                if std_output_sync_is_on() {
+                    arbiter.set_std_output_sync();
+                }
 
-                arbiter.set_panic_sync();
+                if panic_sync_is_on() {
+                    arbiter.set_panic_sync();
+                }
                 arbiter
 
                 // #[cfg(not(feature = "minimal_writer"))]
@@ -999,23 +1325,32 @@ pub mod instances {
         ///
         /// To be used by the users to replace the default writer with the custom one.
         pub static THREAD_DECORATOR: Rc<RefCell<dyn LogDecorator>> = /*unsafe*/ {
-            #[cfg(feature = "log_merger")]
+            // Apply the `fcl.toml` and `FCL_*` environment variables configuration (upon the first access
+            // to the settings) before the writer creation, and reject the subsequent `fcl::Builder::init()`:
+            crate::common::builder::set_infra_is_created();
+
+            #[cfg(all(feature = "log_merger", not(feature = "parallel_logging")))]
             let writer: Option<Box<dyn Write>> = unsafe { Some(Box::new(WriterAdapter::new((*THREAD_SHARED_WRITER).clone()))) };
+            // The thread's output is merged with the other threads' one by the arbiter
+            // (see `crate::common::parallel`):
+            #[cfg(feature = "parallel_logging")]
+            let writer: Option<Box<dyn Write>> = Some(crate::common::parallel::new_thread_update_log_writer());
+            // Each thread logs to its own file (see `crate::common::settings::set_thread_log_file_pattern()`):
             #[cfg(not(feature = "log_merger"))]
-            let writer: Option<Box<dyn Write>> = None;
+            let writer: Option<Box<dyn Write>> = crate::common::thread_log_file::open_thread_log_file();
             // #[cfg(not(feature = "minimal_writer"))]
             // let writer: Option<Box<dyn Write>> = Some(Box::new(WriterAdapter::new((*THREAD_SHARED_WRITER).clone())));
             // #[cfg(feature = "minimal_writer")]
             // let writer: Option<Box<dyn Write>> = None;
 
-            std::rc::Rc::new(std::cell::RefCell::new(
-                // TODO: Consider making the default decorator type a macro in a separate file of defaults.
-                // crate::decorators::TreeLikeDecorator::new(
-                //     writer,
-                //     None, None, None))))))
-                crate::common::decorators::CodeLikeDecorator::new(
-                    writer,
-                    None)))
+            // The colors (if `AnsiColors::Auto`) are used only if the log ends up in a terminal:
+            #[cfg(feature = "log_merger")]
+            let writer_is_terminal = crate::common::call_log_infra::writer::thread_shared_writer_is_terminal();
+            #[cfg(not(feature = "log_merger"))]
+            let writer_is_terminal = writer.is_none() && crate::common::decorators::stdout_is_terminal();
+
+            // The decorator kind is selected with `crate::common::settings::set_decorator_kind()`.
+            crate::common::decorators::new_decorator(writer, writer_is_terminal)
         };
 
 
@@ -1023,8 +1358,13 @@ pub mod instances {
         /// * the function and closure calls and returns
         /// * and loop body begins and ends.
         pub static THREAD_LOGGER: crate::common::call_log_infra::instances::ThreadLoggerType /*RefCell<Rc<RefCell<CallLoggerArbiter>>>*/ = unsafe {
-            let logging_infra = Box::new(CallLogInfra::new(
+            let mut logging_infra = Box::new(CallLogInfra::new(
                 THREAD_DECORATOR.with(|decorator| decorator.clone())));
+            let config = crate::common::config::config();
+            logging_infra.set_exclude(config.exclude.clone());
+            if let Some(logging_is_on) = config.logging_is_on {
+                logging_infra.push_logging_is_on(logging_is_on);
+            }
 
             #[cfg(feature = "single_threaded")]
             {
@@ -1032,7 +1372,17 @@ pub mod instances {
 
                 RefCell::new((*CALL_LOGGER_ARBITER).clone())
             }
-            #[cfg(feature = "multithreaded")]
+            // The thread updates its call graph under its own mutex, in parallel with the other threads:
+            #[cfg(feature = "parallel_logging")]
+            {
+                use crate::common::parallel::{ParallelInfra, ParallelThreadLogger, lock, take_thread_update_log};
+                let update_log = take_thread_update_log();
+                let infra = std::sync::Arc::new(std::sync::Mutex::new(ParallelInfra::new(*logging_infra, update_log.clone())));
+                let gatekeeper = (*crate::common::multithreaded::THREAD_GATEKEEPER).clone();
+                lock(&gatekeeper).add_parallel_thread_logger(infra.clone(), update_log);
+                RefCell::new(Box::new(ParallelThreadLogger::new(infra, gatekeeper)))
+            }
+            #[cfg(all(feature = "multithreaded", not(feature = "parallel_logging")))]
             {
                 match (*crate::common::multithreaded::THREAD_GATEKEEPER).lock() {
                     Ok(mut gatekeeper) => gatekeeper.add_thread_logger(logging_infra),
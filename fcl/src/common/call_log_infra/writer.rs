@@ -1,15 +1,35 @@
 use std::{
     cell::RefCell,
-    io::{Write, stderr, stdout},
-    sync::{Arc, LazyLock},
+    io::{IsTerminal, Write, stderr, stdout},
+    sync::{
+        Arc, LazyLock,
+        atomic::{AtomicBool, Ordering},
+    },
 };
 
+use crate::common::decorators::stdout_is_terminal;
+use crate::common::defaults;
+use crate::common::ring_buffer_writer::{RingBufferWriter, set_fcl_ring};
+use crate::common::rotating_file_writer::RotatingFileWriter;
+#[cfg(unix)]
+use crate::common::unix_socket_writer::UnixSocketWriter;
+
 /// Specifies the instance used by the FCL for logging.
 pub enum FclWriter {
     /// The FCL uses `stdout` for logging.
     Stdout,
     /// The FCL uses `stderr` for logging.
     Stderr,
+    /// The FCL uses the rotating files for logging.
+    /// The user's `stdout` and `stderr` output (synchronized with the log) lands in the same files.
+    RotatingFile(RotatingFileWriter),
+    /// The FCL uses the in-memory ring buffer for logging, that is dumped by the FCL's panic hook and `fcl::dump_ring()`.
+    /// The user's `stdout` and `stderr` output (synchronized with the log) lands in the same ring buffer.
+    RingBuffer(RingBufferWriter),
+    /// The FCL streams the log over a Unix domain socket to a viewer (e.g. `fcl-tail`).
+    /// The user's `stdout` and `stderr` output (synchronized with the log) is streamed to the same viewer.
+    #[cfg(unix)]
+    UnixSocket(UnixSocketWriter),
     /// The FCL uses for logging some other instance implementing `Write`.
     Other(Box<dyn Write>),
 }
@@ -19,9 +39,52 @@ pub enum FclWriter {
 pub enum WriterKind {
     Stdout,
     Stderr,
+    RotatingFile,
+    RingBuffer,
+    UnixSocket,
     Other,
 }
 
+thread_local! {
+    /// The writer set with `set_fcl_writer()` by the thread, if any, to be taken upon the `THREAD_SHARED_WRITER` creation.
+    /// Is thread-local (rather than a part of the `Settings`) since the `FclWriter` is not necessarily `Send`.
+    static PENDING_FCL_WRITER: RefCell<Option<FclWriter>> = const { RefCell::new(None) };
+}
+
+/// Sets the writer to be taken upon the `THREAD_SHARED_WRITER` creation by the calling thread.
+#[cfg(feature = "log_merger")]
+pub(crate) fn set_pending_fcl_writer(fcl_writer: FclWriter) {
+    PENDING_FCL_WRITER.with(|pending| *pending.borrow_mut() = Some(fcl_writer));
+}
+
+/// Returns whether the `THREAD_SHARED_WRITER` (created if not yet) writes to a terminal
+/// (`stdout` or `stderr` connected to a terminal).
+#[cfg(feature = "log_merger")]
+pub(crate) fn thread_shared_writer_is_terminal() -> bool {
+    create_thread_shared_writer();
+    THREAD_SHARED_WRITER_IS_TERMINAL.load(Ordering::Relaxed)
+}
+
+/// Creates the `THREAD_SHARED_WRITER` (if not yet created), taking the writer set by the calling thread.
+#[cfg(feature = "log_merger")]
+pub(crate) fn create_thread_shared_writer() {
+    unsafe { LazyLock::force(&*std::ptr::addr_of!(THREAD_SHARED_WRITER)) };
+}
+
+/// Returns the writer of the log output specified in the configuration (see `crate::common::config::Config`), if any
+/// (with the "log_merger" feature off the configured output is the per-thread log files).
+fn take_config_fcl_writer() -> Option<FclWriter> {
+    #[cfg(feature = "log_merger")]
+    return crate::common::config::config().fcl_writer();
+    #[cfg(not(feature = "log_merger"))]
+    None
+}
+
+/// Takes the writer set with `set_fcl_writer()` by the calling thread, if any.
+fn take_fcl_writer() -> Option<FclWriter> {
+    PENDING_FCL_WRITER.with(|pending| pending.borrow_mut().take())
+}
+
 // TODO: Consider removing `Shared` from the type name. There is nothing sharing-specific. Nothing
 // prevents different threads from having separate instances of this Writer.
 // TODO: Consider Thread[Shared]Writer -> LogWriter (since not only threads write/log to it but also the user code's std output)
@@ -39,12 +102,27 @@ pub struct ThreadSharedWriter {
 impl ThreadSharedWriter {
     /// Creates the new `ThreadSharedWriter` with the writer passed as an argument.
     /// If the argument is `None` then the `std::io::stdio::stdout()` is used.
+    /// Records whether the writer is `stdout` or `stderr` connected to a terminal
+    /// (see `thread_shared_writer_is_terminal()`).
     pub fn new(fcl_writer: Option<FclWriter>) -> Self {
+        let is_terminal = match fcl_writer {
+            None | Some(FclWriter::Stdout) => stdout_is_terminal(),
+            Some(FclWriter::Stderr) => stderr().is_terminal(),
+            Some(_) => false,
+        };
+        THREAD_SHARED_WRITER_IS_TERMINAL.store(is_terminal, Ordering::Relaxed);
         let (writer, _writer_kind): (Box<dyn Write>, WriterKind) = match fcl_writer {
-            None => (Box::new(stdout()), WriterKind::Stdout),   // TODO: Consider extracting `stdout()` to a file of defaults.
+            None => (defaults::writer(), WriterKind::Stdout),
             Some(writer) => match writer {
                 FclWriter::Stdout => (Box::new(stdout()), WriterKind::Stdout),
                 FclWriter::Stderr => (Box::new(stderr()), WriterKind::Stderr),
+                FclWriter::RotatingFile(rotating_file_writer) => (Box::new(rotating_file_writer), WriterKind::RotatingFile),
+                FclWriter::RingBuffer(ring_buffer_writer) => {
+                    set_fcl_ring(ring_buffer_writer.clone());
+                    (Box::new(ring_buffer_writer), WriterKind::RingBuffer)
+                }
+                #[cfg(unix)]
+                FclWriter::UnixSocket(unix_socket_writer) => (Box::new(unix_socket_writer), WriterKind::UnixSocket),
                 FclWriter::Other(non_std_writer) => (non_std_writer, WriterKind::Other),
             },
         };
@@ -61,6 +139,7 @@ impl ThreadSharedWriter {
     }
     #[cfg(feature = "std_output_sync")]
     pub fn set_writer(&mut self, file: std::fs::File) {
+        THREAD_SHARED_WRITER_IS_TERMINAL.store(false, Ordering::Relaxed);
         self.override_writer = Some(file); // TODO: What about updating `writer_kind`? Isn't that update 
         // required to preserve the invariant of `writer_kind: WriterKind::Other`?
     }
@@ -85,7 +164,10 @@ impl Write for ThreadSharedWriter {
 
 pub type ThreadSharedWriterPtr = Arc<RefCell<ThreadSharedWriter>>; // TODO: Consider -> Arc<RefCell<dyn Write>>.
 
-#[cfg(feature = "log_merger")]
+#[cfg(any(
+    all(feature = "log_merger", not(feature = "parallel_logging")),
+    feature = "std_output_sync"
+))]
 /// The adapter for the writer.
 /// 
 /// Is used per-thread in the environements with the writer access sinchronization, 
@@ -96,7 +178,10 @@ pub struct WriterAdapter {
     writer: ThreadSharedWriterPtr,
 }
 
-#[cfg(feature = "log_merger")]
+#[cfg(any(
+    all(feature = "log_merger", not(feature = "parallel_logging")),
+    feature = "std_output_sync"
+))]
 impl WriterAdapter {
     /// Creates new `WriterAdapter` with the writer passed as an argument.
     pub fn new(writer: ThreadSharedWriterPtr) -> Self {
@@ -104,7 +189,10 @@ impl WriterAdapter {
     }
 }
 
-#[cfg(feature = "log_merger")]
+#[cfg(any(
+    all(feature = "log_merger", not(feature = "parallel_logging")),
+    feature = "std_output_sync"
+))]
 impl Write for WriterAdapter {
     /// Forwards the call to the writer's `Write::write()`.
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
@@ -122,8 +210,13 @@ impl Write for WriterAdapter {
 // but also by the user code's stdout and astderr output.
 pub static mut THREAD_SHARED_WRITER: LazyLock<ThreadSharedWriterPtr> = LazyLock::new(|| {
     Arc::new(RefCell::new(ThreadSharedWriter::new(Some(
-        FclWriter::Stdout, // TODO: Consider either `None` or 
+        take_fcl_writer()
+            .or_else(take_config_fcl_writer)
+            .unwrap_or(FclWriter::Stdout), // TODO: Consider either `None` or 
         // fully creating the writer outside of ThreadSharedWriter and passing to ThreadSharedWriter::new().
         // Such that the ThreadSharedWriter works with whatever `dyn Write` provided from outside.
     ))))
 });
+
+/// Whether the `THREAD_SHARED_WRITER` writes to a terminal (is determined upon the `ThreadSharedWriter` creation).
+static THREAD_SHARED_WRITER_IS_TERMINAL: AtomicBool = AtomicBool::new(false);
@@ -0,0 +1,312 @@
+use std::sync::OnceLock;
+
+use crate::common::decorators::DecoratorKind;
+use crate::common::defaults::CONFIG_FILE_NAME;
+use crate::common::settings::Settings;
+
+
+/// The FCL configuration read upon the first logged call (see `config()`)
+/// from the `fcl.toml` file (with the "config_file" feature) and the `FCL_*` environment variables,
+/// such that the logging can be changed without recompiling.
+///
+/// The configuration is applied to the settings upon their first access (see `fcl::common::settings`),
+/// i.e. before any setting is made in the code, such that the settings made in the code
+/// (the `set_*()` functions and `fcl::Builder`) take precedence over the configuration.
+/// The settings absent both in the code and in the configuration keep the defaults.
+/// The log output is created upon the first logged call (by any thread) unless the writer is set in the code.
+///
+/// | `fcl.toml` key       | Environment variable     | Value |
+/// |----------------------|--------------------------|-------|
+/// | `decorator`          | `FCL_DECORATOR`          | `code_like`, `tree_like`, `json_lines`, `chrome_trace`, `folded_stack`, `html`, `sequence_diagram`, `dot_graph`, `binary_trace`, `tracing` |
+/// | `output`             | `FCL_OUTPUT`             | `stdout`, `stderr`, `ring:<capacity in bytes>`, `unix:<socket path>`, or a file path |
+/// | `indent_step`        | `FCL_INDENT_STEP`        | The number of spaces of the function indent step |
+/// | `thread_indent_step` | `FCL_THREAD_INDENT_STEP` | The number of spaces of the thread indent step |
+/// | `logging_is_on`      | `FCL_LOGGING_IS_ON`      | `true` or `false`, whether the logging is on by default |
+/// | `thread_headers`     | `FCL_THREAD_HEADERS`     | `true` or `false`, whether the thread start header and thread end summary lines are logged |
+/// | `exclude`            | `FCL_EXCLUDE`            | The patterns (array or comma-separated) of the function and closure names not logged along with the calls they make, `*` matches any characters |
+///
+/// #### Examples
+/// ```toml
+/// # fcl.toml
+/// decorator = "tree_like"
+/// output = "fcl.log"
+/// exclude = ["log_*", "*::closure*"]
+/// ```
+/// ```sh
+/// FCL_DECORATOR=json_lines FCL_OUTPUT=stderr cargo run
+/// ```
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Config {
+    /// The kind of the log decorator created for each thread.
+    pub decorator_kind: Option<DecoratorKind>,
+    /// The log output.
+    pub output: Option<String>,
+    /// The number of spaces of the function indent step (of the `CodeLikeDecorator`).
+    pub indent_step: Option<usize>,
+    /// The number of spaces of the thread indent step.
+    pub thread_indent_step: Option<usize>,
+    /// Whether the logging is on by default.
+    pub logging_is_on: Option<bool>,
+    /// Whether the thread start header and thread end summary lines are logged.
+    pub thread_headers: Option<bool>,
+    /// The patterns of the function and closure names that are not logged along with the calls they make.
+    pub exclude: Vec<String>,
+}
+
+impl Config {
+    /// Sets the setting with the `key` (e.g. `"decorator"`) parsed from the `value`.
+    ///
+    /// Returns the error message if the key is unknown or the value is invalid.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        fn width(key: &str, value: &str) -> Result<usize, String> {
+            value
+                .trim()
+                .parse()
+                .map_err(|_error| format!("Invalid `{}` '{}', the number of spaces expected", key, value))
+        }
+        fn flag(key: &str, value: &str) -> Result<bool, String> {
+            match value.trim() {
+                "true" | "on" | "1" => Ok(true),
+                "false" | "off" | "0" => Ok(false),
+                _ => Err(format!("Invalid `{}` '{}', `true` or `false` expected", key, value)),
+            }
+        }
+        match key {
+            "decorator" => self.decorator_kind = Some(value.trim().parse()?),
+            "output" => self.output = Some(String::from(value.trim())),
+            "indent_step" => self.indent_step = Some(width(key, value)?),
+            "thread_indent_step" => self.thread_indent_step = Some(width(key, value)?),
+            "logging_is_on" => self.logging_is_on = Some(flag(key, value)?),
+            "thread_headers" => self.thread_headers = Some(flag(key, value)?),
+            "exclude" => {
+                self.exclude = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|pattern| !pattern.is_empty())
+                    .map(String::from)
+                    .collect()
+            }
+            _ => return Err(format!("Unknown setting `{}`", key)),
+        }
+        Ok(())
+    }
+    /// Updates the configuration with the `FCL_*` environment variables
+    /// (e.g. `FCL_DECORATOR` for the `decorator` setting) among the `vars` passed (e.g. `std::env::vars()`).
+    ///
+    /// Returns the error messages for the unknown variables and invalid values (that are ignored).
+    pub fn update_from_env_vars(&mut self, vars: impl IntoIterator<Item = (String, String)>) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (name, value) in vars {
+            let Some(key) = name.strip_prefix("FCL_") else {
+                continue;
+            };
+            if name == "FCL_CONFIG" {
+                continue;
+            }
+            if let Err(error) = self.set(&key.to_lowercase(), &value) {
+                errors.push(format!("{}: {}", name, error));
+            }
+        }
+        errors
+    }
+    /// Updates the configuration with the content of the `fcl.toml` file.
+    ///
+    /// Returns the error messages for the syntax errors, unknown keys, and invalid values (that are ignored).
+    #[cfg(feature = "config_file")]
+    pub fn update_from_toml(&mut self, toml: &str) -> Vec<String> {
+        let table = match toml.parse::<toml::Table>() {
+            Ok(table) => table,
+            Err(error) => return vec![error.to_string()],
+        };
+        let mut errors = Vec::new();
+        for (key, value) in table {
+            fn to_string(value: &toml::Value) -> Option<String> {
+                match value {
+                    toml::Value::String(string) => Some(string.clone()),
+                    toml::Value::Integer(integer) => Some(integer.to_string()),
+                    toml::Value::Boolean(boolean) => Some(boolean.to_string()),
+                    toml::Value::Array(array) => array
+                        .iter()
+                        .map(to_string)
+                        .collect::<Option<Vec<_>>>()
+                        .map(|items| items.join(",")),
+                    _ => None,
+                }
+            }
+            let result = match to_string(&value) {
+                Some(value) => self.set(&key, &value),
+                None => Err(format!("Unsupported value type of `{}`", key)),
+            };
+            if let Err(error) = result {
+                errors.push(error);
+            }
+        }
+        errors
+    }
+    /// Reads the configuration from the `fcl.toml` file (with the "config_file" feature; the path can be overridden
+    /// with the `FCL_CONFIG` environment variable) and then from the `FCL_*` environment variables
+    /// (that take precedence over the file).
+    ///
+    /// The errors are reported to `stderr` and the corresponding settings are ignored.
+    pub fn load() -> Self {
+        let mut config = Self::default();
+        let mut errors = Vec::new();
+        #[cfg(feature = "config_file")]
+        {
+            let path = std::env::var("FCL_CONFIG").unwrap_or(String::from(CONFIG_FILE_NAME));
+            match std::fs::read_to_string(&path) {
+                Ok(toml) => errors.extend(
+                    config
+                        .update_from_toml(&toml)
+                        .into_iter()
+                        .map(|error| format!("{}: {}", path, error)),
+                ),
+                Err(error) => {
+                    // The absent default file is not an error:
+                    if std::env::var("FCL_CONFIG").is_ok() || error.kind() != std::io::ErrorKind::NotFound {
+                        errors.push(format!("{}: {}", path, error))
+                    }
+                }
+            }
+        }
+        #[cfg(not(feature = "config_file"))]
+        if std::env::var("FCL_CONFIG").is_ok() || std::path::Path::new(CONFIG_FILE_NAME).exists() {
+            errors.push(String::from(
+                "The configuration file is ignored since the \"config_file\" feature is off",
+            ));
+        }
+        errors.extend(config.update_from_env_vars(std::env::vars()));
+        for error in errors {
+            eprintln!("Warning: FCL configuration: {}", error);
+        }
+        config
+    }
+    /// Applies the settings present in the configuration to the process-wide FCL `settings`
+    /// (the decorator kind, the indent steps, the thread headers, and with the "log_merger" feature off the log output).
+    /// Is called upon the first access to the settings (before any setting is made in the code).
+    ///
+    /// With the "log_merger" feature the log output is created upon the `THREAD_SHARED_WRITER` creation
+    /// (see `fcl_writer()`).
+    ///
+    /// The default logging on/off state and the exclusions are taken from the configuration
+    /// upon the thread loggers creation.
+    pub(crate) fn apply(&self, settings: &mut Settings) {
+        if let Some(decorator_kind) = self.decorator_kind {
+            settings.decorator_kind = decorator_kind;
+        }
+        if let Some(indent_step) = self.indent_step {
+            settings.indent_step = Some(" ".repeat(indent_step));
+        }
+        if let Some(thread_indent_step) = self.thread_indent_step {
+            settings.thread_indent_step = Some(" ".repeat(thread_indent_step));
+        }
+        if let Some(thread_headers) = self.thread_headers {
+            settings.thread_headers = thread_headers;
+        }
+        #[cfg(not(feature = "log_merger"))]
+        if let Some(output) = &self.output
+            && let Err(error) = apply_output(output, settings)
+        {
+            eprintln!("Warning: FCL configuration: output '{}': {}", output, error);
+        }
+    }
+    /// Creates the writer of the log output specified in the configuration, if any (and valid).
+    ///
+    /// Is called upon the `THREAD_SHARED_WRITER` creation (by whichever thread makes it)
+    /// unless the writer has been set in the code (e.g. with `fcl::Builder`).
+    #[cfg(feature = "log_merger")]
+    pub(crate) fn fcl_writer(&self) -> Option<crate::common::call_log_infra::FclWriter> {
+        let output = self.output.as_ref()?;
+        new_fcl_writer(output)
+            .map_err(|error| eprintln!("Warning: FCL configuration: output '{}': {}", output, error))
+            .ok()
+    }
+}
+
+/// Creates the writer of the log output specified in the configuration.
+#[cfg(feature = "log_merger")]
+fn new_fcl_writer(output: &str) -> Result<crate::common::call_log_infra::FclWriter, String> {
+    use crate::common::call_log_infra::FclWriter;
+    use crate::common::ring_buffer_writer::RingBufferWriter;
+    use crate::common::rotating_file_writer::{FileRotation, RotatingFileWriter};
+
+    let fcl_writer = if output == "stdout" {
+        FclWriter::Stdout
+    } else if output == "stderr" {
+        FclWriter::Stderr
+    } else if let Some(capacity) = output.strip_prefix("ring:") {
+        let capacity = capacity
+            .parse()
+            .map_err(|_error| String::from("The ring buffer capacity in bytes expected"))?;
+        FclWriter::RingBuffer(RingBufferWriter::new(capacity, None))
+    } else if let Some(path) = output.strip_prefix("unix:") {
+        #[cfg(unix)]
+        {
+            FclWriter::UnixSocket(
+                crate::common::unix_socket_writer::UnixSocketWriter::new(path, crate::common::defaults::UNIX_SOCKET_BACKLOG_CAPACITY)
+                    .map_err(|error| error.to_string())?,
+            )
+        }
+        #[cfg(not(unix))]
+        {
+            let _unused = path;
+            return Err(String::from("The Unix domain sockets are not supported on this platform"));
+        }
+    } else {
+        // The file that is not rotated:
+        let rotation = FileRotation { max_size: None, max_age: None, generations: 0 };
+        FclWriter::RotatingFile(RotatingFileWriter::new(output, rotation).map_err(|error| error.to_string())?)
+    };
+    Ok(fcl_writer)
+}
+
+/// Sets the log output specified in the configuration
+/// (`stdout` or the per-thread log file name pattern, see `crate::common::thread_log_file`).
+#[cfg(not(feature = "log_merger"))]
+fn apply_output(output: &str, settings: &mut Settings) -> Result<(), String> {
+    if output == "stdout" {
+        settings.thread_log_file_pattern = None;
+    } else if output == "stderr" || output.starts_with("ring:") || output.starts_with("unix:") {
+        return Err(String::from(
+            "Only `stdout` or the per-thread log file name pattern is supported with the \"log_merger\" feature off",
+        ));
+    } else {
+        settings.thread_log_file_pattern = Some(String::from(output).into());
+    }
+    Ok(())
+}
+
+/// The configuration read upon the first use.
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Returns the FCL configuration (read upon the first call, see `Config::load()`).
+pub fn config() -> &'static Config {
+    CONFIG.get_or_init(Config::load)
+}
+
+/// Returns whether the function or closure `name` matches the `pattern`
+/// where `*` matches any sequence of characters (including the empty one).
+/// #### Examples
+/// ```rs
+/// use fcl::common::config::name_matches;
+/// assert!(name_matches("log_*", "log_value"));
+/// assert!(name_matches("*::closure*", "f()::closure{1,1:1,0}"));
+/// ```
+pub fn name_matches(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty(); // No `*` in the pattern.
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
@@ -1,10 +1,23 @@
 use std::{
     cell::RefCell,
-    io::{Write, stdout},
+    io::{IsTerminal, Write, stdout},
     rc::Rc,
+    sync::{
+        OnceLock,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    time::{Instant, SystemTime, UNIX_EPOCH},
 };
 
-use code_commons::{CoderunNotifiable, ItemKind, RepeatCountCategory};
+use code_commons::CoderunNotifiable;
+
+use crate::common::defaults::{self, FOLDING_SUSPENDED_NOTE, LOOPBODY_NAME, RECURSION_DEPTH_LABEL};
+use crate::common::settings::{
+    ansi_colors, decorator_factory, decorator_kind, dot_graph_output, indent_step, line_prefixes, tee_outputs,
+    thread_headers, tree_style,
+};
+#[cfg(feature = "call_timing")]
+use code_commons::DurationStats;
 
 /// Trait to be implemented by the instances that handle any thread specifics, e.g. the thread indent.
 /// 
@@ -16,6 +29,18 @@ pub trait ThreadSpecific {
     /// and the other thread's output can be logged in the right half,
     /// or _indented_ by half of the console width.
     fn set_thread_indent(&mut self, thread_indent: String);
+    /// Notifies about the start of logging the `thread`'s calls (before the thread's first logged call).
+    ///
+    /// The decorator can log a header in the thread's "column", e.g. `==== thread 'worker-3' (#4) started ====`.
+    fn notify_thread_start(&mut self, _thread: &std::thread::Thread) {}
+    /// Notifies about the end of logging the `thread`'s calls (upon the thread's exit or panic).
+    ///
+    /// The decorator can log a summary line in the thread's "column",
+    /// e.g. `==== thread 'worker-3' (#4) ended, 15 call(s) ====`.
+    /// # Parameters
+    /// * The thread.
+    /// * The number of the thread's logged calls.
+    fn notify_thread_end(&mut self, _thread: &std::thread::Thread, _call_count: usize) {}
 }
 
 /// Trait to be implemented by the writer possessing types.
@@ -47,21 +72,50 @@ enum Writer {
     Substitute(Rc<RefCell<dyn Write>>),
 }
 
+/// The optional prefixes of each line logged by the text decorators (`CodeLikeDecorator`, `TreeLikeDecorator`),
+/// for correlating the log with the other logs. For example, all the prefixes on:
+/// ```txt
+/// [+0.001234s] [12:34:56.789] [main#1] f() {
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct LinePrefixes {
+    /// The monotonic time elapsed since the log start, e.g. `[+0.001234s]`.
+    pub elapsed: bool,
+    /// The wall-clock time of the day (UTC), e.g. `[12:34:56.789]`.
+    pub wall_clock: bool,
+    /// The thread name and ID, e.g. `[main#1]` (`[#2]` if the thread is unnamed).
+    pub thread: bool,
+}
+
+/// The moment the monotonic timestamps are counted from (the first decorator creation).
+static LOG_START: OnceLock<Instant> = OnceLock::new();
+
 /// Common part of the log decorators.
 struct CommonDecorator {
     /// The pointer to a writer.
     writer: Writer,
     /// The thread indent for visual separation of different thread logs into different "columns".
     thread_indent: String,
+    /// The optional prefixes of each line (timestamps, thread name and ID).
+    line_prefixes: LinePrefixes,
+    /// The name and ID of the thread that has created the decorator (e.g. `main#1`), used in the line prefix.
+    thread_label: String,
+    /// Tells whether the thread start header and thread end summary lines are logged.
+    thread_headers: bool,
 }
 impl CommonDecorator {
     /// Creates a new `CommonDecorator` with the optional writer passed as an argument.
     /// If `None` then `std::io::stdio::stdout()` is used as a writer.
     /// An emtpy string is used as a thread indent.
     fn new(writer: Option<Box<dyn Write>>) -> Self {
+        LOG_START.get_or_init(Instant::now);
+        let thread = std::thread::current();
         Self {
-            writer: Writer::Original(writer.unwrap_or(Box::new(stdout()))), // TODO: Move `stdout()` to a separate file of defaults.
+            writer: Writer::Original(writer.unwrap_or_else(defaults::writer)),
             thread_indent: String::from(""),
+            line_prefixes: LinePrefixes::default(),
+            thread_label: format!("{}#{}", thread.name().unwrap_or(""), thread_id_number(&thread)),
+            thread_headers: false,
         }
     }
 
@@ -69,9 +123,55 @@ impl CommonDecorator {
     fn set_thread_indent(&mut self, thread_indent: String) {
         self.thread_indent = thread_indent;
     }
-    /// Returns the thread indent.
-    fn get_thread_indent(&self) -> String {
-        self.thread_indent.clone()
+    /// Replaces the line prefixes with the ones passed as an argument.
+    fn set_line_prefixes(&mut self, line_prefixes: LinePrefixes) {
+        self.line_prefixes = line_prefixes;
+    }
+    /// Enables or disables logging the thread start header and thread end summary lines.
+    fn set_thread_headers(&mut self, thread_headers: bool) {
+        self.thread_headers = thread_headers;
+    }
+    /// Returns the thread start header or thread end summary line (with the line start, without the `'\n'`),
+    /// e.g. `==== thread 'worker-3' (#4) started ====` or `==== thread (#5) ended, 15 call(s) ====` (for an unnamed thread).
+    /// # Parameters
+    /// * The thread.
+    /// * The event, e.g. `"started"` or `"ended, 15 call(s)"`.
+    fn get_thread_header(&self, thread: &std::thread::Thread, event: &str) -> String {
+        format!(
+            "{}==== thread {}(#{}) {} ====",
+            self.get_line_start(),
+            thread.name().map_or(String::new(), |name| format!("'{}' ", name)),
+            thread_id_number(thread),
+            event
+        )
+    }
+    /// Returns the start of a line: the line prefixes (if any) followed by the thread indent,
+    /// e.g. `[+0.001234s] [12:34:56.789] [main#1] ` followed by the thread indent.
+    ///
+    /// The timestamps are taken at the moment of logging the line (the lines of the repeated calls
+    /// cached by the call graph are logged upon the repeat end or flush).
+    fn get_line_start(&self) -> String {
+        let mut line_start = String::new();
+        if self.line_prefixes.elapsed {
+            let elapsed = LOG_START.get_or_init(Instant::now).elapsed();
+            line_start.push_str(&format!("[+{}.{:06}s] ", elapsed.as_secs(), elapsed.subsec_micros()));
+        }
+        if self.line_prefixes.wall_clock {
+            let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+            let day_secs = since_epoch.as_secs() % (24 * 60 * 60);
+            line_start.push_str(&format!(
+                "[{:02}:{:02}:{:02}.{:03}] ", // UTC.
+                day_secs / (60 * 60),
+                day_secs / 60 % 60,
+                day_secs % 60,
+                since_epoch.subsec_millis()
+            ));
+        }
+        if self.line_prefixes.thread {
+            line_start.push_str(&format!("[{}] ", self.thread_label));
+        }
+        line_start.push_str(&self.thread_indent);
+        line_start
     }
 
     /// Replaces the writer pointer with the one passed as an argument.
@@ -97,354 +197,246 @@ macro_rules! decorator_write {  // TODO: Condsider renaming to `writer_write()`.
     }};
 }
 
-/// The decorator that logs the function calls in a code-like manner (as opposed to a tree-like manner).
+/// Returns the string representation of the repeats' duration statistics
+/// (e.g. `", total 12.3ms, min 10.1µs, avg 12.3µs, max 400.2µs"`), or an empty string if there are no statistics.
+#[cfg(feature = "call_timing")]
+fn timing_string(timing: &DurationStats) -> String {
+    if timing.is_empty() {
+        return String::new();
+    }
+    format!(
+        ", total {:.1?}, min {:.1?}, avg {:.1?}, max {:.1?}",
+        timing.total(),
+        timing.min(),
+        timing.avg(),
+        timing.max()
+    )
+}
+
+/// Returns the number of the thread ID (e.g. `"1"` for `ThreadId(1)`).
+fn thread_id_number(thread: &std::thread::Thread) -> String {
+    format!("{:?}", thread.id()) // "ThreadId(1)".
+        .trim_start_matches("ThreadId(")
+        .trim_end_matches(')') // "1".
+        .to_string()
+}
+
+/// The tracker of whether the output start (e.g. the document header) has been logged.
 ///
-/// For example,
-/// ```txt
-/// f() {
-///     g() {}
-///     // g() repeats 3 time(s).
-///     h() {}
-/// }
-/// ```
-pub struct CodeLikeDecorator {
-    /// The part (of the decorator) common for multiple decorators.
-    common: CommonDecorator,
-    /// The indent step used for indenting functions with different call depth.
-    ///
-    /// Typically consists of a single Tab character (`'\t'`) or multiple spaces,
-    /// but can be any `'static` string slice.
-    indent_step: &'static str, // TODO (func_indent): Consider -> func_indent_step (to clearly distinguish from the thread_indent).
-    /// Tells that the line end `'\n'` is pending after `f() {` before
-    /// * loggign a nested call,
-    /// * or a different thread's output,
-    /// * or an `stdout` and/or `stderr` output by a user's code or panic hook.
-    line_end_pending: bool, // '\n' pending after "f() {" before printing a nested call.
+/// Multiple threads' decorators write to the shared output, and the output start is logged
+/// by the first one. But after the writer substitution the decorator writes to a separate output
+/// and logs its own output start.
+struct OutputStart {
+    /// Whether the output start has been logged to the shared output.
+    shared_logged: &'static AtomicBool,
+    /// Whether the output start has been logged to the decorator's own (substitute) output,
+    /// `None` if the shared output is used.
+    own_logged: Option<bool>,
 }
 
-impl CodeLikeDecorator {
-    /// Creates a new `CodeLikeDecorator` with the optional writer and optional indent step.
-    /// * If the writer is `None` then uses the one in the `CommonDecorator`.
-    /// * If the indent step is `None` then uses 2 spaces.
-    pub fn new(writer: Option<Box<dyn Write>>, indent_step: Option<&'static str>) -> Self {
+impl OutputStart {
+    /// Creates a new `OutputStart` for the shared output.
+    fn new(shared_logged: &'static AtomicBool) -> Self {
         Self {
-            common: CommonDecorator::new(writer),
-            indent_step: indent_step.unwrap_or(&"  "), // TODO: Move the default (&"  ") to a separate file of defaults.
-            line_end_pending: false,
+            shared_logged,
+            own_logged: None,
         }
     }
-    /// Returns the indent string for the specified call depth. In other words, a string containing
-    /// `self.indent_step` `call_depth` times.
-    fn get_indent_string(&self, call_depth: usize) -> String {
-        // TODO (func_indent): Consider -> get_func_indent_string.
-        let mut indent_string = String::with_capacity(8);
-        for _ in 0..call_depth {
-            indent_string.push_str(self.indent_step);
+    /// Returns whether the output start is pending (and marks it as logged).
+    fn take_pending(&mut self) -> bool {
+        match &mut self.own_logged {
+            Some(own_logged) => !std::mem::replace(own_logged, true),
+            None => !self.shared_logged.swap(true, Ordering::Relaxed),
         }
-        indent_string
     }
-    /// Returns a tuple of
-    /// * the thread indent
-    /// * and function indent string (that reflects the call depth).
-    ///
-    /// Those combined provide an overall indent for logging a line by the current thread.
-    fn get_indents(&self, call_depth: usize) -> (String, String) {
-        (
-            self.common.get_thread_indent(),
-            self.get_indent_string(call_depth),
-        )
+    /// Switches to the decorator's own output (upon the writer substitution).
+    fn set_own_output(&mut self) {
+        self.own_logged = Some(false);
     }
 }
+mod binary_trace;
+mod chrome_trace;
+mod code_like;
+mod dot_graph;
+mod folded_stack;
+mod html;
+mod json_lines;
+mod sequence_diagram;
+mod tee;
+#[cfg(feature = "tracing_bridge")]
+mod tracing_bridge;
+mod tree_like;
 
-/// The string used to name loop bodies in the log.
-const LOOPBODY_NAME: &str = &"Loop body"; // TODO: Move this deault to a separate file of defaults.
+pub use binary_trace::{BinaryTraceDecorator, BinaryTraceTag};
+pub(crate) use binary_trace::{BINARY_TRACE_START, BINARY_TRACE_TAGS};
+pub use chrome_trace::ChromeTraceDecorator;
+pub use code_like::CodeLikeDecorator;
+pub use dot_graph::{DotGraphDecorator, write_dot_graph};
+pub use folded_stack::FoldedStackDecorator;
+pub use html::HtmlDecorator;
+pub use json_lines::JsonLinesDecorator;
+pub use sequence_diagram::SequenceDiagramDecorator;
+pub use tee::TeeDecorator;
+#[cfg(feature = "tracing_bridge")]
+pub use tracing_bridge::TracingDecorator;
+pub use tree_like::{TreeLikeDecorator, TreeStyle};
 
-impl CoderunNotifiable for CodeLikeDecorator {
-    fn notify_flush(&mut self) {
-        if self.line_end_pending {
-            decorator_write!(self, "\n"); // '\n' after "parent() {" before an output of another thread.
-            self.line_end_pending = false;
-        }
-    }
-    fn notify_call(&mut self, call_depth: usize, name: &str, 
-        #[cfg(feature = "params_logging")]
-        param_vals: &Option<String>
-    ) {
-        if self.line_end_pending {
-            decorator_write!(self, "\n"); // '\n' after "parent() {" before printing a nested call.
-        }
-        let indents = self.get_indents(call_depth); // TODO: Consider -> `let (thread_indent, func_indent) =`.
-
-        #[cfg(feature = "params_logging")]
-        let param_vals = param_vals
-                .as_ref()
-                .map(|string| string.as_str())
-                .unwrap_or("");
-        #[cfg(not(feature = "params_logging"))]
-        let param_vals = "";
-
-        decorator_write!(
-            self,
-            "{}{}{}({}) {{",
-            indents.0,
-            indents.1,
-            name,
-            param_vals,
-        ); // E.g. "<thread_indent><indent>sibling() {"
-        self.line_end_pending = true; // '\n' pending. Won't be printed if there will be no nested calls (immediate "}\n").
-    }
-    fn notify_return(
-        &mut self,
-        call_depth: usize,
-        name: &str,
-        has_nested_calls: bool,
-        #[cfg(feature = "ret_val_logging")]
-        ret_val: &Option<String>,
-    ) {
-        #[cfg(feature = "ret_val_logging")]
-        let ret_val_str = ret_val.as_ref().map_or_else(
-            || "".to_string(), // None -> "".
-            |output| format!(" -> {}", output),
-        ); // Some() -> " -> Value".
-        #[cfg(not(feature = "ret_val_logging"))]
-        let ret_val_str = "";
-        
-        if !has_nested_calls && self.line_end_pending {
-            decorator_write!(self, "}}{}\n", ret_val_str); // "}\n" or "} -> RetVal\n".
-        } else {
-            let indents = self.get_indents(call_depth); // TODO: Consider -> `let (thread_indent, func_indent) =`.
-            decorator_write!(
-                self,
-                "{}{}}}{} // {}().\n", // E.g. "<thread_indent><indent>} -> RetVal // sibling().\n".
-                indents.0,
-                indents.1,
-                ret_val_str,
-                name
-            );
-        }
-        self.line_end_pending = false;
-    }
-    fn notify_repeat_count(
-        &mut self,
-        call_depth: usize,
-        kind: &ItemKind, // TODO: Consider -> item_kind or call_tree_item_kind.
-        count: RepeatCountCategory,
-    ) {
-        let item_name = match kind {
-            ItemKind::Call { name, .. } => format!("{}()", name),
-            ItemKind::Loopbody { .. } => String::from(LOOPBODY_NAME), // "Loop body".
-        };
-        let indents = self.get_indents(call_depth); // TODO: Consider -> `let (thread_indent, func_indent) =`.
-        decorator_write!(
-            self,
-            "{}{}// {} repeats {} time(s).\n", // E.g. "<thread_indent><indent>// sibling() repeats 8 time(s).\n"
-            indents.0,
-            indents.1,
-            item_name,
-            count.to_string()
-        );
-    }
-    fn notify_loopbody_start(&mut self, call_depth: usize) {
-        if self.line_end_pending {
-            decorator_write!(self, "\n"); // '\n' after "parent() {" before printing a nested call.
-        }
-        let indents = self.get_indents(call_depth); // TODO: Consider -> `let (thread_indent, func_indent) =`.
-        decorator_write!(
-            self,
-            "{}{}{{ // {} start.\n",
-            indents.0,
-            indents.1,
-            LOOPBODY_NAME,
-        ); // E.g. "<thread_indent><indent>{ // Loop body start."
-        self.line_end_pending = false;
-    }
-    fn notify_loopbody_end(&mut self, call_depth: usize) {
-        let indents = self.get_indents(call_depth); // TODO: Consider -> `let (thread_indent, func_indent) =`.
-        decorator_write!(
-            self,
-            "{}{}}} // {} end.\n", // E.g. "<thread_indent><indent>} // Loop body end.\n".
-            indents.0,
-            indents.1,
-            LOOPBODY_NAME,
-        );
-        self.line_end_pending = false;
-    }
+/// The kind of the log decorator that is created for each thread
+/// (upon the thread's first logged call, see `call_log_infra::instances::THREAD_DECORATOR`).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DecoratorKind {
+    /// `CodeLikeDecorator` (the default).
+    CodeLike,
+    /// `TreeLikeDecorator`.
+    TreeLike,
+    /// `JsonLinesDecorator`.
+    JsonLines,
+    /// `ChromeTraceDecorator`.
+    ChromeTrace,
+    /// `FoldedStackDecorator`.
+    FoldedStack,
+    /// `HtmlDecorator`.
+    Html,
+    /// `SequenceDiagramDecorator`.
+    SequenceDiagram,
+    /// `DotGraphDecorator`.
+    DotGraph,
+    /// `BinaryTraceDecorator`.
+    BinaryTrace,
+    /// `TracingDecorator`.
+    #[cfg(feature = "tracing_bridge")]
+    Tracing,
 }
 
-impl ThreadSpecific for CodeLikeDecorator {
-    fn set_thread_indent(&mut self, thread_indent: String) {
-        self.common.set_thread_indent(thread_indent);
+impl std::str::FromStr for DecoratorKind {
+    type Err = String;
+    /// Parses the snake case name of the decorator kind (e.g. `"tree_like"` for `DecoratorKind::TreeLike`).
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "code_like" => Ok(DecoratorKind::CodeLike),
+            "tree_like" => Ok(DecoratorKind::TreeLike),
+            "json_lines" => Ok(DecoratorKind::JsonLines),
+            "chrome_trace" => Ok(DecoratorKind::ChromeTrace),
+            "folded_stack" => Ok(DecoratorKind::FoldedStack),
+            "html" => Ok(DecoratorKind::Html),
+            "sequence_diagram" => Ok(DecoratorKind::SequenceDiagram),
+            "dot_graph" => Ok(DecoratorKind::DotGraph),
+            "binary_trace" => Ok(DecoratorKind::BinaryTrace),
+            #[cfg(feature = "tracing_bridge")]
+            "tracing" => Ok(DecoratorKind::Tracing),
+            _ => Err(format!("Unknown decorator kind '{}'", name)),
+        }
     }
 }
 
-impl WriterPossessor for CodeLikeDecorator {
-    fn set_writer(&mut self, writer: Rc<RefCell<dyn Write>>) {
-        self.common.set_writer(writer);
-    }
+/// Whether the ANSI colors are used in the log (by the `CodeLikeDecorator`).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AnsiColors {
+    /// The colors are not used (the default).
+    Never,
+    /// The colors are used if the writer is a terminal (`stdout` or `stderr` connected to a terminal)
+    /// and the `NO_COLOR` environment variable is not set. The colors are turned off upon the writer substitution.
+    Auto,
+    /// The colors are used regardless of the writer.
+    Always,
 }
 
-impl LogDecorator for CodeLikeDecorator {}
+/// Whether the standard output is a terminal. Is determined upon the first check,
+/// before the standard output is redirected (see the "std_output_sync" feature).
+static STDOUT_IS_TERMINAL: OnceLock<bool> = OnceLock::new();
+/// The index (in `THREAD_COLORS`) of the color to be assigned to the next thread.
+static NEXT_THREAD_COLOR: AtomicUsize = AtomicUsize::new(0);
 
-/// The decorator that logs the function calls in a tree-like manner (as opposed to a code-like manner).
-///
-/// For example,
-/// ```ignore
-/// // TreeLikeDecorator Log          Explanation in CodeLikeDecorator manner.
-/// // -----------------------------------------------------------------------
-/// +-f                               // f() {
-/// | +-h                             //   h() {}
-/// |   h repeats 99 time(s).         //   // h() repeats 99 time(s).
-/// | +-i                             //   i() {
-/// | | +-j                           //     j() {}
-/// | |   j repeats 9 time(s).        //     // j() repeats 9 time(s).
-///                                   //   } // i()
-/// |   i repeats 100 time(s).        //   // i() repeats 100 time(s).
-///   f repeats 2 time(s).            // } f() repeats 2 time(s).
-/// +-Loop body                       // { // Loop body starts.
-/// | +-k                             //   k() {}
-/// | +-m                             //   m() {}
-///                                   // } // Loop body ends.
-///   Loop body repeats 3 time(s).    // // Loop body repeats 3 time(s).
-/// ```
-/// This decorator was added to test and make sure that the `CodeLikeDecorator`'s support 
-/// has everything that is required to enable various other decorators.
-#[rustfmt::skip]
-pub struct TreeLikeDecorator {
-    /// The part (of the decorator) common for multiple decorators.
-    common: CommonDecorator,
-
-    // TODO: These defaults to a separate file of defaults.
-    /// The string that prepends the function call in the log. 
-    /// For example, the fragment `+-` in the line `+-f`.
-    indent_step_call   : &'static str,  // "+-"  f
-    /// The string that prepends the repeat count in the log. 
-    /// For example, the fragment of 2 spaces in the line 
-    /// ```txt
-    /// | |   j repeats 9 time(s)
-    /// ```
-    indent_step_noncall: &'static str,  // "  "  Repeats ..
-    /// The string that shows the call depth, e.g. the multiple fragments `| ` in the line `| | +-j`.
-    indent_step_parent : &'static str,  // "| "  Prepends multiple times those above.
+/// Returns whether the standard output (before the redirection, if any) is a terminal.
+pub(crate) fn stdout_is_terminal() -> bool {
+    *STDOUT_IS_TERMINAL.get_or_init(|| stdout().is_terminal())
 }
 
-impl TreeLikeDecorator {
-    /// Creates a new `CodeLikeDecorator` with the optional writer and indent steps.
-    /// * If the writer is `None` then uses the one in the `CommonDecorator`.
-    /// * If the indent steps are `None` then uses
-    ///   * `&"+-"` for `indent_step_call`,
-    ///   * `&"  "` (2 spaces) for `indent_step_noncall`,
-    ///   * `&"| "` for `indent_step_parent`,
-    #[rustfmt::skip]
-    pub fn new(writer: Option<Box<dyn Write>>,
-        indent_step_call   : Option<&'static str>,
-        indent_step_noncall: Option<&'static str>,
-        indent_step_parent : Option<&'static str>,
-    ) -> Self {
-        Self {
-            common: CommonDecorator::new(writer),
-
-            // TODO: Move the defaults to a separate file of defaults.
-            indent_step_call   : indent_step_call   .unwrap_or(&"+-"),
-            indent_step_noncall: indent_step_noncall.unwrap_or(&"  "),
-            indent_step_parent : indent_step_parent .unwrap_or(&"| ")
-        }
-    }
-    /// Returns the indent string for the specified call depth. In other words, a string containing
-    /// `self.indent_step_parent` `call_depth` times.
-    fn get_indent_string(&self, call_depth: usize) -> String {
-        let mut indent_string = String::with_capacity(8);
-        for _ in 0..call_depth {
-            indent_string.push_str(self.indent_step_parent);
-        }
-        indent_string
-    }
-    /// Returns a tuple of
-    /// * the thread indent
-    /// * and function indent string (that reflects the call depth).
-    ///
-    /// Those combined provide an indent for logging a line by the current thread.
-    fn get_indents(&self, call_depth: usize) -> (String, String) {
-        (
-            self.common.get_thread_indent(),
-            self.get_indent_string(call_depth),
-        )
-    }
+/// Returns the ANSI foreground color code for the next thread's lines, or `None` if the colors are not used
+/// (e.g. with `AnsiColors::Auto` if the writer is not a terminal).
+fn next_thread_color(writer_is_terminal: bool) -> Option<u8> {
+    let colors_are_used = match ansi_colors() {
+        AnsiColors::Never => false,
+        AnsiColors::Auto => writer_is_terminal && std::env::var_os("NO_COLOR").is_none(),
+        AnsiColors::Always => true,
+    };
+    colors_are_used.then(|| {
+        defaults::THREAD_COLORS[NEXT_THREAD_COLOR.fetch_add(1, Ordering::Relaxed) % defaults::THREAD_COLORS.len()]
+    })
 }
 
-impl CoderunNotifiable for TreeLikeDecorator {
-    fn notify_call(&mut self, call_depth: usize, name: &str, 
-        #[cfg(feature = "params_logging")]
-        param_vals: &Option<String>
-    ) {
-        let indents = self.get_indents(call_depth);  // TODO: Consider -> `let (thread_indent, func_indent) =`.
+/// A function creating the writer of an additional log output (see `add_tee_output()`).
+/// Is called for each thread upon the thread's first logged call.
+pub type TeeWriterFactory = fn() -> Box<dyn Write>;
 
-        #[cfg(feature = "params_logging")]
-        let param_vals = param_vals
-                .as_ref()
-                .map(|string| string.as_str())
-                .unwrap_or("");
-        #[cfg(not(feature = "params_logging"))]
-        let param_vals = "";
-
-        decorator_write!(
-            self,
-            "{}{}{}{}({})\n",
-            indents.0,
-            indents.1,
-            self.indent_step_call,
-            name,
-            param_vals,
-        ); // E.g."<thread_indent><indent>+-sibling", "| | | | +-sibling"
-    }
+/// A function creating the writer of the static call graph aggregated by the `DotGraphDecorator`s
+/// (see `set_dot_graph_output()`). Is called by the thread writing the graph.
+pub type DotGraphWriterFactory = fn() -> Box<dyn Write>;
 
-    // NOTE: Reusing the default behavior of `notify_return()` that does nothing.
+/// A function creating the log decorator of a thread with the optional writer (see `set_decorator_factory()`).
+/// Is called for each thread upon the thread's first logged call.
+pub type DecoratorFactory = fn(Option<Box<dyn Write>>) -> Rc<RefCell<dyn LogDecorator>>;
 
-    fn notify_repeat_count(
-        &mut self,
-        call_depth: usize,
-        kind: &ItemKind,
-        count: RepeatCountCategory,
-    ) {
-        let item_name = match kind {
-            ItemKind::Call { name, .. } => name.clone(),
-            ItemKind::Loopbody { .. } => String::from(LOOPBODY_NAME), // "Loop body",
-        };
-        let indents = self.get_indents(call_depth); // TODO: Consider -> `let (thread_indent, func_indent) =`.
-        decorator_write!(
-            self,
-            "{}{}{}{} repeats {} time(s).\n", // E.g. "<thread_indent><indent> sibling repeats 8 time(s).\n"
-            indents.0,
-            indents.1,
-            self.indent_step_noncall,
-            item_name,
-            count.to_string()
-        );
+/// Creates a new log decorator with the optional writer by the function set with `set_decorator_factory()`, if any,
+/// otherwise of the kind returned by `decorator_kind()` with the default indent steps.
+/// If there are additional log outputs (see `add_tee_output()`), creates a `TeeDecorator`
+/// forwarding to that decorator and to the decorators of the additional outputs.
+///
+/// `writer_is_terminal` tells whether the writer (or the default one, `stdout`, if `None`) is a terminal,
+/// which enables the `AnsiColors::Auto` colors. The writers of the additional outputs are not considered terminals.
+pub fn new_decorator(writer: Option<Box<dyn Write>>, writer_is_terminal: bool) -> Rc<RefCell<dyn LogDecorator>> {
+    let decorator = match decorator_factory() {
+        Some(factory) => factory(writer),
+        None => new_decorator_of_kind(decorator_kind(), writer, writer_is_terminal),
+    };
+    let tee_outputs = tee_outputs();
+    if tee_outputs.is_empty() {
+        return decorator;
     }
-    fn notify_loopbody_start(&mut self, call_depth: usize) {
-        let indents = self.get_indents(call_depth); // TODO: Consider -> `let (thread_indent, func_indent) =`.
-        decorator_write!(
-            self,
-            "{}{}{}{}\n",
-            indents.0,
-            indents.1,
-            self.indent_step_call,
-            LOOPBODY_NAME,
-        ); // E.g."<thread_indent><indent>+-Loop body", "| | | | +-Loop body"
+    let mut tee_decorator = TeeDecorator::new(vec![decorator]);
+    for (kind, make_writer) in tee_outputs {
+        tee_decorator.add_child(new_decorator_of_kind(kind, Some(make_writer()), false));
     }
-
-    // NOTE: Reusing the default implementation of `notify_loopbody_end()` that does nothing.
+    Rc::new(RefCell::new(tee_decorator))
 }
 
-impl ThreadSpecific for TreeLikeDecorator {
-    fn set_thread_indent(&mut self, thread_indent: String) {
-        self.common.set_thread_indent(thread_indent);
-    }
-}
-
-impl WriterPossessor for TreeLikeDecorator {
-    fn set_writer(&mut self, writer: Rc<RefCell<dyn Write>>) {
-        self.common.set_writer(writer);
+/// Creates a new log decorator of the `kind`, with the optional writer (a terminal if `writer_is_terminal`)
+/// and the default indent steps.
+fn new_decorator_of_kind(
+    kind: DecoratorKind,
+    writer: Option<Box<dyn Write>>,
+    writer_is_terminal: bool,
+) -> Rc<RefCell<dyn LogDecorator>> {
+    match kind {
+        DecoratorKind::CodeLike => {
+            let mut decorator = CodeLikeDecorator::new(writer, None);
+            if let Some(indent_step) = indent_step() {
+                decorator.set_indent_step(indent_step);
+            }
+            decorator.set_thread_color(next_thread_color(writer_is_terminal));
+            decorator.set_line_prefixes(line_prefixes());
+            decorator.set_thread_headers(thread_headers());
+            Rc::new(RefCell::new(decorator))
+        }
+        DecoratorKind::TreeLike => {
+            let mut decorator = TreeLikeDecorator::new(writer, None, None, None);
+            decorator.set_style(tree_style());
+            decorator.set_line_prefixes(line_prefixes());
+            decorator.set_thread_headers(thread_headers());
+            Rc::new(RefCell::new(decorator))
+        }
+        DecoratorKind::JsonLines => {
+            let mut decorator = JsonLinesDecorator::new(writer);
+            decorator.set_thread_headers(thread_headers());
+            Rc::new(RefCell::new(decorator))
+        }
+        DecoratorKind::ChromeTrace => Rc::new(RefCell::new(ChromeTraceDecorator::new(writer))),
+        DecoratorKind::FoldedStack => Rc::new(RefCell::new(FoldedStackDecorator::new(writer))),
+        DecoratorKind::Html => Rc::new(RefCell::new(HtmlDecorator::new(writer))),
+        DecoratorKind::SequenceDiagram => Rc::new(RefCell::new(SequenceDiagramDecorator::new(writer))),
+        DecoratorKind::DotGraph => Rc::new(RefCell::new(DotGraphDecorator::new(writer))),
+        DecoratorKind::BinaryTrace => Rc::new(RefCell::new(BinaryTraceDecorator::new(writer))),
+        #[cfg(feature = "tracing_bridge")]
+        DecoratorKind::Tracing => Rc::new(RefCell::new(TracingDecorator::new())), // The writer is not used.
     }
 }
-
-impl LogDecorator for TreeLikeDecorator {}
@@ -0,0 +1,399 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    io::Write,
+    rc::Rc,
+    sync::atomic::AtomicBool,
+};
+
+use code_commons::{CoderunNotifiable, ItemKind, RepeatCountCategory};
+#[cfg(feature = "call_timing")]
+use code_commons::DurationStats;
+#[cfg(feature = "call_timing")]
+use std::time::{Duration, Instant};
+
+use super::*;
+
+/// The decorator that logs the calls as a compact binary trace with minimal work at runtime
+/// (no text formatting), to be decoded afterwards to the code-like or tree-like text
+/// (see `fcl::common::binary_trace::decode_binary_trace()` and the `fcl_decode` binary).
+///
+/// The trace starts with the magic `FCLB` followed by the format version byte (`1`),
+/// then the records follow. Each record is
+/// * the `u32` length of the rest of the record,
+/// * the `u8` record tag,
+/// * the `u64` number of the thread ID,
+/// * the tag-specific fields (see `BinaryTraceTag`).
+///
+/// The integers are little-endian, the strings are the `u32` length followed by the UTF-8 bytes,
+/// the optional values are the `u8` `0` (`None`) or `1` followed by the value.
+/// The function and closure names are interned per thread, i.e. the name is logged once (`BinaryTraceTag::Name`)
+/// and is referred to by the name ID afterwards.
+///
+/// Each record is written with a single `write_all()` call such that the records of different threads
+/// writing to the shared output are not intermixed.
+pub struct BinaryTraceDecorator {
+    /// The part (of the decorator) common for multiple decorators.
+    /// The thread indent is not used.
+    common: CommonDecorator,
+    /// The number of the ID of the thread that has created the decorator
+    /// (the decorator is thread-local, but can be flushed by a different thread).
+    thread_id: u64,
+    /// The name of the thread that has created the decorator (empty if the thread is unnamed).
+    thread_name: String,
+    /// Whether the thread record has been logged.
+    thread_logged: bool,
+    /// The IDs of the names logged so far.
+    name_ids: HashMap<String, u32>,
+    /// Whether the trace start (the magic and version) has been logged.
+    trace_start: OutputStart,
+    /// The record being composed (reused to avoid the allocation for each record).
+    record: Vec<u8>,
+}
+
+/// Whether the trace start has been logged to the shared output.
+static BINARY_TRACE_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// The start of the binary trace: the magic and the format version.
+pub(crate) const BINARY_TRACE_START: &[u8] = b"FCLB\x01";
+
+/// The tag of a binary trace record, followed by the tag-specific fields.
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BinaryTraceTag {
+    /// The thread name (string), precedes the other records of the thread.
+    Thread = 0,
+    /// The name interning: the `u32` name ID, the name (string).
+    Name,
+    /// The call: the `u32` call depth, the `u32` name ID, the optional parameters (string),
+    /// the `u32` recursion depth (`0` if the call is not recursion-folded).
+    Call,
+    /// The return: the `u32` call depth, the `u32` name ID, the `u8` flag of having the nested calls,
+    /// the optional return value (string), the optional duration (`u64` nanoseconds).
+    Return,
+    /// The repeat count: the `u32` call depth, the item, the repeat count, the optional duration statistics
+    /// (the `u32` count, and `u64` nanoseconds of the total, min, and max).
+    ///
+    /// The item is the `u8` `0` followed by the `u32` name ID and the optional parameters (string) for a call,
+    /// or the `u8` `1` followed by the `u8` flag of ending the loop for a loop body.
+    /// The repeat count is the `u8` category (`0` exact, `1` at least, `2` unknown) followed by the `u64` count.
+    RepeatCount,
+    /// The sequence repeat count: the `u32` call depth, the `u32` number of items, the items, the repeat count.
+    SequenceRepeatCount,
+    /// The flush (no fields).
+    Flush,
+    /// The loop body start: the `u32` call depth.
+    LoopbodyStart,
+    /// The loop body end: the `u32` call depth, the optional duration (`u64` nanoseconds).
+    LoopbodyEnd,
+    /// The repeat folding suspension: the `u32` call depth.
+    FoldingSuspended,
+}
+
+/// The binary trace record tags in the order of their values.
+pub(crate) const BINARY_TRACE_TAGS: [BinaryTraceTag; 10] = [
+    BinaryTraceTag::Thread,
+    BinaryTraceTag::Name,
+    BinaryTraceTag::Call,
+    BinaryTraceTag::Return,
+    BinaryTraceTag::RepeatCount,
+    BinaryTraceTag::SequenceRepeatCount,
+    BinaryTraceTag::Flush,
+    BinaryTraceTag::LoopbodyStart,
+    BinaryTraceTag::LoopbodyEnd,
+    BinaryTraceTag::FoldingSuspended,
+];
+
+/// Appends the `u32` to the record.
+fn put_u32(record: &mut Vec<u8>, value: u32) {
+    record.extend_from_slice(&value.to_le_bytes());
+}
+/// Appends the `u64` to the record.
+fn put_u64(record: &mut Vec<u8>, value: u64) {
+    record.extend_from_slice(&value.to_le_bytes());
+}
+/// Appends the string (length-prefixed) to the record.
+fn put_string(record: &mut Vec<u8>, string: &str) {
+    put_u32(record, string.len() as u32);
+    record.extend_from_slice(string.as_bytes());
+}
+/// Appends the optional string to the record.
+fn put_optional_string(record: &mut Vec<u8>, string: Option<&str>) {
+    match string {
+        None => record.push(0),
+        Some(string) => {
+            record.push(1);
+            put_string(record, string);
+        }
+    }
+}
+/// Appends the optional duration (in nanoseconds) to the record.
+#[cfg(feature = "call_timing")]
+fn put_optional_duration(record: &mut Vec<u8>, duration: Option<Duration>) {
+    match duration {
+        None => record.push(0),
+        Some(duration) => {
+            record.push(1);
+            put_u64(record, duration.as_nanos() as u64);
+        }
+    }
+}
+/// Appends the repeat count to the record.
+fn put_repeat_count(record: &mut Vec<u8>, count: RepeatCountCategory) {
+    let (category, count) = match count {
+        RepeatCountCategory::Exact(count) => (0, count as u64),
+        RepeatCountCategory::AtLeast(count) => (1, count as u64),
+        RepeatCountCategory::Unknown => (2, 0),
+    };
+    record.push(category);
+    put_u64(record, count);
+}
+
+impl BinaryTraceDecorator {
+    /// Creates a new `BinaryTraceDecorator` with the optional writer.
+    /// If the writer is `None` then uses the one in the `CommonDecorator`.
+    ///
+    /// The records are marked with the ID of the thread calling this function.
+    pub fn new(writer: Option<Box<dyn Write>>) -> Self {
+        let thread = std::thread::current();
+        Self {
+            common: CommonDecorator::new(writer),
+            thread_id: thread_id_number(&thread).parse().unwrap_or_default(),
+            thread_name: thread.name().unwrap_or_default().to_string(),
+            thread_logged: false,
+            name_ids: HashMap::new(),
+            trace_start: OutputStart::new(&BINARY_TRACE_STARTED),
+            record: Vec::with_capacity(64),
+        }
+    }
+    /// Starts composing the record with the tag.
+    fn start_record(&mut self, tag: BinaryTraceTag) {
+        self.record.clear();
+        put_u32(&mut self.record, 0); // The length placeholder.
+        self.record.push(tag as u8);
+        put_u64(&mut self.record, self.thread_id);
+    }
+    /// Writes the composed record. Before the first record writes the trace start (if not yet written to the output)
+    /// and the thread record.
+    fn write_record(&mut self) {
+        let length = (self.record.len() - 4) as u32;
+        self.record[..4].copy_from_slice(&length.to_le_bytes());
+        if !self.thread_logged {
+            self.thread_logged = true;
+            let record = std::mem::take(&mut self.record);
+            self.start_record(BinaryTraceTag::Thread);
+            put_string(&mut self.record, &self.thread_name.clone());
+            self.write_record();
+            self.record = record;
+        }
+        let writer = match &mut self.common.writer {
+            Writer::Original(writer) => &mut **writer,
+            Writer::Substitute(writer) => &mut *writer.borrow_mut(),
+        };
+        if self.trace_start.take_pending() {
+            let _ignore_result = writer.write_all(BINARY_TRACE_START);
+        }
+        let _ignore_result = writer.write_all(&self.record);
+    }
+    /// Returns the ID of the name. Upon the first use of the name writes the name record.
+    fn name_id(&mut self, name: &str) -> u32 {
+        if let Some(name_id) = self.name_ids.get(name) {
+            return *name_id;
+        }
+        let name_id = self.name_ids.len() as u32;
+        self.name_ids.insert(name.to_string(), name_id);
+        let record = std::mem::take(&mut self.record);
+        self.start_record(BinaryTraceTag::Name);
+        put_u32(&mut self.record, name_id);
+        put_string(&mut self.record, name);
+        self.write_record();
+        self.record = record;
+        name_id
+    }
+    /// Appends the item (of the repeat count) to the record.
+    fn put_item(&mut self, kind: &ItemKind) {
+        match kind {
+            ItemKind::Call {
+                name,
+                #[cfg(feature = "params_logging")]
+                param_vals,
+            } => {
+                #[cfg(feature = "params_logging")]
+                let params = param_vals.as_deref();
+                #[cfg(not(feature = "params_logging"))]
+                let params = None;
+                let name_id = self.name_id(name);
+                self.record.push(0);
+                put_u32(&mut self.record, name_id);
+                put_optional_string(&mut self.record, params);
+            }
+            ItemKind::Loopbody { ends_the_loop } => {
+                self.record.push(1);
+                self.record.push(*ends_the_loop as u8);
+            }
+        }
+    }
+    /// Writes the call record.
+    fn write_call(&mut self, call_depth: usize, name: &str, params: Option<&str>, recursion_depth: usize) {
+        let name_id = self.name_id(name);
+        self.start_record(BinaryTraceTag::Call);
+        put_u32(&mut self.record, call_depth as u32);
+        put_u32(&mut self.record, name_id);
+        put_optional_string(&mut self.record, params);
+        put_u32(&mut self.record, recursion_depth as u32);
+        self.write_record();
+    }
+}
+
+impl CoderunNotifiable for BinaryTraceDecorator {
+    fn notify_call(&mut self, call_depth: usize, name: &str, 
+        #[cfg(feature = "params_logging")]
+        param_vals: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        #[cfg(feature = "params_logging")]
+        let params = param_vals.as_deref();
+        #[cfg(not(feature = "params_logging"))]
+        let params = None;
+        self.write_call(call_depth, name, params, 0);
+    }
+    fn notify_recursive_call(&mut self, call_depth: usize, name: &str,
+        #[cfg(feature = "params_logging")]
+        param_vals: &Option<String>,
+        recursion_depth: usize,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        #[cfg(feature = "params_logging")]
+        let params = param_vals.as_deref();
+        #[cfg(not(feature = "params_logging"))]
+        let params = None;
+        self.write_call(call_depth, name, params, recursion_depth);
+    }
+    fn notify_return(
+        &mut self,
+        call_depth: usize,
+        name: &str,
+        has_nested_calls: bool,
+        #[cfg(feature = "ret_val_logging")]
+        ret_val: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        duration: Option<Duration>,
+    ) {
+        #[cfg(feature = "ret_val_logging")]
+        let ret_val = ret_val.as_deref();
+        #[cfg(not(feature = "ret_val_logging"))]
+        let ret_val = None;
+        let name_id = self.name_id(name);
+        self.start_record(BinaryTraceTag::Return);
+        put_u32(&mut self.record, call_depth as u32);
+        put_u32(&mut self.record, name_id);
+        self.record.push(has_nested_calls as u8);
+        put_optional_string(&mut self.record, ret_val);
+        #[cfg(feature = "call_timing")]
+        put_optional_duration(&mut self.record, duration);
+        #[cfg(not(feature = "call_timing"))]
+        self.record.push(0); // No duration.
+        self.write_record();
+    }
+    fn notify_repeat_count(
+        &mut self,
+        call_depth: usize,
+        kind: &ItemKind,
+        count: RepeatCountCategory,
+        #[cfg(feature = "call_timing")]
+        timing: &DurationStats,
+    ) {
+        if let ItemKind::Call { name, .. } = kind {
+            self.name_id(name); // Log the name record (if needed) before composing this record.
+        }
+        self.start_record(BinaryTraceTag::RepeatCount);
+        put_u32(&mut self.record, call_depth as u32);
+        self.put_item(kind);
+        put_repeat_count(&mut self.record, count);
+        #[cfg(feature = "call_timing")]
+        if timing.is_empty() {
+            self.record.push(0);
+        } else {
+            self.record.push(1);
+            put_u32(&mut self.record, timing.count());
+            put_u64(&mut self.record, timing.total().as_nanos() as u64);
+            put_u64(&mut self.record, timing.min().as_nanos() as u64);
+            put_u64(&mut self.record, timing.max().as_nanos() as u64);
+        }
+        #[cfg(not(feature = "call_timing"))]
+        self.record.push(0); // No duration statistics.
+        self.write_record();
+    }
+    fn notify_sequence_repeat_count(
+        &mut self,
+        call_depth: usize,
+        kinds: &[ItemKind],
+        count: RepeatCountCategory,
+    ) {
+        for kind in kinds {
+            if let ItemKind::Call { name, .. } = kind {
+                self.name_id(name); // Log the name record (if needed) before composing this record.
+            }
+        }
+        self.start_record(BinaryTraceTag::SequenceRepeatCount);
+        put_u32(&mut self.record, call_depth as u32);
+        put_u32(&mut self.record, kinds.len() as u32);
+        for kind in kinds {
+            self.put_item(kind);
+        }
+        put_repeat_count(&mut self.record, count);
+        self.write_record();
+    }
+    fn notify_flush(&mut self) {
+        if !self.thread_logged {
+            return; // Nothing has been logged by the thread.
+        }
+        self.start_record(BinaryTraceTag::Flush);
+        self.write_record();
+    }
+    fn notify_loopbody_start(&mut self, call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        self.start_record(BinaryTraceTag::LoopbodyStart);
+        put_u32(&mut self.record, call_depth as u32);
+        self.write_record();
+    }
+    fn notify_loopbody_end(&mut self, call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        duration: Option<Duration>,
+    ) {
+        self.start_record(BinaryTraceTag::LoopbodyEnd);
+        put_u32(&mut self.record, call_depth as u32);
+        #[cfg(feature = "call_timing")]
+        put_optional_duration(&mut self.record, duration);
+        #[cfg(not(feature = "call_timing"))]
+        self.record.push(0); // No duration.
+        self.write_record();
+    }
+    fn notify_folding_suspended(&mut self, call_depth: usize) {
+        self.start_record(BinaryTraceTag::FoldingSuspended);
+        put_u32(&mut self.record, call_depth as u32);
+        self.write_record();
+    }
+}
+
+impl ThreadSpecific for BinaryTraceDecorator {
+    fn set_thread_indent(&mut self, thread_indent: String) {
+        self.common.set_thread_indent(thread_indent); // Is not used.
+    }
+}
+
+impl WriterPossessor for BinaryTraceDecorator {
+    fn set_writer(&mut self, writer: Rc<RefCell<dyn Write>>) {
+        self.common.set_writer(writer);
+        // The substitute writer is a separate output that needs its own trace start, thread record, and names.
+        self.trace_start.set_own_output();
+        self.thread_logged = false;
+        self.name_ids.clear();
+    }
+}
+
+impl LogDecorator for BinaryTraceDecorator {}
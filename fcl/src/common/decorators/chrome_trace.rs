@@ -0,0 +1,316 @@
+use std::{
+    cell::RefCell,
+    io::Write,
+    rc::Rc,
+    sync::atomic::AtomicBool,
+    time::Instant,
+};
+
+use code_commons::{CoderunNotifiable, ItemKind, RepeatCountCategory};
+#[cfg(feature = "call_timing")]
+use code_commons::DurationStats;
+#[cfg(feature = "call_timing")]
+use std::time::Duration;
+
+use super::*;
+use super::json_lines::{json_repeat_count_fields, json_string};
+#[cfg(any(feature = "params_logging", feature = "ret_val_logging"))]
+use super::json_lines::json_optional_string;
+
+/// The decorator that logs the calls in the [Chrome Trace Event Format](https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU)
+/// such that the log can be opened in [Perfetto](https://ui.perfetto.dev/) or `chrome://tracing`.
+///
+/// The calls and loop bodies are logged as the duration events (`"ph":"B"` and `"ph":"E"`),
+/// the repeat counts, sequence repeat counts, and the repeat folding suspension are logged as the instant events
+/// (`"ph":"i"`) carrying the repeat count in the `"args"`, the thread name is logged as the metadata event (`"ph":"M"`).
+/// Each logged thread is a separate `"tid"`.
+///
+/// For example,
+/// ```txt
+/// [
+/// {"name":"thread_name","ph":"M","pid":7,"tid":1,"args":{"name":"main"}},
+/// {"name":"f","cat":"call","ph":"B","ts":10.250,"pid":7,"tid":1,"args":{"params":"x: 1"}},
+/// {"name":"g","cat":"call","ph":"B","ts":11.000,"pid":7,"tid":1,"args":{"params":null}},
+/// {"name":"g","cat":"call","ph":"E","ts":12.500,"pid":7,"tid":1,"args":{"ret_val":null}},
+/// {"name":"g() repeats 2 time(s)","cat":"repeat_count","ph":"i","s":"t","ts":15.000,"pid":7,"tid":1,"args":{"category":"exact","count":2}},
+/// {"name":"f","cat":"call","ph":"E","ts":15.250,"pid":7,"tid":1,"args":{"ret_val":"5"}},
+/// ```
+/// The closing `]` is optional in the format and is not logged (the log can be interrupted at any moment).
+///
+/// The timestamps (`"ts"`, in microseconds since the first decorator creation) of the calls, returns,
+/// and loop bodies are the moments recorded by the call graph if the call timing is on
+/// (the "call_timing" feature and `fcl::set_call_timing!(true)`), such that the calls cached by the call graph
+/// (e.g. as the potential repeats, logged upon the repeat end or flush) are logged at their real moments.
+/// Otherwise (and for the instant events) the timestamps are taken at the moment of the notification,
+/// i.e. the timestamps of the cached calls are delayed.
+pub struct ChromeTraceDecorator {
+    /// The part (of the decorator) common for multiple decorators.
+    /// The thread indent is not used.
+    common: CommonDecorator,
+    /// The `"pid":..,"tid":..` fragment identifying the thread that has created the decorator
+    /// (the decorator is thread-local, but can be flushed by a different thread).
+    thread_fields: String,
+    /// The name of the thread that has created the decorator (the thread ID if the thread is unnamed).
+    thread_name: String,
+    /// Whether the thread name metadata event has been logged.
+    thread_name_logged: bool,
+    /// Whether the array start `[` has been logged.
+    array_start: OutputStart,
+    /// The timestamps of the calls and loop bodies logged and not yet returned or ended,
+    /// from the outermost to the innermost (`None` if the moment of the call or loop body start is unknown).
+    #[cfg(feature = "call_timing")]
+    start_timestamps: Vec<Option<f64>>,
+}
+
+/// Whether the trace array start `[` has been logged to the shared output.
+static TRACE_ARRAY_STARTED: AtomicBool = AtomicBool::new(false);
+
+impl ChromeTraceDecorator {
+    /// Creates a new `ChromeTraceDecorator` with the optional writer.
+    /// If the writer is `None` then uses the one in the `CommonDecorator`.
+    ///
+    /// The events are marked with the ID of the thread calling this function.
+    pub fn new(writer: Option<Box<dyn Write>>) -> Self {
+        let thread = std::thread::current();
+        let thread_id = thread_id_number(&thread);
+        Self {
+            common: CommonDecorator::new(writer),
+            thread_fields: format!("\"pid\":{},\"tid\":{}", std::process::id(), thread_id),
+            thread_name: thread.name().map_or_else(|| format!("ThreadId({})", thread_id), String::from),
+            thread_name_logged: false,
+            array_start: OutputStart::new(&TRACE_ARRAY_STARTED),
+            #[cfg(feature = "call_timing")]
+            start_timestamps: Vec::new(),
+        }
+    }
+    /// Writes an event. Before the first event writes the array start `[` (if not yet written to the output)
+    /// and the thread name metadata event.
+    /// # Parameters
+    /// * The event name.
+    /// * The event fields following the name (e.g. `,"cat":"call","ph":"B"`), each prefixed with a comma.
+    /// * The optional `"args"` object contents (e.g. `"params":"x: 1"`).
+    /// * The timestamp.
+    fn write_event(&mut self, name: &str, fields: &str, args: &str, timestamp: f64) {
+        if self.array_start.take_pending() {
+            decorator_write!(self, "[\n");
+        }
+        if !self.thread_name_logged {
+            self.thread_name_logged = true;
+            decorator_write!(
+                self,
+                "{{\"name\":\"thread_name\",\"ph\":\"M\",{},\"args\":{{\"name\":{}}}}},\n",
+                self.thread_fields,
+                json_string(&self.thread_name),
+            );
+        }
+        decorator_write!(
+            self,
+            "{{\"name\":{}{},\"ts\":{:.3},{},\"args\":{{{}}}}},\n", // E.g. `{"name":"f","cat":"call","ph":"B","ts":1.250,"pid":7,"tid":1,"args":{}},`.
+            json_string(name),
+            fields,
+            timestamp,
+            self.thread_fields,
+            args,
+        );
+    }
+    /// Writes a duration event start or end of the call.
+    fn write_call_event(&mut self, name: &str, phase: &str, args: &str, timestamp: f64) {
+        self.write_event(name, &format!(",\"cat\":\"call\",\"ph\":\"{}\"", phase), args, timestamp);
+    }
+    /// Writes an instant event (thread-scoped) timestamped with the current moment.
+    fn write_instant_event(&mut self, name: &str, category: &str, args: &str) {
+        self.write_event(
+            name,
+            &format!(",\"cat\":\"{}\",\"ph\":\"i\",\"s\":\"t\"", category),
+            args,
+            current_timestamp(),
+        );
+    }
+    /// Returns the timestamp of the call or loop body start (the current one if the `start` is unknown),
+    /// remembers it for the return or loop body end.
+    fn start_timestamp(&mut self, #[cfg(feature = "call_timing")] start: Option<Instant>) -> f64 {
+        #[cfg(feature = "call_timing")]
+        {
+            let start_timestamp = start.map(timestamp);
+            self.start_timestamps.push(start_timestamp);
+            start_timestamp.unwrap_or_else(current_timestamp)
+        }
+        #[cfg(not(feature = "call_timing"))]
+        current_timestamp()
+    }
+    /// Returns the timestamp of the return or loop body end, i.e. the start timestamp plus the `duration`
+    /// (the current one if either is unknown).
+    fn end_timestamp(&mut self, #[cfg(feature = "call_timing")] duration: Option<Duration>) -> f64 {
+        #[cfg(feature = "call_timing")]
+        if let (Some(Some(start_timestamp)), Some(duration)) = (self.start_timestamps.pop(), duration) {
+            return start_timestamp + duration.as_nanos() as f64 / 1000.0;
+        }
+        current_timestamp()
+    }
+}
+
+/// Returns the timestamp (in microseconds since the first decorator creation) of the `moment`.
+fn timestamp(moment: Instant) -> f64 {
+    moment
+        .saturating_duration_since(*LOG_START.get_or_init(Instant::now))
+        .as_nanos() as f64
+        / 1000.0
+}
+
+/// Returns the timestamp of the current moment.
+fn current_timestamp() -> f64 {
+    timestamp(Instant::now())
+}
+
+/// Returns the `"category":..,"count":..` fields describing the repeat count (without the leading comma).
+fn trace_repeat_count_args(count: RepeatCountCategory) -> String {
+    json_repeat_count_fields(count)[1..].to_string() // `[1..]`: Skip the leading comma.
+}
+
+impl CoderunNotifiable for ChromeTraceDecorator {
+    fn notify_call(&mut self, _call_depth: usize, name: &str, 
+        #[cfg(feature = "params_logging")]
+        param_vals: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        start: Option<Instant>,
+    ) {
+        #[cfg(feature = "params_logging")]
+        let args = format!("\"params\":{}", json_optional_string(param_vals));
+        #[cfg(not(feature = "params_logging"))]
+        let args = String::new();
+        let timestamp = self.start_timestamp(
+            #[cfg(feature = "call_timing")]
+            start,
+        );
+        self.write_call_event(name, "B", &args, timestamp);
+    }
+    fn notify_recursive_call(&mut self, _call_depth: usize, name: &str,
+        #[cfg(feature = "params_logging")]
+        param_vals: &Option<String>,
+        recursion_depth: usize,
+        #[cfg(feature = "call_timing")]
+        start: Option<Instant>,
+    ) {
+        #[cfg(feature = "params_logging")]
+        let args = format!(
+            "\"params\":{},\"recursion_depth\":{}",
+            json_optional_string(param_vals),
+            recursion_depth
+        );
+        #[cfg(not(feature = "params_logging"))]
+        let args = format!("\"recursion_depth\":{}", recursion_depth);
+        let timestamp = self.start_timestamp(
+            #[cfg(feature = "call_timing")]
+            start,
+        );
+        self.write_call_event(name, "B", &args, timestamp);
+    }
+    fn notify_return(
+        &mut self,
+        _call_depth: usize,
+        name: &str,
+        _has_nested_calls: bool,
+        #[cfg(feature = "ret_val_logging")]
+        ret_val: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        duration: Option<Duration>, // The trace viewer calculates the duration.
+    ) {
+        #[cfg(feature = "ret_val_logging")]
+        let args = format!("\"ret_val\":{}", json_optional_string(ret_val));
+        #[cfg(not(feature = "ret_val_logging"))]
+        let args = String::new();
+        let timestamp = self.end_timestamp(
+            #[cfg(feature = "call_timing")]
+            duration,
+        );
+        self.write_call_event(name, "E", &args, timestamp);
+    }
+    fn notify_repeat_count(
+        &mut self,
+        _call_depth: usize,
+        kind: &ItemKind,
+        count: RepeatCountCategory,
+        #[cfg(feature = "call_timing")]
+        timing: &DurationStats,
+    ) {
+        let item_name = match kind {
+            ItemKind::Call { name, .. } => format!("{}()", name),
+            ItemKind::Loopbody { .. } => String::from(LOOPBODY_NAME), // "Loop body".
+        };
+        #[cfg(feature = "call_timing")]
+        let timing_args = if timing.is_empty() {
+            String::new()
+        } else {
+            format!(
+                ",\"total_us\":{:.3},\"max_us\":{:.3}",
+                timing.total().as_nanos() as f64 / 1000.0,
+                timing.max().as_nanos() as f64 / 1000.0
+            )
+        };
+        #[cfg(not(feature = "call_timing"))]
+        let timing_args = "";
+        let name = format!("{} repeats {} time(s)", item_name, count.to_string());
+        self.write_instant_event(
+            &name,
+            "repeat_count",
+            &format!("{}{}", trace_repeat_count_args(count), timing_args),
+        );
+    }
+    fn notify_sequence_repeat_count(
+        &mut self,
+        _call_depth: usize,
+        kinds: &[ItemKind],
+        count: RepeatCountCategory,
+    ) {
+        let item_names = kinds
+            .iter()
+            .map(|kind| match kind {
+                ItemKind::Call { name, .. } => format!("{}()", name),
+                ItemKind::Loopbody { .. } => String::from(LOOPBODY_NAME), // "Loop body".
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+        let name = format!("({}) repeats {} time(s)", item_names, count.to_string());
+        self.write_instant_event(&name, "sequence_repeat_count", &trace_repeat_count_args(count));
+    }
+    fn notify_loopbody_start(&mut self, _call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        start: Option<Instant>,
+    ) {
+        let timestamp = self.start_timestamp(
+            #[cfg(feature = "call_timing")]
+            start,
+        );
+        self.write_event(LOOPBODY_NAME, ",\"cat\":\"loopbody\",\"ph\":\"B\"", "", timestamp);
+    }
+    fn notify_loopbody_end(&mut self, _call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        duration: Option<Duration>,
+    ) {
+        let timestamp = self.end_timestamp(
+            #[cfg(feature = "call_timing")]
+            duration,
+        );
+        self.write_event(LOOPBODY_NAME, ",\"cat\":\"loopbody\",\"ph\":\"E\"", "", timestamp);
+    }
+    fn notify_folding_suspended(&mut self, _call_depth: usize) {
+        self.write_instant_event(FOLDING_SUSPENDED_NOTE, "folding_suspended", "");
+    }
+}
+
+impl ThreadSpecific for ChromeTraceDecorator {
+    fn set_thread_indent(&mut self, thread_indent: String) {
+        self.common.set_thread_indent(thread_indent); // Is not used.
+    }
+}
+
+impl WriterPossessor for ChromeTraceDecorator {
+    fn set_writer(&mut self, writer: Rc<RefCell<dyn Write>>) {
+        self.common.set_writer(writer);
+        // The substitute writer is a separate output that needs its own array start and thread name.
+        self.array_start.set_own_output();
+        self.thread_name_logged = false;
+    }
+}
+
+impl LogDecorator for ChromeTraceDecorator {}
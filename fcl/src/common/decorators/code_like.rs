@@ -0,0 +1,415 @@
+use std::{
+    cell::RefCell,
+    io::Write,
+    rc::Rc,
+};
+
+use code_commons::{CoderunNotifiable, ItemKind, RepeatCountCategory};
+#[cfg(feature = "call_timing")]
+use code_commons::DurationStats;
+#[cfg(feature = "call_timing")]
+use std::time::{Duration, Instant};
+
+use super::*;
+
+/// The decorator that logs the function calls in a code-like manner (as opposed to a tree-like manner).
+///
+/// For example,
+/// ```txt
+/// f() {
+///     g() {}
+///     // g() repeats 3 time(s).
+///     h() {}
+/// }
+/// ```
+pub struct CodeLikeDecorator {
+    /// The part (of the decorator) common for multiple decorators.
+    common: CommonDecorator,
+    /// The indent step used for indenting functions with different call depth.
+    ///
+    /// Typically consists of a single Tab character (`'\t'`) or multiple spaces,
+    /// but can be any string.
+    indent_step: String, // TODO (func_indent): Consider -> func_indent_step (to clearly distinguish from the thread_indent).
+    /// Tells that the line end `'\n'` is pending after `f() {` before
+    /// * loggign a nested call,
+    /// * or a different thread's output,
+    /// * or an `stdout` and/or `stderr` output by a user's code or panic hook.
+    line_end_pending: bool, // '\n' pending after "f() {" before printing a nested call.
+    /// The optional ANSI foreground color code of the thread's lines (e.g. `36` for cyan).
+    /// If `None` then the lines are not colored.
+    thread_color: Option<u8>,
+}
+
+impl CodeLikeDecorator {
+    /// Creates a new `CodeLikeDecorator` with the optional writer and optional indent step.
+    /// * If the writer is `None` then uses the one in the `CommonDecorator`.
+    /// * If the indent step is `None` then uses 2 spaces.
+    pub fn new(writer: Option<Box<dyn Write>>, indent_step: Option<&'static str>) -> Self {
+        Self {
+            common: CommonDecorator::new(writer),
+            indent_step: String::from(indent_step.unwrap_or(defaults::INDENT_STEP)),
+            line_end_pending: false,
+            thread_color: None,
+        }
+    }
+    /// Sets the indent step used for indenting functions with different call depth
+    /// (e.g. the one specified in the configuration rather than a `'static` string slice).
+    pub fn set_indent_step(&mut self, indent_step: String) {
+        self.indent_step = indent_step;
+    }
+    /// Sets the optional ANSI foreground color code of the thread's lines (e.g. `Some(36)` for cyan).
+    /// If `None` then the lines are not colored (the default).
+    ///
+    /// In the colored lines the function and closure names are bold, the parameter values are italic,
+    /// the return values are underlined, and the repeat count comments are faint.
+    pub fn set_thread_color(&mut self, thread_color: Option<u8>) {
+        self.thread_color = thread_color;
+    }
+    /// Sets the optional prefixes of each line (timestamps, thread name and ID).
+    /// The continuation of a line (e.g. `}` in `f() {}`) is not prefixed.
+    pub fn set_line_prefixes(&mut self, line_prefixes: LinePrefixes) {
+        self.common.set_line_prefixes(line_prefixes);
+    }
+    /// Enables or disables logging the thread start header and thread end summary lines (disabled by default).
+    pub fn set_thread_headers(&mut self, thread_headers: bool) {
+        self.common.set_thread_headers(thread_headers);
+    }
+    /// Logs the thread start header or thread end summary line (see `CommonDecorator::get_thread_header()`)
+    /// if enabled.
+    fn log_thread_header(&mut self, thread: &std::thread::Thread, event: &str) {
+        if !self.common.thread_headers {
+            return;
+        }
+        if self.line_end_pending {
+            decorator_write!(self, "\n"); // '\n' after "f() {" (e.g. the thread has panicked in `f()`).
+            self.line_end_pending = false;
+        }
+        let (color_start, color_end) = self.color_bounds();
+        let header = self.common.get_thread_header(thread, event);
+        decorator_write!(self, "{}{}{}\n", color_start, header, color_end);
+    }
+    /// Returns the pair of the ANSI escape sequences starting and ending the thread's color,
+    /// or the pair of empty strings if the lines are not colored.
+    fn color_bounds(&self) -> (String, &'static str) {
+        self.thread_color.map_or((String::new(), ""), |thread_color| {
+            (format!("\x1b[{}m", thread_color), ANSI_RESET)
+        })
+    }
+    /// Returns the text surrounded by the ANSI escape sequences turning the style on and off
+    /// (e.g. `ANSI_BOLD` and `ANSI_BOLD_OFF`), or the text itself if the lines are not colored.
+    /// The thread's color is not affected.
+    fn styled(&self, style: (&str, &str), text: &str) -> String {
+        if self.thread_color.is_none() || text.is_empty() {
+            return text.to_string();
+        }
+        format!("{}{}{}", style.0, text, style.1)
+    }
+    /// Returns the indent string for the specified call depth. In other words, a string containing
+    /// `self.indent_step` `call_depth` times.
+    fn get_indent_string(&self, call_depth: usize) -> String {
+        // TODO (func_indent): Consider -> get_func_indent_string.
+        let mut indent_string = String::with_capacity(8);
+        for _ in 0..call_depth {
+            indent_string.push_str(&self.indent_step);
+        }
+        indent_string
+    }
+    /// Returns a tuple of
+    /// * the line prefixes (if any) followed by the thread indent
+    /// * and function indent string (that reflects the call depth).
+    ///
+    /// Those combined provide an overall indent for logging a line by the current thread.
+    fn get_indents(&self, call_depth: usize) -> (String, String) {
+        (
+            self.common.get_line_start(),
+            self.get_indent_string(call_depth),
+        )
+    }
+    /// Logs the call, e.g. `f() {`, or `f() recursion depth 40 {` if the call is recursion-folded.
+    fn log_call(&mut self, call_depth: usize, name: &str, 
+        #[cfg(feature = "params_logging")]
+        param_vals: &Option<String>,
+        recursion_depth: Option<usize>,
+    ) {
+        if self.line_end_pending {
+            decorator_write!(self, "\n"); // '\n' after "parent() {" before printing a nested call.
+        }
+        let indents = self.get_indents(call_depth); // TODO: Consider -> `let (thread_indent, func_indent) =`.
+
+        #[cfg(feature = "params_logging")]
+        let param_vals = param_vals
+                .as_ref()
+                .map(|string| string.as_str())
+                .unwrap_or("");
+        #[cfg(not(feature = "params_logging"))]
+        let param_vals = "";
+
+        let recursion_depth = recursion_depth.map_or_else(
+            || "".to_string(), // None -> "".
+            |recursion_depth| format!(" {} {}", RECURSION_DEPTH_LABEL, recursion_depth),
+        ); // Some() -> " recursion depth 40".
+
+        let (color_start, color_end) = self.color_bounds();
+        let name = self.styled(ANSI_BOLD, name);
+        let param_vals = self.styled(ANSI_ITALIC, param_vals);
+        decorator_write!(
+            self,
+            "{}{}{}{}({}){} {{{}",
+            indents.0,
+            indents.1,
+            color_start,
+            name,
+            param_vals,
+            recursion_depth,
+            color_end,
+        ); // E.g. "<thread_indent><indent>sibling() {", "<thread_indent><indent>sibling() recursion depth 40 {"
+        self.line_end_pending = true; // '\n' pending. Won't be printed if there will be no nested calls (immediate "}\n").
+    }
+}
+
+/// The ANSI escape sequence resetting all the colors and styles.
+const ANSI_RESET: &str = "\x1b[0m";
+/// The ANSI escape sequences turning the bold style on and off (used for the function and closure names).
+const ANSI_BOLD: (&str, &str) = ("\x1b[1m", "\x1b[22m");
+/// The ANSI escape sequences turning the italic style on and off (used for the parameter values).
+const ANSI_ITALIC: (&str, &str) = ("\x1b[3m", "\x1b[23m");
+/// The ANSI escape sequences turning the underline on and off (used for the return values).
+#[cfg(feature = "ret_val_logging")]
+const ANSI_UNDERLINE: (&str, &str) = ("\x1b[4m", "\x1b[24m");
+/// The ANSI escape sequences turning the faint style on and off (used for the repeat count comments).
+const ANSI_FAINT: (&str, &str) = ("\x1b[2m", "\x1b[22m");
+
+impl CoderunNotifiable for CodeLikeDecorator {
+    fn notify_flush(&mut self) {
+        if self.line_end_pending {
+            decorator_write!(self, "\n"); // '\n' after "parent() {" before an output of another thread.
+            self.line_end_pending = false;
+        }
+    }
+    fn notify_call(&mut self, call_depth: usize, name: &str, 
+        #[cfg(feature = "params_logging")]
+        param_vals: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        self.log_call(
+            call_depth,
+            name,
+            #[cfg(feature = "params_logging")]
+            param_vals,
+            None,
+        );
+    }
+    fn notify_recursive_call(&mut self, call_depth: usize, name: &str,
+        #[cfg(feature = "params_logging")]
+        param_vals: &Option<String>,
+        recursion_depth: usize,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        self.log_call(
+            call_depth,
+            name,
+            #[cfg(feature = "params_logging")]
+            param_vals,
+            Some(recursion_depth),
+        );
+    }
+    fn notify_return(
+        &mut self,
+        call_depth: usize,
+        name: &str,
+        has_nested_calls: bool,
+        #[cfg(feature = "ret_val_logging")]
+        ret_val: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        duration: Option<Duration>,
+    ) {
+        #[cfg(feature = "ret_val_logging")]
+        let ret_val_str = ret_val.as_ref().map_or_else(
+            || "".to_string(), // None -> "".
+            |output| format!(" -> {}", self.styled(ANSI_UNDERLINE, output)),
+        ); // Some() -> " -> Value".
+        #[cfg(not(feature = "ret_val_logging"))]
+        let ret_val_str = "";
+
+        #[cfg(feature = "call_timing")]
+        let duration_str = duration.map_or_else(
+            || "".to_string(), // None -> "".
+            |duration| format!(", {:.1?}", duration),
+        ); // Some() -> ", 1.2ms".
+        #[cfg(not(feature = "call_timing"))]
+        let duration_str = "";
+        
+        let (color_start, color_end) = self.color_bounds();
+        if !has_nested_calls && self.line_end_pending {
+            if duration_str.is_empty() {
+                decorator_write!(self, "{}}}{}{}\n", color_start, ret_val_str, color_end); // "}\n" or "} -> RetVal\n".
+            } else {
+                // "} // 1.2ms.\n" or "} -> RetVal // 1.2ms.\n".
+                decorator_write!(self, "{}}}{} // {}.{}\n", color_start, ret_val_str, &duration_str[2..], color_end); // `[2..]`: Skip ", ".
+            }
+        } else {
+            let indents = self.get_indents(call_depth); // TODO: Consider -> `let (thread_indent, func_indent) =`.
+            decorator_write!(
+                self,
+                "{}{}{}}}{} // {}(){}.{}\n", // E.g. "<thread_indent><indent>} -> RetVal // sibling().\n", "} // sibling(), 1.2ms.\n".
+                indents.0,
+                indents.1,
+                color_start,
+                ret_val_str,
+                name,
+                duration_str,
+                color_end,
+            );
+        }
+        self.line_end_pending = false;
+    }
+    fn notify_repeat_count(
+        &mut self,
+        call_depth: usize,
+        kind: &ItemKind, // TODO: Consider -> item_kind or call_tree_item_kind.
+        count: RepeatCountCategory,
+        #[cfg(feature = "call_timing")]
+        timing: &DurationStats,
+    ) {
+        let item_name = match kind {
+            ItemKind::Call { name, .. } => format!("{}()", name),
+            ItemKind::Loopbody { .. } => String::from(LOOPBODY_NAME), // "Loop body".
+        };
+        #[cfg(feature = "call_timing")]
+        let timing_str = timing_string(timing); // E.g. ", total 12.3ms, min 10.1µs, avg 12.3µs, max 400.2µs".
+        #[cfg(not(feature = "call_timing"))]
+        let timing_str = "";
+        let indents = self.get_indents(call_depth); // TODO: Consider -> `let (thread_indent, func_indent) =`.
+        let (color_start, color_end) = self.color_bounds();
+        let comment = self.styled(
+            ANSI_FAINT,
+            &format!("// {} repeats {} time(s){}.", item_name, count.to_string(), timing_str),
+        );
+        decorator_write!(
+            self,
+            "{}{}{}{}{}\n", // E.g. "<thread_indent><indent>// sibling() repeats 8 time(s).\n"
+            indents.0,
+            indents.1,
+            color_start,
+            comment,
+            color_end,
+        );
+    }
+    fn notify_sequence_repeat_count(
+        &mut self,
+        call_depth: usize,
+        kinds: &[ItemKind],
+        count: RepeatCountCategory,
+    ) {
+        let item_names = kinds
+            .iter()
+            .map(|kind| match kind {
+                ItemKind::Call { name, .. } => format!("{}()", name),
+                ItemKind::Loopbody { .. } => String::from(LOOPBODY_NAME), // "Loop body".
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+        let indents = self.get_indents(call_depth); // TODO: Consider -> `let (thread_indent, func_indent) =`.
+        let (color_start, color_end) = self.color_bounds();
+        let comment = self.styled(
+            ANSI_FAINT,
+            &format!("// ({}) repeats {} time(s).", item_names, count.to_string()),
+        );
+        decorator_write!(
+            self,
+            "{}{}{}{}{}\n", // E.g. "<thread_indent><indent>// (a(), b()) repeats 8 time(s).\n"
+            indents.0,
+            indents.1,
+            color_start,
+            comment,
+            color_end,
+        );
+    }
+    fn notify_loopbody_start(&mut self, call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        if self.line_end_pending {
+            decorator_write!(self, "\n"); // '\n' after "parent() {" before printing a nested call.
+        }
+        let indents = self.get_indents(call_depth); // TODO: Consider -> `let (thread_indent, func_indent) =`.
+        let (color_start, color_end) = self.color_bounds();
+        decorator_write!(
+            self,
+            "{}{}{}{{ // {} start.{}\n",
+            indents.0,
+            indents.1,
+            color_start,
+            LOOPBODY_NAME,
+            color_end,
+        ); // E.g. "<thread_indent><indent>{ // Loop body start."
+        self.line_end_pending = false;
+    }
+    fn notify_loopbody_end(&mut self, call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        duration: Option<Duration>,
+    ) {
+        #[cfg(feature = "call_timing")]
+        let duration_str = duration.map_or_else(
+            || "".to_string(), // None -> "".
+            |duration| format!(", {:.1?}", duration),
+        ); // Some() -> ", 1.2ms".
+        #[cfg(not(feature = "call_timing"))]
+        let duration_str = "";
+        let indents = self.get_indents(call_depth); // TODO: Consider -> `let (thread_indent, func_indent) =`.
+        let (color_start, color_end) = self.color_bounds();
+        decorator_write!(
+            self,
+            "{}{}{}}} // {} end{}.{}\n", // E.g. "<thread_indent><indent>} // Loop body end.\n", "} // Loop body end, 1.2ms.\n".
+            indents.0,
+            indents.1,
+            color_start,
+            LOOPBODY_NAME,
+            duration_str,
+            color_end,
+        );
+        self.line_end_pending = false;
+    }
+    fn notify_folding_suspended(&mut self, call_depth: usize) {
+        if self.line_end_pending {
+            decorator_write!(self, "\n"); // '\n' after "parent() {" before printing the note.
+        }
+        let indents = self.get_indents(call_depth); // TODO: Consider -> `let (thread_indent, func_indent) =`.
+        let (color_start, color_end) = self.color_bounds();
+        decorator_write!(
+            self,
+            "{}{}{}// {}{}\n", // E.g. "<thread_indent><indent>// FCL: repeat folding suspended (node budget reached)\n".
+            indents.0,
+            indents.1,
+            color_start,
+            FOLDING_SUSPENDED_NOTE,
+            color_end,
+        );
+        self.line_end_pending = false;
+    }
+}
+
+impl ThreadSpecific for CodeLikeDecorator {
+    fn set_thread_indent(&mut self, thread_indent: String) {
+        self.common.set_thread_indent(thread_indent);
+    }
+    fn notify_thread_start(&mut self, thread: &std::thread::Thread) {
+        self.log_thread_header(thread, "started");
+    }
+    fn notify_thread_end(&mut self, thread: &std::thread::Thread, call_count: usize) {
+        self.log_thread_header(thread, &format!("ended, {} call(s)", call_count));
+    }
+}
+
+impl WriterPossessor for CodeLikeDecorator {
+    fn set_writer(&mut self, writer: Rc<RefCell<dyn Write>>) {
+        self.common.set_writer(writer);
+        if ansi_colors() == AnsiColors::Auto {
+            self.thread_color = None; // The substitute writer is not known to be a terminal.
+        }
+    }
+}
+
+impl LogDecorator for CodeLikeDecorator {}
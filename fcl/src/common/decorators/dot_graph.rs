@@ -0,0 +1,248 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+    rc::Rc,
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use code_commons::{CoderunNotifiable, ItemKind, RepeatCountCategory};
+#[cfg(feature = "call_timing")]
+use code_commons::DurationStats;
+#[cfg(feature = "call_timing")]
+use std::time::{Duration, Instant};
+
+use super::*;
+use super::folded_stack::{StackAggregator, repeat_count_value};
+
+/// The decorator that aggregates all the threads' calls into the static call graph
+/// ("who calls whom, how often") and writes it in the [Graphviz DOT](https://graphviz.org/doc/info/lang.html) format.
+///
+/// Each function or closure is a node labeled with its total call count,
+/// each caller-callee pair is an edge labeled with the number of calls (the repeats included).
+/// The calls made outside of any logged call are the edges from the thread's node (e.g. `"thread main"`).
+///
+/// For example,
+/// ```txt
+/// digraph fcl {
+///   node [shape=box];
+///   "f" [label="f\n1 call(s)"];
+///   "g" [label="g\n3 call(s)"];
+///   "thread main" [shape=ellipse];
+///   "f" -> "g" [label="3"];
+///   "thread main" -> "f" [label="1"];
+/// }
+/// ```
+/// The thread's aggregates are merged into the process-wide graph upon the call graph flush
+/// and upon the decorator drop (at the thread exit).
+///
+/// The reliable way to get the graph is writing it explicitly with `write_dot_graph()` (after the log flush),
+/// since the decorators of the threads still running at the process exit are not dropped.
+/// Otherwise the graph is written upon the drop of the last decorator alive (at the exit of the last logged thread)
+/// to the output set with `fcl::common::settings::set_dot_graph_output()`, or (if not set) to that decorator's writer
+/// (that is the shared log output with the "log_merger" feature, but is the thread's log file otherwise).
+pub struct DotGraphDecorator {
+    /// The part (of the decorator) common for multiple decorators.
+    /// The thread indent is not used.
+    common: CommonDecorator,
+    /// The aggregator of the call counts by the call stacks.
+    aggregator: StackAggregator,
+    /// The name of the thread's node (e.g. `"thread main"`).
+    thread_node: String,
+}
+
+/// The process-wide static call graph.
+#[derive(Default)]
+struct DotGraph {
+    /// The total call counts by the function or closure name.
+    call_counts: BTreeMap<String, u64>,
+    /// The call counts by the caller and callee names.
+    edges: BTreeMap<(String, String), u64>,
+    /// The thread node names.
+    thread_nodes: BTreeSet<String>,
+}
+
+/// The process-wide static call graph.
+static DOT_GRAPH: Mutex<DotGraph> = Mutex::new(DotGraph {
+    call_counts: BTreeMap::new(),
+    edges: BTreeMap::new(),
+    thread_nodes: BTreeSet::new(),
+});
+/// The number of the `DotGraphDecorator` instances alive.
+static DOT_GRAPH_DECORATORS_ALIVE: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the string with the characters special for the DOT quoted strings escaped.
+fn dot_escaped(name: &str) -> String {
+    name.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Returns the DOT ID (in double quotes, with special characters escaped).
+fn dot_id(name: &str) -> String {
+    format!("\"{}\"", dot_escaped(name))
+}
+
+/// Writes the process-wide static call graph aggregated by the `DotGraphDecorator`s so far.
+///
+/// The calling thread's aggregates not yet merged into the graph are merged upon the thread's log flush.
+/// #### Examples
+/// ```rs
+/// let mut file = std::fs::File::create("calls.dot").unwrap();
+/// fcl::common::decorators::write_dot_graph(&mut file).unwrap();
+/// ```
+pub fn write_dot_graph(writer: &mut dyn Write) -> std::io::Result<()> {
+    let graph = match DOT_GRAPH.lock() {
+        Ok(graph) => graph,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    writeln!(writer, "digraph fcl {{")?;
+    writeln!(writer, "  node [shape=box];")?;
+    for (name, call_count) in &graph.call_counts {
+        writeln!(
+            writer,
+            "  {} [label=\"{}\\n{} call(s)\"];", // E.g. `  "f" [label="f\n1 call(s)"];`.
+            dot_id(name),
+            dot_escaped(name),
+            call_count
+        )?;
+    }
+    for thread_node in &graph.thread_nodes {
+        writeln!(writer, "  {} [shape=ellipse];", dot_id(thread_node))?;
+    }
+    for ((caller, callee), call_count) in &graph.edges {
+        writeln!(writer, "  {} -> {} [label=\"{}\"];", dot_id(caller), dot_id(callee), call_count)?;
+    }
+    writeln!(writer, "}}")
+}
+
+impl DotGraphDecorator {
+    /// Creates a new `DotGraphDecorator` with the optional writer.
+    /// If the writer is `None` then uses the one in the `CommonDecorator`.
+    pub fn new(writer: Option<Box<dyn Write>>) -> Self {
+        DOT_GRAPH_DECORATORS_ALIVE.fetch_add(1, Ordering::Relaxed);
+        let thread = std::thread::current();
+        Self {
+            common: CommonDecorator::new(writer),
+            aggregator: StackAggregator::new(),
+            thread_node: format!(
+                "thread {}",
+                thread.name().map_or_else(|| format!("ThreadId({})", thread_id_number(&thread)), String::from)
+            ),
+        }
+    }
+    /// Merges the aggregates not yet merged into the process-wide graph.
+    fn merge_into_graph(&mut self) {
+        let stack_weights = self.aggregator.take_stack_weights();
+        if stack_weights.is_empty() {
+            return;
+        }
+        let mut graph = match DOT_GRAPH.lock() {
+            Ok(graph) => graph,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        for (stack, call_count) in stack_weights {
+            let mut names = stack.rsplit(';');
+            let Some(callee) = names.next() else {
+                continue;
+            };
+            let caller = names.next().map_or_else(
+                || {
+                    graph.thread_nodes.insert(self.thread_node.clone());
+                    self.thread_node.clone()
+                },
+                String::from,
+            );
+            *graph.call_counts.entry(callee.to_string()).or_default() += call_count;
+            *graph.edges.entry((caller, callee.to_string())).or_default() += call_count;
+        }
+    }
+}
+
+impl Drop for DotGraphDecorator {
+    fn drop(&mut self) {
+        self.merge_into_graph();
+        if DOT_GRAPH_DECORATORS_ALIVE.fetch_sub(1, Ordering::Relaxed) == 1 {
+            // The last logged thread exits.
+            let _ignore_result = match dot_graph_output() {
+                Some(make_writer) => write_dot_graph(&mut *make_writer()),
+                None => match &mut self.common.writer {
+                    Writer::Original(writer) => write_dot_graph(&mut **writer),
+                    Writer::Substitute(writer) => write_dot_graph(&mut *writer.borrow_mut()),
+                },
+            };
+        }
+    }
+}
+
+impl CoderunNotifiable for DotGraphDecorator {
+    fn notify_flush(&mut self) {
+        self.merge_into_graph();
+    }
+    fn notify_call(&mut self, _call_depth: usize, name: &str, 
+        #[cfg(feature = "params_logging")]
+        _param_vals: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        self.aggregator.push_frame(Some(name));
+        self.aggregator.add_weight("", 1); // The call count.
+    }
+    fn notify_return(
+        &mut self,
+        _call_depth: usize,
+        _name: &str,
+        _has_nested_calls: bool,
+        #[cfg(feature = "ret_val_logging")]
+        _ret_val: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        _duration: Option<Duration>,
+    ) {
+        self.aggregator.pop_frame();
+    }
+    fn notify_repeat_count(
+        &mut self,
+        _call_depth: usize,
+        _kind: &ItemKind,
+        count: RepeatCountCategory,
+        #[cfg(feature = "call_timing")]
+        _timing: &DurationStats,
+    ) {
+        self.aggregator.add_repeats(1, repeat_count_value(count), 1);
+    }
+    fn notify_sequence_repeat_count(
+        &mut self,
+        _call_depth: usize,
+        kinds: &[ItemKind],
+        count: RepeatCountCategory,
+    ) {
+        self.aggregator.add_repeats(kinds.len(), repeat_count_value(count), 1);
+    }
+    fn notify_loopbody_start(&mut self, _call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        self.aggregator.push_frame(None);
+    }
+    fn notify_loopbody_end(&mut self, _call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        _duration: Option<Duration>,
+    ) {
+        self.aggregator.pop_frame();
+    }
+}
+
+impl ThreadSpecific for DotGraphDecorator {
+    fn set_thread_indent(&mut self, thread_indent: String) {
+        self.common.set_thread_indent(thread_indent); // Is not used.
+    }
+}
+
+impl WriterPossessor for DotGraphDecorator {
+    fn set_writer(&mut self, writer: Rc<RefCell<dyn Write>>) {
+        self.common.set_writer(writer);
+    }
+}
+
+impl LogDecorator for DotGraphDecorator {}
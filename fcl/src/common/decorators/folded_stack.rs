@@ -0,0 +1,366 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, VecDeque},
+    io::Write,
+    rc::Rc,
+};
+
+use code_commons::{CoderunNotifiable, ItemKind, RepeatCountCategory};
+#[cfg(feature = "call_timing")]
+use code_commons::DurationStats;
+#[cfg(feature = "call_timing")]
+use std::time::{Duration, Instant};
+
+use super::*;
+
+/// The decorator that aggregates the calls into the "folded" (collapsed) stacks,
+/// the input format of the flame graph tools such as [inferno](https://github.com/jonhoo/inferno)
+/// and [flamegraph.pl](https://github.com/brendangregg/FlameGraph).
+///
+/// Each line is a stack of the function and closure names (from the outermost to the innermost)
+/// followed by the weight of that stack. The weight is the number of calls made with exactly that stack,
+/// or (if the `call_timing` feature and the call timing are on) the self-time (the wall-clock duration minus the durations
+/// of the nested calls) of those calls in nanoseconds. The loop bodies are transparent (are not on the stack).
+/// The unit of the weights is chosen upon the first call (by whether the call timing is on) and is kept
+/// for the decorator's lifetime, such that the weights of different units are not mixed
+/// (the calls made with the call timing off add no self-time).
+///
+/// For example,
+/// ```txt
+/// main 1
+/// main;work 3
+/// main;work;parse 6
+/// ```
+/// The repeat counts are multiplied by the aggregates of the latest (repeated) subtree,
+/// e.g. for `// work() repeats 2 time(s).` the aggregates of the latest `work()` subtree are multiplied by 2.
+///
+/// The aggregates are written (and cleared) upon the call graph flush and upon the decorator drop
+/// (at the thread exit). As a result the same stack can be written multiple times, the flame graph tools sum up the weights.
+pub struct FoldedStackDecorator {
+    /// The part (of the decorator) common for multiple decorators.
+    /// The thread indent is not used.
+    common: CommonDecorator,
+    /// The aggregator of the stack weights.
+    aggregator: StackAggregator,
+    /// Whether the stacks are weighted by the self-time (rather than by the call count),
+    /// `None` until the first call.
+    #[cfg(feature = "call_timing")]
+    self_time_weighted: Option<bool>,
+}
+
+/// The subtree aggregates: the pairs of a stack relative to the parent frame (e.g. `"work;parse"`) and its weight.
+type FoldedSubtree = Vec<(String, u64)>;
+
+/// The frame of a call or a loop body being run.
+struct FoldedStackFrame {
+    /// The function or closure name, `None` for a loop body (or the thread pseudo-frame).
+    name: Option<String>,
+    /// The aggregates of the frame's subtree. The stacks are relative to the frame's function or closure
+    /// (`""` stands for the function or closure itself), or relative to the frame's parent for a loop body.
+    subtree: BTreeMap<String, u64>,
+    /// The aggregates of the latest children (used to multiply by the repeat counts).
+    /// The stacks are relative to this frame.
+    latest_children: VecDeque<FoldedSubtree>,
+    /// The total wall-clock duration (in nanoseconds) of the nested calls.
+    #[cfg(feature = "call_timing")]
+    children_duration: u64,
+}
+
+impl FoldedStackFrame {
+    /// Creates a new frame of a call (if the name is not `None`) or a loop body.
+    fn new(name: Option<String>) -> Self {
+        Self {
+            name,
+            subtree: BTreeMap::new(),
+            latest_children: VecDeque::new(),
+            #[cfg(feature = "call_timing")]
+            children_duration: 0,
+        }
+    }
+    /// Returns the frame's subtree aggregates with the stacks relative to the frame's parent.
+    fn into_parent_relative_subtree(self) -> FoldedSubtree {
+        match &self.name {
+            Some(name) => self
+                .subtree
+                .into_iter()
+                .map(|(stack, weight)| (join_folded_stacks(name, &stack), weight))
+                .collect(),
+            None => self.subtree.into_iter().collect(), // Loop body is transparent.
+        }
+    }
+}
+
+/// Returns the stack with the relative stack appended (`"a;b"` for `"a"` and `"b"`).
+/// An empty stack stands for the stack itself.
+fn join_folded_stacks(stack: &str, relative_stack: &str) -> String {
+    match (stack.is_empty(), relative_stack.is_empty()) {
+        (_, true) => stack.to_string(),
+        (true, false) => relative_stack.to_string(),
+        (false, false) => format!("{};{}", stack, relative_stack),
+    }
+}
+
+/// Returns the number of repeats (`0` if unknown).
+pub(super) fn repeat_count_value(count: RepeatCountCategory) -> u64 {
+    match count {
+        RepeatCountCategory::Exact(exact) => exact as u64,
+        RepeatCountCategory::AtLeast(at_least) => at_least as u64,
+        RepeatCountCategory::Unknown => 0,
+    }
+}
+
+/// The aggregator of the weights of the thread's call stacks (e.g. `"main;work;parse"`),
+/// shared by the decorators that aggregate the calls rather than log them as they come.
+pub(super) struct StackAggregator {
+    /// The stack of the calls and loop bodies being run.
+    /// The bottom frame is the pseudo-frame of the thread (is never popped).
+    frames: Vec<FoldedStackFrame>,
+    /// The weights of the full stacks (e.g. `"main;work;parse"`) not yet taken.
+    stack_weights: BTreeMap<String, u64>,
+}
+
+impl StackAggregator {
+    /// Creates a new `StackAggregator` with the thread pseudo-frame only.
+    pub(super) fn new() -> Self {
+        Self {
+            frames: vec![FoldedStackFrame::new(None)],
+            stack_weights: BTreeMap::new(),
+        }
+    }
+    /// Pushes the frame of a call (if the name is not `None`) or a loop body.
+    pub(super) fn push_frame(&mut self, name: Option<&str>) {
+        self.frames.push(FoldedStackFrame::new(name.map(String::from)));
+    }
+    /// Returns the full stack of the top frame (e.g. `"main;work"`).
+    fn top_stack(&self) -> String {
+        self.frames
+            .iter()
+            .filter_map(|frame| frame.name.as_deref())
+            .collect::<Vec<&str>>()
+            .join(";")
+    }
+    /// Adds the weight to the stack relative to the top frame (`""` stands for the top frame itself).
+    pub(super) fn add_weight(&mut self, relative_stack: &str, weight: u64) {
+        if weight == 0 {
+            return;
+        }
+        let stack = join_folded_stacks(&self.top_stack(), relative_stack);
+        if stack.is_empty() {
+            return; // The weight of a loop body outside of any call, e.g. in a non-logged function.
+        }
+        *self.stack_weights.entry(stack).or_default() += weight;
+        if let Some(top) = self.frames.last_mut() {
+            *top.subtree.entry(relative_stack.to_string()).or_default() += weight;
+        }
+    }
+    /// Pops the top frame (if it is not the thread pseudo-frame) and merges its aggregates into the parent's.
+    /// Returns the popped frame's children duration.
+    pub(super) fn pop_frame(&mut self) -> Option<u64> {
+        if self.frames.len() < 2 {
+            debug_assert!(false, "Unexpected end of a call or loop body");
+            return None;
+        }
+        let frame = self.frames.pop()?;
+        #[cfg(feature = "call_timing")]
+        let children_duration = frame.children_duration;
+        #[cfg(not(feature = "call_timing"))]
+        let children_duration = 0;
+        let subtree = frame.into_parent_relative_subtree();
+        if let Some(parent) = self.frames.last_mut() {
+            for (stack, weight) in &subtree {
+                *parent.subtree.entry(stack.clone()).or_default() += *weight;
+            }
+            if parent.latest_children.len() == defaults::FOLDED_STACK_LATEST_CHILDREN_MAX {
+                parent.latest_children.pop_front();
+            }
+            parent.latest_children.push_back(subtree);
+        }
+        Some(children_duration)
+    }
+    /// Adds the aggregates of the latest `sibling_count` children of the top frame multiplied by the factor
+    /// (`weight * numerator / denominator`). The multiplied aggregates are also added
+    /// to the latest children such that the repeated children are multiplied as a whole
+    /// by the repeat count of a sequence containing them.
+    /// Returns the added weight.
+    pub(super) fn add_repeats(&mut self, sibling_count: usize, numerator: u64, denominator: u64) -> u64 {
+        let Some(top) = self.frames.last_mut() else {
+            return 0;
+        };
+        if denominator == 0 || sibling_count == 0 || sibling_count > top.latest_children.len() {
+            return 0;
+        }
+        let first_sibling = top.latest_children.len() - sibling_count;
+        let mut repeats = Vec::new();
+        for sibling in top.latest_children.range_mut(first_sibling..) {
+            let sibling_repeats = sibling
+                .iter()
+                .map(|(stack, weight)| {
+                    (stack.clone(), (*weight as u128 * numerator as u128 / denominator as u128) as u64)
+                })
+                .collect::<FoldedSubtree>();
+            for ((_, weight), (_, repeats_weight)) in sibling.iter_mut().zip(sibling_repeats.iter()) {
+                *weight += repeats_weight;
+            }
+            repeats.extend(sibling_repeats);
+        }
+        let mut added_weight = 0;
+        for (stack, weight) in repeats {
+            self.add_weight(&stack, weight);
+            added_weight += weight;
+        }
+        added_weight
+    }
+    /// Takes the aggregates not yet taken.
+    pub(super) fn take_stack_weights(&mut self) -> BTreeMap<String, u64> {
+        std::mem::take(&mut self.stack_weights)
+    }
+}
+
+impl FoldedStackDecorator {
+    /// Creates a new `FoldedStackDecorator` with the optional writer.
+    /// If the writer is `None` then uses the one in the `CommonDecorator`.
+    pub fn new(writer: Option<Box<dyn Write>>) -> Self {
+        Self {
+            common: CommonDecorator::new(writer),
+            aggregator: StackAggregator::new(),
+            #[cfg(feature = "call_timing")]
+            self_time_weighted: None,
+        }
+    }
+    /// Returns whether the stacks are weighted by the self-time. Upon the first call chooses the unit
+    /// by whether the call is timed (has the `start` moment), i.e. by whether the call timing is on.
+    #[cfg(feature = "call_timing")]
+    fn is_self_time_weighted(&mut self, start: Option<Instant>) -> bool {
+        *self.self_time_weighted.get_or_insert(start.is_some())
+    }
+    /// Writes the aggregates not yet written and clears them.
+    fn write_stack_weights(&mut self) {
+        for (stack, weight) in self.aggregator.take_stack_weights() {
+            decorator_write!(self, "{} {}\n", stack, weight);
+        }
+    }
+}
+
+impl Drop for FoldedStackDecorator {
+    fn drop(&mut self) {
+        self.write_stack_weights();
+    }
+}
+
+impl CoderunNotifiable for FoldedStackDecorator {
+    fn notify_flush(&mut self) {
+        self.write_stack_weights();
+    }
+    fn notify_call(&mut self, _call_depth: usize, name: &str, 
+        #[cfg(feature = "params_logging")]
+        _param_vals: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        start: Option<Instant>,
+    ) {
+        self.aggregator.push_frame(Some(name));
+        #[cfg(feature = "call_timing")]
+        let call_count_weighted = !self.is_self_time_weighted(start);
+        #[cfg(not(feature = "call_timing"))]
+        let call_count_weighted = true;
+        if call_count_weighted {
+            self.aggregator.add_weight("", 1); // The call count.
+        }
+    }
+    fn notify_return(
+        &mut self,
+        _call_depth: usize,
+        _name: &str,
+        _has_nested_calls: bool,
+        #[cfg(feature = "ret_val_logging")]
+        _ret_val: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        duration: Option<Duration>,
+    ) {
+        // The duration (in nanoseconds) if the stacks are weighted by the self-time
+        // (the duration of a call made with the call timing off is unknown and is counted as 0):
+        #[cfg(feature = "call_timing")]
+        let duration = (self.self_time_weighted == Some(true))
+            .then(|| duration.map_or(0, |duration| duration.as_nanos() as u64));
+        #[cfg(feature = "call_timing")]
+        if let (Some(duration), Some(top)) = (duration, self.aggregator.frames.last()) {
+            let self_time = duration.saturating_sub(top.children_duration);
+            self.aggregator.add_weight("", self_time);
+        }
+        let _children_duration = self.aggregator.pop_frame();
+        #[cfg(feature = "call_timing")]
+        if let (Some(duration), Some(parent)) = (duration, self.aggregator.frames.last_mut()) {
+            parent.children_duration += duration;
+        }
+    }
+    fn notify_repeat_count(
+        &mut self,
+        _call_depth: usize,
+        _kind: &ItemKind,
+        count: RepeatCountCategory,
+        #[cfg(feature = "call_timing")]
+        timing: &DurationStats,
+    ) {
+        #[cfg(feature = "call_timing")]
+        if self.self_time_weighted == Some(true) {
+            // Distribute the repeats' total duration proportionally to the latest repeated subtree's weights.
+            let subtree_weight = self.aggregator.frames.last().and_then(|top| top.latest_children.back()).map_or(0, |subtree| {
+                subtree.iter().map(|(_, weight)| *weight).sum::<u64>()
+            });
+            let (numerator, denominator) = if timing.is_empty() || subtree_weight == 0 {
+                (repeat_count_value(count), 1) // The duration is unknown, assume the same as of the latest repeat.
+            } else {
+                (timing.total().as_nanos() as u64, subtree_weight)
+            };
+            let added_weight = self.aggregator.add_repeats(1, numerator, denominator);
+            if let Some(top) = self.aggregator.frames.last_mut() {
+                top.children_duration += added_weight;
+            }
+            return;
+        }
+        self.aggregator.add_repeats(1, repeat_count_value(count), 1);
+    }
+    fn notify_sequence_repeat_count(
+        &mut self,
+        _call_depth: usize,
+        kinds: &[ItemKind],
+        count: RepeatCountCategory,
+    ) {
+        let _added_weight = self.aggregator.add_repeats(kinds.len(), repeat_count_value(count), 1);
+        #[cfg(feature = "call_timing")]
+        if self.self_time_weighted == Some(true)
+            && let Some(top) = self.aggregator.frames.last_mut()
+        {
+            top.children_duration += _added_weight; // Assume the same duration as of the latest repeat.
+        }
+    }
+    fn notify_loopbody_start(&mut self, _call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        self.aggregator.push_frame(None);
+    }
+    fn notify_loopbody_end(&mut self, _call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        _duration: Option<Duration>, // The loop body's self-time belongs to the enclosing call.
+    ) {
+        let _children_duration = self.aggregator.pop_frame();
+        #[cfg(feature = "call_timing")]
+        if let (Some(children_duration), Some(parent)) = (_children_duration, self.aggregator.frames.last_mut()) {
+            parent.children_duration += children_duration;
+        }
+    }
+}
+
+impl ThreadSpecific for FoldedStackDecorator {
+    fn set_thread_indent(&mut self, thread_indent: String) {
+        self.common.set_thread_indent(thread_indent); // Is not used.
+    }
+}
+
+impl WriterPossessor for FoldedStackDecorator {
+    fn set_writer(&mut self, writer: Rc<RefCell<dyn Write>>) {
+        self.common.set_writer(writer);
+    }
+}
+
+impl LogDecorator for FoldedStackDecorator {}
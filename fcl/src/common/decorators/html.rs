@@ -0,0 +1,320 @@
+use std::{
+    cell::RefCell,
+    io::Write,
+    rc::Rc,
+    sync::atomic::AtomicBool,
+};
+
+use code_commons::{CoderunNotifiable, ItemKind, RepeatCountCategory};
+#[cfg(feature = "call_timing")]
+use code_commons::DurationStats;
+#[cfg(feature = "call_timing")]
+use std::time::{Duration, Instant};
+
+use super::*;
+
+/// The decorator that logs the calls to a self-contained HTML file (report)
+/// where each call and loop body is a collapsible `<details>` node, the repeat counts are the badges,
+/// the parameters and return values are shown on hover, and each thread's log is a separate tab.
+///
+/// The threads' logs can be interleaved in the shared output (and a call can be cut by a flush),
+/// so the decorator logs each event as a separate flat element (one per line)
+/// marked with the thread ID, e.g.
+/// ```txt
+/// <div data-t="1" data-e="thread">main</div>
+/// <div data-t="1" data-e="call" data-p="x: 1">f</div>
+/// <div data-t="1" data-e="call">g</div>
+/// <div data-t="1" data-e="ret"></div>
+/// <div data-t="1" data-e="repeat">2</div>
+/// <div data-t="1" data-e="ret" data-v="5"></div>
+/// ```
+/// and the script in the HTML prolog (logged before the first event) turns the flat elements
+/// into the per-thread trees upon the page load.
+/// The closing tags of the HTML document are not logged (the log can be interrupted at any moment),
+/// the browsers tolerate that.
+pub struct HtmlDecorator {
+    /// The part (of the decorator) common for multiple decorators.
+    /// The thread indent is not used (the threads' logs are separate tabs).
+    common: CommonDecorator,
+    /// The ID of the thread that has created the decorator
+    /// (the decorator is thread-local, but can be flushed by a different thread).
+    thread_id: String,
+    /// The name of the thread that has created the decorator (the thread ID if the thread is unnamed).
+    thread_name: String,
+    /// Whether the thread (tab) start event has been logged.
+    thread_start_logged: bool,
+    /// Whether the HTML prolog has been logged.
+    prolog: OutputStart,
+}
+
+/// Whether the HTML prolog has been logged to the shared output.
+static HTML_PROLOG_LOGGED: AtomicBool = AtomicBool::new(false);
+
+/// The HTML document start (logged once per output before the first event),
+/// including the style and the script that builds the per-thread trees of the flat event elements.
+/// Ends with the start of the `fcl-events` element containing the events.
+const HTML_PROLOG: &str = concat!(
+    "<!DOCTYPE html>\n",
+    "<html><head><meta charset=\"utf-8\"><title>FCL log</title>\n",
+    "<style>\n",
+    "body{font-family:monospace}\n",
+    "#fcl-tabs button{font-family:monospace;margin-right:2px}\n",
+    "#fcl-tabs button.active{font-weight:bold}\n",
+    "details{margin-left:1.5em}\n",
+    "details.leaf>summary{list-style:none}\n",
+    "details.leaf>summary::-webkit-details-marker{display:none}\n",
+    ".fcl-badge{background:#ddd;border-radius:0.6em;padding:0 0.4em;margin-left:0.4em;font-size:0.85em}\n",
+    ".fcl-note{margin-left:1.5em;color:#a00}\n",
+    ".fcl-ret{color:#666}\n",
+    "</style>\n",
+    "<script>\n",
+    "document.addEventListener('DOMContentLoaded', () => {\n",
+    "  const events = document.getElementById('fcl-events'), tabs = document.getElementById('fcl-tabs'), threads = {};\n",
+    "  const element = (tag, cls, text) => { const e = document.createElement(tag); if (cls) e.className = cls; if (text) e.textContent = text; return e; };\n",
+    "  const show = (id) => { for (const t in threads) { threads[t].pane.hidden = t !== id; threads[t].tab.classList.toggle('active', t === id); } };\n",
+    "  const thread = (id, name) => {\n",
+    "    if (!threads[id]) {\n",
+    "      const tab = element('button', '', name || ('Thread ' + id)), pane = element('div');\n",
+    "      tab.onclick = () => show(id); tabs.appendChild(tab); document.body.appendChild(pane);\n",
+    "      threads[id] = { tab, pane, stack: [pane], latest: null };\n",
+    "      if (Object.keys(threads).length === 1) show(id); else pane.hidden = true;\n",
+    "    }\n",
+    "    return threads[id];\n",
+    "  };\n",
+    "  const node = (t, summaryText, title) => {\n",
+    "    const details = element('details', 'leaf'), summary = element('summary', '', summaryText);\n",
+    "    details.open = true; if (title) summary.title = title; details.appendChild(summary);\n",
+    "    const parent = t.stack[t.stack.length - 1]; if (parent.tagName === 'DETAILS') parent.classList.remove('leaf');\n",
+    "    parent.appendChild(details); t.stack.push(details); t.latest = null;\n",
+    "  };\n",
+    "  const end = (t) => { if (t.stack.length > 1) t.latest = t.stack.pop(); };\n",
+    "  for (const e of Array.from(events.children)) {\n",
+    "    const t = thread(e.dataset.t, e.dataset.e === 'thread' ? e.textContent : null), d = e.dataset;\n",
+    "    const parent = t.stack[t.stack.length - 1];\n",
+    "    switch (d.e) {\n",
+    "      case 'call': node(t, e.textContent + '()' + (d.r ? ' recursion depth ' + d.r : ''), d.p); break;\n",
+    "      case 'ret': {\n",
+    "        const summary = t.stack[t.stack.length - 1].firstChild;\n",
+    "        if (summary && d.v !== undefined) { summary.appendChild(element('span', 'fcl-ret', ' -> ' + d.v)); }\n",
+    "        if (summary && d.d) summary.title = (summary.title ? summary.title + '\\n' : '') + d.d;\n",
+    "        end(t); break;\n",
+    "      }\n",
+    "      case 'lb': node(t, 'Loop body', null); break;\n",
+    "      case 'lbe': { const summary = t.stack[t.stack.length - 1].firstChild; if (summary && d.d) summary.title = d.d; end(t); break; }\n",
+    "      case 'repeat': {\n",
+    "        const badge = element('span', 'fcl-badge', 'repeats ' + e.textContent + ' time(s)'); if (d.s) badge.title = d.s;\n",
+    "        if (t.latest) t.latest.firstChild.appendChild(badge); else parent.appendChild(badge); break;\n",
+    "      }\n",
+    "      case 'seq': {\n",
+    "        const badge = element('div', 'fcl-note', '(' + d.n + ')'); badge.style.color = 'inherit';\n",
+    "        badge.appendChild(element('span', 'fcl-badge', 'repeats ' + e.textContent + ' time(s)')); parent.appendChild(badge); break;\n",
+    "      }\n",
+    "      case 'note': parent.appendChild(element('div', 'fcl-note', e.textContent)); break;\n",
+    "    }\n",
+    "  }\n",
+    "  events.remove();\n",
+    "});\n",
+    "</script>\n",
+    "</head><body>\n",
+    "<div id=\"fcl-tabs\"></div>\n",
+    "<div id=\"fcl-events\">\n",
+);
+
+/// Returns the string with the HTML special characters escaped.
+fn html_escaped(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len());
+    for character in string.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            character => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+/// Returns the ` name="value"` attribute (with the value escaped) or an empty string if the value is `None`.
+#[cfg(any(feature = "params_logging", feature = "ret_val_logging", feature = "call_timing"))]
+fn html_optional_attribute(name: &str, value: &Option<String>) -> String {
+    value.as_ref().map_or_else(String::new, |value| {
+        format!(" {}=\"{}\"", name, html_escaped(value))
+    })
+}
+
+impl HtmlDecorator {
+    /// Creates a new `HtmlDecorator` with the optional writer.
+    /// If the writer is `None` then uses the one in the `CommonDecorator`.
+    ///
+    /// The events are marked with the ID of the thread calling this function.
+    pub fn new(writer: Option<Box<dyn Write>>) -> Self {
+        let thread = std::thread::current();
+        let thread_id = thread_id_number(&thread);
+        Self {
+            common: CommonDecorator::new(writer),
+            thread_name: thread.name().map_or_else(|| format!("ThreadId({})", thread_id), String::from),
+            thread_id,
+            thread_start_logged: false,
+            prolog: OutputStart::new(&HTML_PROLOG_LOGGED),
+        }
+    }
+    /// Writes an event element. Before the first event writes the HTML prolog (if not yet written to the output)
+    /// and the thread (tab) start event.
+    /// # Parameters
+    /// * The event kind (the `data-e` attribute value).
+    /// * The extra attributes (e.g. ` data-p="x: 1"`), each prefixed with a space.
+    /// * The element text (not escaped yet).
+    fn write_event(&mut self, event: &str, attributes: &str, text: &str) {
+        if self.prolog.take_pending() {
+            decorator_write!(self, "{}", HTML_PROLOG);
+        }
+        if !self.thread_start_logged {
+            self.thread_start_logged = true;
+            decorator_write!(
+                self,
+                "<div data-t=\"{}\" data-e=\"thread\">{}</div>\n",
+                self.thread_id,
+                html_escaped(&self.thread_name)
+            );
+        }
+        decorator_write!(
+            self,
+            "<div data-t=\"{}\" data-e=\"{}\"{}>{}</div>\n",
+            self.thread_id,
+            event,
+            attributes,
+            html_escaped(text)
+        );
+    }
+}
+
+impl CoderunNotifiable for HtmlDecorator {
+    fn notify_call(&mut self, _call_depth: usize, name: &str, 
+        #[cfg(feature = "params_logging")]
+        param_vals: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        #[cfg(feature = "params_logging")]
+        let attributes = html_optional_attribute("data-p", param_vals);
+        #[cfg(not(feature = "params_logging"))]
+        let attributes = String::new();
+        self.write_event("call", &attributes, name);
+    }
+    fn notify_recursive_call(&mut self, _call_depth: usize, name: &str,
+        #[cfg(feature = "params_logging")]
+        param_vals: &Option<String>,
+        recursion_depth: usize,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        #[cfg(feature = "params_logging")]
+        let attributes = html_optional_attribute("data-p", param_vals);
+        #[cfg(not(feature = "params_logging"))]
+        let attributes = String::new();
+        self.write_event(
+            "call",
+            &format!("{} data-r=\"{}\"", attributes, recursion_depth),
+            name,
+        );
+    }
+    fn notify_return(
+        &mut self,
+        _call_depth: usize,
+        _name: &str,
+        _has_nested_calls: bool,
+        #[cfg(feature = "ret_val_logging")]
+        ret_val: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        duration: Option<Duration>,
+    ) {
+        #[cfg(feature = "ret_val_logging")]
+        let ret_val_attribute = html_optional_attribute("data-v", ret_val);
+        #[cfg(not(feature = "ret_val_logging"))]
+        let ret_val_attribute = "";
+        #[cfg(feature = "call_timing")]
+        let duration_attribute =
+            html_optional_attribute("data-d", &duration.map(|duration| format!("{:.1?}", duration)));
+        #[cfg(not(feature = "call_timing"))]
+        let duration_attribute = "";
+        self.write_event("ret", &format!("{}{}", ret_val_attribute, duration_attribute), "");
+    }
+    fn notify_repeat_count(
+        &mut self,
+        _call_depth: usize,
+        _kind: &ItemKind, // The repeated item is the latest one.
+        count: RepeatCountCategory,
+        #[cfg(feature = "call_timing")]
+        timing: &DurationStats,
+    ) {
+        #[cfg(feature = "call_timing")]
+        let timing_attribute = html_optional_attribute(
+            "data-s",
+            &Some(timing_string(timing))
+                .filter(|timing| !timing.is_empty())
+                .map(|timing| timing.trim_start_matches(", ").to_string()),
+        );
+        #[cfg(not(feature = "call_timing"))]
+        let timing_attribute = String::new();
+        self.write_event("repeat", &timing_attribute, &count.to_string());
+    }
+    fn notify_sequence_repeat_count(
+        &mut self,
+        _call_depth: usize,
+        kinds: &[ItemKind],
+        count: RepeatCountCategory,
+    ) {
+        let item_names = kinds
+            .iter()
+            .map(|kind| match kind {
+                ItemKind::Call { name, .. } => format!("{}()", name),
+                ItemKind::Loopbody { .. } => String::from(LOOPBODY_NAME), // "Loop body".
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+        self.write_event(
+            "seq",
+            &format!(" data-n=\"{}\"", html_escaped(&item_names)),
+            &count.to_string(),
+        );
+    }
+    fn notify_loopbody_start(&mut self, _call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        self.write_event("lb", "", "");
+    }
+    fn notify_loopbody_end(&mut self, _call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        duration: Option<Duration>,
+    ) {
+        #[cfg(feature = "call_timing")]
+        let duration_attribute =
+            html_optional_attribute("data-d", &duration.map(|duration| format!("{:.1?}", duration)));
+        #[cfg(not(feature = "call_timing"))]
+        let duration_attribute = String::new();
+        self.write_event("lbe", &duration_attribute, "");
+    }
+    fn notify_folding_suspended(&mut self, _call_depth: usize) {
+        self.write_event("note", "", FOLDING_SUSPENDED_NOTE);
+    }
+}
+
+impl ThreadSpecific for HtmlDecorator {
+    fn set_thread_indent(&mut self, thread_indent: String) {
+        self.common.set_thread_indent(thread_indent); // Is not used.
+    }
+}
+
+impl WriterPossessor for HtmlDecorator {
+    fn set_writer(&mut self, writer: Rc<RefCell<dyn Write>>) {
+        self.common.set_writer(writer);
+        // The substitute writer is a separate output that needs its own prolog and thread start.
+        self.prolog.set_own_output();
+        self.thread_start_logged = false;
+    }
+}
+
+impl LogDecorator for HtmlDecorator {}
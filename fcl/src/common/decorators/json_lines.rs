@@ -0,0 +1,294 @@
+use std::{
+    cell::RefCell,
+    io::Write,
+    rc::Rc,
+};
+
+use code_commons::{CoderunNotifiable, ItemKind, RepeatCountCategory};
+#[cfg(feature = "call_timing")]
+use code_commons::DurationStats;
+#[cfg(feature = "call_timing")]
+use std::time::{Duration, Instant};
+
+use super::*;
+
+/// The decorator that logs each call graph event as a separate JSON object on a separate line
+/// ([JSON Lines](https://jsonlines.org/)), such that the log can be post-processed by the tools
+/// without parsing the human-readable text.
+///
+/// For example,
+/// ```txt
+/// {"thread_id":1,"thread_name":"main","event":"call","depth":0,"name":"f","params":"x: 1"}
+/// {"thread_id":1,"thread_name":"main","event":"call","depth":1,"name":"g","params":null}
+/// {"thread_id":1,"thread_name":"main","event":"return","depth":1,"name":"g","has_nested_calls":false,"ret_val":null}
+/// {"thread_id":1,"thread_name":"main","event":"repeat_count","depth":1,"kind":"call","name":"g","category":"exact","count":3}
+/// {"thread_id":1,"thread_name":"main","event":"return","depth":0,"name":"f","has_nested_calls":true,"ret_val":"5"}
+/// ```
+/// The `"params"` and `"ret_val"` fields are present with the "params_logging" and "ret_val_logging" features,
+/// the `"duration_ns"` and the repeats' duration statistics fields are present with the "call_timing" feature
+/// and the call timing on.
+/// With `set_thread_headers()` the `"thread_start"` and `"thread_end"` (with the `"call_count"` field) events are also logged.
+pub struct JsonLinesDecorator {
+    /// The part (of the decorator) common for multiple decorators.
+    /// The thread indent is not used (the JSON lines are not indented).
+    common: CommonDecorator,
+    /// The `"thread_id":..,"thread_name":..` fragment identifying the thread that has created the decorator
+    /// (the decorator is thread-local, but can be flushed by a different thread).
+    thread_fields: String,
+}
+
+impl JsonLinesDecorator {
+    /// Creates a new `JsonLinesDecorator` with the optional writer.
+    /// If the writer is `None` then uses the one in the `CommonDecorator`.
+    ///
+    /// The log lines are marked with the ID and name of the thread calling this function.
+    pub fn new(writer: Option<Box<dyn Write>>) -> Self {
+        let thread = std::thread::current();
+        Self {
+            common: CommonDecorator::new(writer),
+            thread_fields: format!(
+                "\"thread_id\":{},\"thread_name\":{}",
+                thread_id_number(&thread),
+                json_optional_string(&thread.name().map(String::from))
+            ),
+        }
+    }
+    /// Enables or disables logging the `"thread_start"` and `"thread_end"` events (disabled by default).
+    pub fn set_thread_headers(&mut self, thread_headers: bool) {
+        self.common.set_thread_headers(thread_headers);
+    }
+    /// Writes a JSON object containing the thread fields, the event name, the call depth, and the other fields.
+    /// # Parameters
+    /// * The event name (e.g. `"call"`).
+    /// * The call depth.
+    /// * The other fields (e.g. `,"name":"f"`), each prefixed with a comma.
+    fn write_event(&mut self, event: &str, call_depth: usize, fields: &str) {
+        decorator_write!(
+            self,
+            "{{{},\"event\":\"{}\",\"depth\":{}{}}}\n", // E.g. `{"thread_id":1,"thread_name":"main","event":"call","depth":0,"name":"f"}`.
+            self.thread_fields,
+            event,
+            call_depth,
+            fields,
+        );
+    }
+}
+/// Returns the JSON string literal (in double quotes, with special characters escaped).
+pub(super) fn json_string(string: &str) -> String {
+    let mut json_string = String::with_capacity(string.len() + 2);
+    json_string.push('"');
+    for character in string.chars() {
+        match character {
+            '"' => json_string.push_str("\\\""),
+            '\\' => json_string.push_str("\\\\"),
+            '\n' => json_string.push_str("\\n"),
+            '\r' => json_string.push_str("\\r"),
+            '\t' => json_string.push_str("\\t"),
+            character if (character as u32) < 0x20 => {
+                json_string.push_str(&format!("\\u{:04x}", character as u32))
+            }
+            character => json_string.push(character),
+        }
+    }
+    json_string.push('"');
+    json_string
+}
+
+/// Returns the JSON string literal or `null`.
+pub(super) fn json_optional_string(string: &Option<String>) -> String {
+    string
+        .as_ref()
+        .map_or_else(|| String::from("null"), |string| json_string(string))
+}
+
+/// Returns the `,"kind":..,"name":..` fields describing the call tree item.
+fn json_item_fields(kind: &ItemKind) -> String {
+    match kind {
+        ItemKind::Call { name, .. } => format!(",\"kind\":\"call\",\"name\":{}", json_string(name)),
+        ItemKind::Loopbody { .. } => String::from(",\"kind\":\"loopbody\""),
+    }
+}
+
+/// Returns the `,"category":..,"count":..` fields describing the repeat count.
+pub(super) fn json_repeat_count_fields(count: RepeatCountCategory) -> String {
+    match count {
+        RepeatCountCategory::Exact(exact) => format!(",\"category\":\"exact\",\"count\":{}", exact),
+        RepeatCountCategory::AtLeast(at_least) => {
+            format!(",\"category\":\"at_least\",\"count\":{}", at_least)
+        }
+        RepeatCountCategory::Unknown => String::from(",\"category\":\"unknown\",\"count\":null"),
+    }
+}
+
+/// Returns the `,"duration_ns":..` field (empty if the duration is unknown, e.g. the call timing is off).
+#[cfg(feature = "call_timing")]
+fn json_duration_field(duration: Option<Duration>) -> String {
+    duration.map_or_else(
+        String::new,
+        |duration| format!(",\"duration_ns\":{}", duration.as_nanos()),
+    )
+}
+
+impl CoderunNotifiable for JsonLinesDecorator {
+    fn notify_call(&mut self, call_depth: usize, name: &str, 
+        #[cfg(feature = "params_logging")]
+        param_vals: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        #[cfg(feature = "params_logging")]
+        let params_field = format!(",\"params\":{}", json_optional_string(param_vals));
+        #[cfg(not(feature = "params_logging"))]
+        let params_field = "";
+        self.write_event(
+            "call",
+            call_depth,
+            &format!(",\"name\":{}{}", json_string(name), params_field),
+        );
+    }
+    fn notify_recursive_call(&mut self, call_depth: usize, name: &str,
+        #[cfg(feature = "params_logging")]
+        param_vals: &Option<String>,
+        recursion_depth: usize,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        #[cfg(feature = "params_logging")]
+        let params_field = format!(",\"params\":{}", json_optional_string(param_vals));
+        #[cfg(not(feature = "params_logging"))]
+        let params_field = "";
+        self.write_event(
+            "call",
+            call_depth,
+            &format!(
+                ",\"name\":{}{},\"recursion_depth\":{}",
+                json_string(name),
+                params_field,
+                recursion_depth
+            ),
+        );
+    }
+    fn notify_return(
+        &mut self,
+        call_depth: usize,
+        name: &str,
+        has_nested_calls: bool,
+        #[cfg(feature = "ret_val_logging")]
+        ret_val: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        duration: Option<Duration>,
+    ) {
+        #[cfg(feature = "ret_val_logging")]
+        let ret_val_field = format!(",\"ret_val\":{}", json_optional_string(ret_val));
+        #[cfg(not(feature = "ret_val_logging"))]
+        let ret_val_field = "";
+        #[cfg(feature = "call_timing")]
+        let duration_field = json_duration_field(duration);
+        #[cfg(not(feature = "call_timing"))]
+        let duration_field = "";
+        self.write_event(
+            "return",
+            call_depth,
+            &format!(
+                ",\"name\":{},\"has_nested_calls\":{}{}{}",
+                json_string(name),
+                has_nested_calls,
+                ret_val_field,
+                duration_field
+            ),
+        );
+    }
+    fn notify_repeat_count(
+        &mut self,
+        call_depth: usize,
+        kind: &ItemKind,
+        count: RepeatCountCategory,
+        #[cfg(feature = "call_timing")]
+        timing: &DurationStats,
+    ) {
+        #[cfg(feature = "call_timing")]
+        let timing_fields = if timing.is_empty() {
+            String::new()
+        } else {
+            format!(
+                ",\"total_ns\":{},\"min_ns\":{},\"avg_ns\":{},\"max_ns\":{}",
+                timing.total().as_nanos(),
+                timing.min().as_nanos(),
+                timing.avg().as_nanos(),
+                timing.max().as_nanos()
+            )
+        };
+        #[cfg(not(feature = "call_timing"))]
+        let timing_fields = "";
+        self.write_event(
+            "repeat_count",
+            call_depth,
+            &format!(
+                "{}{}{}",
+                json_item_fields(kind),
+                json_repeat_count_fields(count),
+                timing_fields
+            ),
+        );
+    }
+    fn notify_sequence_repeat_count(
+        &mut self,
+        call_depth: usize,
+        kinds: &[ItemKind],
+        count: RepeatCountCategory,
+    ) {
+        let items = kinds
+            .iter()
+            .map(|kind| format!("{{{}}}", &json_item_fields(kind)[1..])) // `[1..]`: Skip the leading comma.
+            .collect::<Vec<String>>()
+            .join(",");
+        self.write_event(
+            "sequence_repeat_count",
+            call_depth,
+            &format!(",\"items\":[{}]{}", items, json_repeat_count_fields(count)),
+        );
+    }
+    fn notify_loopbody_start(&mut self, call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        self.write_event("loopbody_start", call_depth, "");
+    }
+    fn notify_loopbody_end(&mut self, call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        duration: Option<Duration>,
+    ) {
+        #[cfg(feature = "call_timing")]
+        let duration_field = json_duration_field(duration);
+        #[cfg(not(feature = "call_timing"))]
+        let duration_field = String::new();
+        self.write_event("loopbody_end", call_depth, &duration_field);
+    }
+    fn notify_folding_suspended(&mut self, call_depth: usize) {
+        self.write_event("folding_suspended", call_depth, "");
+    }
+}
+
+impl ThreadSpecific for JsonLinesDecorator {
+    fn set_thread_indent(&mut self, thread_indent: String) {
+        self.common.set_thread_indent(thread_indent); // Is not used.
+    }
+    fn notify_thread_start(&mut self, _thread: &std::thread::Thread) {
+        if self.common.thread_headers {
+            self.write_event("thread_start", 0, ""); // The thread fields identify the thread.
+        }
+    }
+    fn notify_thread_end(&mut self, _thread: &std::thread::Thread, call_count: usize) {
+        if self.common.thread_headers {
+            self.write_event("thread_end", 0, &format!(",\"call_count\":{}", call_count));
+        }
+    }
+}
+
+impl WriterPossessor for JsonLinesDecorator {
+    fn set_writer(&mut self, writer: Rc<RefCell<dyn Write>>) {
+        self.common.set_writer(writer);
+    }
+}
+
+impl LogDecorator for JsonLinesDecorator {}
@@ -0,0 +1,239 @@
+use std::{
+    cell::RefCell,
+    io::Write,
+    rc::Rc,
+    sync::atomic::AtomicBool,
+};
+
+use code_commons::{CoderunNotifiable, ItemKind, RepeatCountCategory};
+#[cfg(feature = "call_timing")]
+use code_commons::DurationStats;
+#[cfg(feature = "call_timing")]
+use std::time::{Duration, Instant};
+
+use super::*;
+
+/// The decorator that logs the calls as a [Mermaid](https://mermaid.js.org/syntax/sequenceDiagram.html)
+/// sequence diagram, where each logged thread is a lifeline (participant), the calls and returns are
+/// the messages activating and deactivating the thread's lifeline, and the repeat counts are the `loop` blocks.
+///
+/// For example,
+/// ```txt
+/// sequenceDiagram
+///   participant T1 as main
+///   T1->>+T1: f(x: 1)
+///     T1->>+T1: g()
+///     T1-->>-T1: return
+///     loop 2 more time(s)
+///       T1->>T1: g()
+///     end
+///   T1-->>-T1: -> 5
+/// ```
+/// The loop bodies are the notes (rather than the blocks) since a block logged by one thread
+/// could otherwise contain the lines of the other threads logged in the meantime.
+pub struct SequenceDiagramDecorator {
+    /// The part (of the decorator) common for multiple decorators.
+    /// The thread indent is not used (each thread is a separate lifeline).
+    common: CommonDecorator,
+    /// The participant ID of the thread that has created the decorator (e.g. `T1`)
+    /// (the decorator is thread-local, but can be flushed by a different thread).
+    participant: String,
+    /// The name of the thread that has created the decorator (the thread ID if the thread is unnamed).
+    thread_name: String,
+    /// Whether the participant declaration has been logged.
+    participant_logged: bool,
+    /// Whether the diagram start (`sequenceDiagram`) has been logged.
+    diagram_start: OutputStart,
+}
+
+/// Whether the diagram start has been logged to the shared output.
+static SEQUENCE_DIAGRAM_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Returns the text with the characters special for the Mermaid messages and notes escaped.
+fn mermaid_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '#' => escaped.push_str("#35;"),
+            ';' => escaped.push_str("#59;"),
+            '\n' => escaped.push_str("<br>"),
+            '\r' => {}
+            character => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+impl SequenceDiagramDecorator {
+    /// Creates a new `SequenceDiagramDecorator` with the optional writer.
+    /// If the writer is `None` then uses the one in the `CommonDecorator`.
+    ///
+    /// The lifeline is the one of the thread calling this function.
+    pub fn new(writer: Option<Box<dyn Write>>) -> Self {
+        let thread = std::thread::current();
+        let thread_id = thread_id_number(&thread);
+        Self {
+            common: CommonDecorator::new(writer),
+            participant: format!("T{}", thread_id),
+            thread_name: thread.name().map_or_else(|| format!("ThreadId({})", thread_id), String::from),
+            participant_logged: false,
+            diagram_start: OutputStart::new(&SEQUENCE_DIAGRAM_STARTED),
+        }
+    }
+    /// Writes the lines indented according to the call depth. Before the first lines writes
+    /// the diagram start (if not yet written to the output) and the participant declaration.
+    fn write_lines(&mut self, call_depth: usize, lines: &[String]) {
+        if self.diagram_start.take_pending() {
+            decorator_write!(self, "sequenceDiagram\n");
+        }
+        if !self.participant_logged {
+            self.participant_logged = true;
+            decorator_write!(
+                self,
+                "  participant {} as {}\n",
+                self.participant,
+                mermaid_text(&self.thread_name)
+            );
+        }
+        let indent = "  ".repeat(call_depth + 1);
+        for line in lines {
+            decorator_write!(self, "{}{}\n", indent, line);
+        }
+    }
+    /// Writes the `loop` block of the repeats of the items.
+    fn write_repeats(&mut self, call_depth: usize, kinds: &[ItemKind], label: &str) {
+        let mut lines = vec![format!("loop {}", mermaid_text(label))];
+        for kind in kinds {
+            lines.push(match kind {
+                ItemKind::Call { name, .. } => {
+                    format!("  {0}->>{0}: {1}", self.participant, mermaid_text(&format!("{}()", name)))
+                }
+                ItemKind::Loopbody { .. } => format!("  Note right of {}: {}", self.participant, LOOPBODY_NAME),
+            });
+        }
+        lines.push(String::from("end"));
+        self.write_lines(call_depth, &lines);
+    }
+    /// Writes the call message (activating the lifeline).
+    fn write_call(&mut self, call_depth: usize, label: &str) {
+        let line = format!("{0}->>+{0}: {1}", self.participant, mermaid_text(label));
+        self.write_lines(call_depth, &[line]);
+    }
+}
+
+impl CoderunNotifiable for SequenceDiagramDecorator {
+    fn notify_call(&mut self, call_depth: usize, name: &str, 
+        #[cfg(feature = "params_logging")]
+        param_vals: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        #[cfg(feature = "params_logging")]
+        let param_vals = param_vals.as_deref().unwrap_or("");
+        #[cfg(not(feature = "params_logging"))]
+        let param_vals = "";
+        self.write_call(call_depth, &format!("{}({})", name, param_vals));
+    }
+    fn notify_recursive_call(&mut self, call_depth: usize, name: &str,
+        #[cfg(feature = "params_logging")]
+        param_vals: &Option<String>,
+        recursion_depth: usize,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        #[cfg(feature = "params_logging")]
+        let param_vals = param_vals.as_deref().unwrap_or("");
+        #[cfg(not(feature = "params_logging"))]
+        let param_vals = "";
+        self.write_call(
+            call_depth,
+            &format!("{}({}) {} {}", name, param_vals, RECURSION_DEPTH_LABEL, recursion_depth),
+        );
+    }
+    fn notify_return(
+        &mut self,
+        call_depth: usize,
+        _name: &str,
+        _has_nested_calls: bool,
+        #[cfg(feature = "ret_val_logging")]
+        ret_val: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        duration: Option<Duration>,
+    ) {
+        #[cfg(feature = "ret_val_logging")]
+        let label = ret_val
+            .as_ref()
+            .map_or_else(|| String::from("return"), |ret_val| format!("-> {}", ret_val)); // "return" or "-> RetVal".
+        #[cfg(not(feature = "ret_val_logging"))]
+        let label = String::from("return");
+        #[cfg(feature = "call_timing")]
+        let label = duration.map_or(label.clone(), |duration| format!("{}, {:.1?}", label, duration)); // E.g. "-> RetVal, 1.2ms".
+        let line = format!("{0}-->>-{0}: {1}", self.participant, mermaid_text(&label));
+        self.write_lines(call_depth, &[line]);
+    }
+    fn notify_repeat_count(
+        &mut self,
+        call_depth: usize,
+        kind: &ItemKind,
+        count: RepeatCountCategory,
+        #[cfg(feature = "call_timing")]
+        timing: &DurationStats,
+    ) {
+        #[cfg(feature = "call_timing")]
+        let timing_str = timing_string(timing); // E.g. ", total 12.3ms, min 10.1µs, avg 12.3µs, max 400.2µs".
+        #[cfg(not(feature = "call_timing"))]
+        let timing_str = "";
+        let label = format!("{} more time(s){}", count.to_string(), timing_str);
+        self.write_repeats(call_depth, std::slice::from_ref(kind), &label);
+    }
+    fn notify_sequence_repeat_count(
+        &mut self,
+        call_depth: usize,
+        kinds: &[ItemKind],
+        count: RepeatCountCategory,
+    ) {
+        self.write_repeats(call_depth, kinds, &format!("{} more time(s)", count.to_string()));
+    }
+    fn notify_loopbody_start(&mut self, call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        let line = format!("Note right of {}: {} start", self.participant, LOOPBODY_NAME);
+        self.write_lines(call_depth, &[line]);
+    }
+    fn notify_loopbody_end(&mut self, call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        duration: Option<Duration>,
+    ) {
+        #[cfg(feature = "call_timing")]
+        let duration_str = duration.map_or_else(
+            || "".to_string(), // None -> "".
+            |duration| format!(", {:.1?}", duration),
+        ); // Some() -> ", 1.2ms".
+        #[cfg(not(feature = "call_timing"))]
+        let duration_str = "";
+        let line = format!("Note right of {}: {} end{}", self.participant, LOOPBODY_NAME, duration_str);
+        self.write_lines(call_depth, &[line]);
+    }
+    fn notify_folding_suspended(&mut self, call_depth: usize) {
+        let line = format!("Note right of {}: {}", self.participant, mermaid_text(FOLDING_SUSPENDED_NOTE));
+        self.write_lines(call_depth, &[line]);
+    }
+}
+
+impl ThreadSpecific for SequenceDiagramDecorator {
+    fn set_thread_indent(&mut self, thread_indent: String) {
+        self.common.set_thread_indent(thread_indent); // Is not used.
+    }
+}
+
+impl WriterPossessor for SequenceDiagramDecorator {
+    fn set_writer(&mut self, writer: Rc<RefCell<dyn Write>>) {
+        self.common.set_writer(writer);
+        // The substitute writer is a separate output that needs its own diagram start and participant.
+        self.diagram_start.set_own_output();
+        self.participant_logged = false;
+    }
+}
+
+impl LogDecorator for SequenceDiagramDecorator {}
@@ -0,0 +1,188 @@
+use std::{
+    cell::RefCell,
+    io::Write,
+    rc::Rc,
+};
+
+use code_commons::{CoderunNotifiable, ItemKind, RepeatCountCategory};
+#[cfg(feature = "call_timing")]
+use code_commons::DurationStats;
+#[cfg(feature = "call_timing")]
+use std::time::{Duration, Instant};
+
+use super::*;
+
+/// The decorator that forwards each notification (and the thread indent and the writer)
+/// to each of its child decorators, in the order of the children.
+///
+/// Enables a single run to produce several logs simultaneously,
+/// e.g. the code-like log on the standard output and the JSON lines or HTML log in a file
+/// (see `add_tee_output()`).
+pub struct TeeDecorator {
+    /// The child decorators.
+    children: Vec<Rc<RefCell<dyn LogDecorator>>>,
+}
+
+impl TeeDecorator {
+    /// Creates a new `TeeDecorator` forwarding to the `children`.
+    pub fn new(children: Vec<Rc<RefCell<dyn LogDecorator>>>) -> Self {
+        Self { children }
+    }
+    /// Adds one more child decorator.
+    pub fn add_child(&mut self, child: Rc<RefCell<dyn LogDecorator>>) {
+        self.children.push(child);
+    }
+}
+
+impl CoderunNotifiable for TeeDecorator {
+    fn notify_call(&mut self, call_depth: usize, name: &str, 
+        #[cfg(feature = "params_logging")]
+        param_vals: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        start: Option<Instant>,
+    ) {
+        for child in &self.children {
+            child.borrow_mut().notify_call(
+                call_depth,
+                name,
+                #[cfg(feature = "params_logging")]
+                param_vals,
+                #[cfg(feature = "call_timing")]
+                start,
+            );
+        }
+    }
+    fn notify_recursive_call(&mut self, call_depth: usize, name: &str,
+        #[cfg(feature = "params_logging")]
+        param_vals: &Option<String>,
+        recursion_depth: usize,
+        #[cfg(feature = "call_timing")]
+        start: Option<Instant>,
+    ) {
+        for child in &self.children {
+            child.borrow_mut().notify_recursive_call(
+                call_depth,
+                name,
+                #[cfg(feature = "params_logging")]
+                param_vals,
+                recursion_depth,
+                #[cfg(feature = "call_timing")]
+                start,
+            );
+        }
+    }
+    fn notify_return(
+        &mut self,
+        call_depth: usize,
+        name: &str,
+        has_nested_calls: bool,
+        #[cfg(feature = "ret_val_logging")]
+        ret_val: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        duration: Option<Duration>,
+    ) {
+        for child in &self.children {
+            child.borrow_mut().notify_return(
+                call_depth,
+                name,
+                has_nested_calls,
+                #[cfg(feature = "ret_val_logging")]
+                ret_val,
+                #[cfg(feature = "call_timing")]
+                duration,
+            );
+        }
+    }
+    fn notify_repeat_count(
+        &mut self,
+        call_depth: usize,
+        kind: &ItemKind,
+        count: RepeatCountCategory,
+        #[cfg(feature = "call_timing")]
+        timing: &DurationStats,
+    ) {
+        for child in &self.children {
+            child.borrow_mut().notify_repeat_count(
+                call_depth,
+                kind,
+                count,
+                #[cfg(feature = "call_timing")]
+                timing,
+            );
+        }
+    }
+    fn notify_sequence_repeat_count(
+        &mut self,
+        call_depth: usize,
+        kinds: &[ItemKind],
+        count: RepeatCountCategory,
+    ) {
+        for child in &self.children {
+            child.borrow_mut().notify_sequence_repeat_count(call_depth, kinds, count);
+        }
+    }
+    fn notify_flush(&mut self) {
+        for child in &self.children {
+            child.borrow_mut().notify_flush();
+        }
+    }
+    fn notify_loopbody_start(&mut self, call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        start: Option<Instant>,
+    ) {
+        for child in &self.children {
+            child.borrow_mut().notify_loopbody_start(
+                call_depth,
+                #[cfg(feature = "call_timing")]
+                start,
+            );
+        }
+    }
+    fn notify_loopbody_end(&mut self, call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        duration: Option<Duration>,
+    ) {
+        for child in &self.children {
+            child.borrow_mut().notify_loopbody_end(
+                call_depth,
+                #[cfg(feature = "call_timing")]
+                duration,
+            );
+        }
+    }
+    fn notify_folding_suspended(&mut self, call_depth: usize) {
+        for child in &self.children {
+            child.borrow_mut().notify_folding_suspended(call_depth);
+        }
+    }
+}
+
+impl ThreadSpecific for TeeDecorator {
+    fn set_thread_indent(&mut self, thread_indent: String) {
+        for child in &self.children {
+            child.borrow_mut().set_thread_indent(thread_indent.clone());
+        }
+    }
+    fn notify_thread_start(&mut self, thread: &std::thread::Thread) {
+        for child in &self.children {
+            child.borrow_mut().notify_thread_start(thread);
+        }
+    }
+    fn notify_thread_end(&mut self, thread: &std::thread::Thread, call_count: usize) {
+        for child in &self.children {
+            child.borrow_mut().notify_thread_end(thread, call_count);
+        }
+    }
+}
+
+impl WriterPossessor for TeeDecorator {
+    /// Replaces the writer of each child decorator.
+    /// To replace the writer of a particular child, call the child's `set_writer()` directly.
+    fn set_writer(&mut self, writer: Rc<RefCell<dyn Write>>) {
+        for child in &self.children {
+            child.borrow_mut().set_writer(writer.clone());
+        }
+    }
+}
+
+impl LogDecorator for TeeDecorator {}
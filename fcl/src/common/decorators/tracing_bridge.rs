@@ -0,0 +1,197 @@
+use code_commons::{CoderunNotifiable, ItemKind, RepeatCountCategory};
+#[cfg(feature = "call_timing")]
+use code_commons::DurationStats;
+#[cfg(feature = "call_timing")]
+use std::time::{Duration, Instant};
+
+use super::*;
+
+/// The decorator that turns the calls and loop bodies into the [`tracing`](https://docs.rs/tracing) spans
+/// (of the `"fcl"` target and the `INFO` level), and the repeat counts into the `tracing` events,
+/// such that the logged calls show up in the existing `tracing` subscribers (and, with the `log_bridge` feature,
+/// in the `log` loggers).
+///
+/// The call span is named `"call"` and has the fields
+/// `name`, `params`, `recursion_depth`, `ret_val`, and `duration_ns` (the latter ones are recorded upon return).
+/// The loop body span is named `"loop_body"` and has the `duration_ns` field.
+/// The repeat count event has the `item` and `count` fields and the message like in the code-like log
+/// (e.g. `g() repeats 2 time(s).`).
+///
+/// The spans are entered and exited upon the call graph's notifications (rather than upon the actual calls and returns),
+/// i.e. the repeats are folded and the span durations reflect the time of the notifications
+/// (the `duration_ns` field reflects the actual duration if the `call_timing` feature is on).
+///
+/// The writer and the thread indent are not used (the subscriber decides where and how to log).
+pub struct TracingDecorator {
+    /// The entered spans of the calls and loop bodies being logged, the innermost one is the last.
+    spans: Vec<tracing::span::EnteredSpan>,
+}
+
+impl TracingDecorator {
+    /// Creates a new `TracingDecorator`.
+    pub fn new() -> Self {
+        Self { spans: Vec::new() }
+    }
+    /// Enters the call span.
+    /// # Parameters
+    /// * The function or closure name.
+    /// * The optional string representation of the parameter names and values.
+    /// * The optional recursion depth.
+    fn enter_call(&mut self, name: &str, params: Option<&str>, recursion_depth: Option<usize>) {
+        let span = tracing::info_span!(
+            target: "fcl",
+            "call",
+            name,
+            params,
+            recursion_depth,
+            ret_val = tracing::field::Empty,
+            duration_ns = tracing::field::Empty,
+        );
+        self.spans.push(span.entered());
+    }
+}
+
+impl Default for TracingDecorator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for TracingDecorator {
+    fn drop(&mut self) {
+        // Exit the spans that are still entered (e.g. upon panic), the innermost one first.
+        while self.spans.pop().is_some() {}
+    }
+}
+
+impl CoderunNotifiable for TracingDecorator {
+    fn notify_call(&mut self, _call_depth: usize, name: &str, 
+        #[cfg(feature = "params_logging")]
+        param_vals: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        #[cfg(feature = "params_logging")]
+        let params = param_vals.as_deref();
+        #[cfg(not(feature = "params_logging"))]
+        let params = None;
+        self.enter_call(name, params, None);
+    }
+    fn notify_recursive_call(&mut self, _call_depth: usize, name: &str,
+        #[cfg(feature = "params_logging")]
+        param_vals: &Option<String>,
+        recursion_depth: usize,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        #[cfg(feature = "params_logging")]
+        let params = param_vals.as_deref();
+        #[cfg(not(feature = "params_logging"))]
+        let params = None;
+        self.enter_call(name, params, Some(recursion_depth));
+    }
+    fn notify_return(
+        &mut self,
+        _call_depth: usize,
+        _name: &str,
+        _has_nested_calls: bool,
+        #[cfg(feature = "ret_val_logging")]
+        ret_val: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        duration: Option<Duration>,
+    ) {
+        let span = self.spans.pop();
+        debug_assert!(span.is_some(), "Internal Error: Return without a call span");
+        if let Some(span) = span {
+            #[cfg(feature = "ret_val_logging")]
+            if let Some(ret_val) = ret_val {
+                span.record("ret_val", ret_val.as_str());
+            }
+            #[cfg(feature = "call_timing")]
+            if let Some(duration) = duration {
+                span.record("duration_ns", duration.as_nanos() as u64);
+            }
+        } // The span is exited (and closed) here.
+    }
+    fn notify_repeat_count(
+        &mut self,
+        _call_depth: usize,
+        kind: &ItemKind,
+        count: RepeatCountCategory,
+        #[cfg(feature = "call_timing")]
+        timing: &DurationStats,
+    ) {
+        let item_name = match kind {
+            ItemKind::Call { name, .. } => format!("{}()", name),
+            ItemKind::Loopbody { .. } => String::from(LOOPBODY_NAME), // "Loop body".
+        };
+        #[cfg(feature = "call_timing")]
+        let timing = timing_string(timing);
+        #[cfg(not(feature = "call_timing"))]
+        let timing = String::new();
+        let count = count.to_string();
+        tracing::info!(
+            target: "fcl",
+            item = item_name.as_str(),
+            count = count.as_str(),
+            "{} repeats {} time(s){}.", // E.g. "g() repeats 2 time(s)."
+            item_name,
+            count,
+            timing
+        );
+    }
+    fn notify_sequence_repeat_count(
+        &mut self,
+        _call_depth: usize,
+        kinds: &[ItemKind],
+        count: RepeatCountCategory,
+    ) {
+        let item_names = kinds
+            .iter()
+            .map(|kind| match kind {
+                ItemKind::Call { name, .. } => format!("{}()", name),
+                ItemKind::Loopbody { .. } => String::from(LOOPBODY_NAME), // "Loop body".
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+        let count = count.to_string();
+        tracing::info!(
+            target: "fcl",
+            item = format!("({})", item_names).as_str(),
+            count = count.as_str(),
+            "({}) repeats {} time(s).", // E.g. "(a(), b()) repeats 8 time(s)."
+            item_names,
+            count
+        );
+    }
+    fn notify_loopbody_start(&mut self, _call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        let span = tracing::info_span!(target: "fcl", "loop_body", duration_ns = tracing::field::Empty);
+        self.spans.push(span.entered());
+    }
+    fn notify_loopbody_end(&mut self, _call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        duration: Option<Duration>,
+    ) {
+        let span = self.spans.pop();
+        debug_assert!(span.is_some(), "Internal Error: Loop body end without a loop body span");
+        #[cfg(feature = "call_timing")]
+        if let (Some(span), Some(duration)) = (&span, duration) {
+            span.record("duration_ns", duration.as_nanos() as u64);
+        }
+        drop(span); // The span is exited (and closed) here.
+    }
+    fn notify_folding_suspended(&mut self, _call_depth: usize) {
+        tracing::warn!(target: "fcl", "{}", FOLDING_SUSPENDED_NOTE);
+    }
+}
+
+impl ThreadSpecific for TracingDecorator {
+    fn set_thread_indent(&mut self, _thread_indent: String) {} // Is not used.
+}
+
+impl WriterPossessor for TracingDecorator {} // The writer is not used.
+
+impl LogDecorator for TracingDecorator {}
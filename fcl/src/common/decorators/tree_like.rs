@@ -0,0 +1,402 @@
+use std::{
+    cell::RefCell,
+    io::Write,
+    rc::Rc,
+};
+
+use code_commons::{CoderunNotifiable, ItemKind, RepeatCountCategory};
+#[cfg(feature = "call_timing")]
+use code_commons::DurationStats;
+#[cfg(feature = "call_timing")]
+use std::time::{Duration, Instant};
+
+use super::*;
+
+/// The decorator that logs the function calls in a tree-like manner (as opposed to a code-like manner).
+///
+/// For example,
+/// ```ignore
+/// // TreeLikeDecorator Log          Explanation in CodeLikeDecorator manner.
+/// // -----------------------------------------------------------------------
+/// +-f()                             // f() {
+/// | +-h()                           //   h() {}
+/// |   h repeats 99 time(s).         //   // h() repeats 99 time(s).
+/// | +-i()                           //   i() {
+/// | | +-j() -> 5                    //     j() {} -> 5
+/// | |   j repeats 9 time(s).        //     // j() repeats 9 time(s).
+/// | | `-return                      //   } // i().
+/// |   i repeats 100 time(s).        //   // i() repeats 100 time(s).
+/// | `-return -> 7                   // } -> 7 // f().
+///   f repeats 2 time(s).            // // f() repeats 2 time(s).
+/// +-Loop body                       // { // Loop body start.
+/// | +-k()                           //   k() {}
+/// | +-m()                           //   m() {}
+/// | `-Loop body end                 // } // Loop body end.
+///   Loop body repeats 3 time(s).    // // Loop body repeats 3 time(s).
+/// ```
+/// The return (or loop body end) line is the last child of the call (or loop body) node.
+/// Thus the last-child step (`` `- ``, `└─`) is known to be the last one at the moment of logging,
+/// and the children (that arrive incrementally) are logged without waiting for their next siblings.
+/// The call with no nested calls is logged with a single line (the return line is not logged).
+///
+/// With the `TreeStyle::Unicode` (see `set_style()`) the box-drawing characters are used:
+/// ```txt
+/// ├─f()
+/// │ ├─h()
+/// │ │ h repeats 99 time(s).
+/// │ └─return -> 7
+/// ```
+/// This decorator was added to test and make sure that the `CodeLikeDecorator`'s support 
+/// has everything that is required to enable various other decorators.
+#[rustfmt::skip]
+pub struct TreeLikeDecorator {
+    /// The part (of the decorator) common for multiple decorators.
+    common: CommonDecorator,
+
+    /// The string that prepends the function call in the log. 
+    /// For example, the fragment `+-` in the line `+-f`.
+    indent_step_call   : &'static str,  // "+-"  f
+    /// The string that prepends the repeat count in the log. 
+    /// For example, the fragment of 2 spaces in the line 
+    /// ```txt
+    /// | |   j repeats 9 time(s)
+    /// ```
+    indent_step_noncall: &'static str,  // "  "  Repeats ..
+    /// The string that shows the call depth, e.g. the multiple fragments `| ` in the line `| | +-j`.
+    indent_step_parent : &'static str,  // "| "  Prepends multiple times those above.
+    /// The string that prepends the last child of a node, i.e. the return or loop body end.
+    /// For example, the fragment `` `- `` in the line `` | `-return -> 7 ``.
+    indent_step_last   : &'static str,  // "`-"  return
+    /// '\n' pending after the call line, e.g. "+-f()", before logging a nested call
+    /// (is not logged if there are no nested calls, e.g. "+-f() -> 5\n").
+    line_end_pending   : bool,
+}
+
+/// The style of the `TreeLikeDecorator`'s log.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TreeStyle {
+    /// The ASCII characters (`+-`, `| `, `` `- ``), the default.
+    Ascii,
+    /// The Unicode box-drawing characters (`├─`, `│ `, `└─`).
+    Unicode,
+}
+
+impl TreeLikeDecorator {
+    /// Creates a new `CodeLikeDecorator` with the optional writer and indent steps.
+    /// * If the writer is `None` then uses the one in the `CommonDecorator`.
+    /// * If the indent steps are `None` then uses
+    ///   * `&"+-"` for `indent_step_call`,
+    ///   * `&"  "` (2 spaces) for `indent_step_noncall`,
+    ///   * `&"| "` for `indent_step_parent`,
+    ///
+    /// The last-child step is `` &"`-" ``.
+    #[rustfmt::skip]
+    pub fn new(writer: Option<Box<dyn Write>>,
+        indent_step_call   : Option<&'static str>,
+        indent_step_noncall: Option<&'static str>,
+        indent_step_parent : Option<&'static str>,
+    ) -> Self {
+        Self {
+            common: CommonDecorator::new(writer),
+
+            indent_step_call   : indent_step_call   .unwrap_or(defaults::TREE_INDENT_STEP_CALL),
+            indent_step_noncall: indent_step_noncall.unwrap_or(defaults::TREE_INDENT_STEP_NONCALL),
+            indent_step_parent : indent_step_parent .unwrap_or(defaults::TREE_INDENT_STEP_PARENT),
+            indent_step_last   : "`-",
+            line_end_pending   : false,
+        }
+    }
+    /// Sets the optional prefixes of each line (timestamps, thread name and ID).
+    pub fn set_line_prefixes(&mut self, line_prefixes: LinePrefixes) {
+        self.common.set_line_prefixes(line_prefixes);
+    }
+    /// Enables or disables logging the thread start header and thread end summary lines (disabled by default).
+    pub fn set_thread_headers(&mut self, thread_headers: bool) {
+        self.common.set_thread_headers(thread_headers);
+    }
+    /// Logs the thread start header or thread end summary line (see `CommonDecorator::get_thread_header()`)
+    /// if enabled.
+    fn log_thread_header(&mut self, thread: &std::thread::Thread, event: &str) {
+        if self.common.thread_headers {
+            self.end_pending_line();
+            let header = self.common.get_thread_header(thread, event);
+            decorator_write!(self, "{}\n", header);
+        }
+    }
+    /// Replaces the indent steps with the ones of the `style`.
+    ///
+    /// In the `TreeStyle::Unicode` the repeat counts are prepended with `│ ` (rather than 2 spaces)
+    /// since the parent node's vertical line continues down to the parent's last child (the return or loop body end).
+    #[rustfmt::skip]
+    pub fn set_style(&mut self, style: TreeStyle) {
+        (self.indent_step_call, self.indent_step_noncall, self.indent_step_parent, self.indent_step_last) = match style {
+            TreeStyle::Ascii   => (&"+-", &"  ", &"| ", &"`-"),
+            TreeStyle::Unicode => (&"├─", &"│ ", &"│ ", &"└─"),
+        };
+    }
+    /// Returns the indent string for the specified call depth. In other words, a string containing
+    /// `self.indent_step_parent` `call_depth` times.
+    fn get_indent_string(&self, call_depth: usize) -> String {
+        let mut indent_string = String::with_capacity(8);
+        for _ in 0..call_depth {
+            indent_string.push_str(self.indent_step_parent);
+        }
+        indent_string
+    }
+    /// Returns a tuple of
+    /// * the line prefixes (if any) followed by the thread indent
+    /// * and function indent string (that reflects the call depth).
+    ///
+    /// Those combined provide an indent for logging a line by the current thread.
+    fn get_indents(&self, call_depth: usize) -> (String, String) {
+        (
+            self.common.get_line_start(),
+            self.get_indent_string(call_depth),
+        )
+    }
+    /// Logs the '\n' pending after the call line (if any).
+    fn end_pending_line(&mut self) {
+        if self.line_end_pending {
+            decorator_write!(self, "\n"); // '\n' after "+-parent()" before logging a nested item.
+            self.line_end_pending = false;
+        }
+    }
+    /// Logs the call, e.g. `+-f()`, or `+-f() recursion depth 40` if the call is recursion-folded.
+    /// The '\n' is pending.
+    fn log_call(&mut self, call_depth: usize, name: &str, 
+        #[cfg(feature = "params_logging")]
+        param_vals: &Option<String>,
+        recursion_depth: Option<usize>,
+    ) {
+        self.end_pending_line();
+        let indents = self.get_indents(call_depth);  // TODO: Consider -> `let (thread_indent, func_indent) =`.
+
+        #[cfg(feature = "params_logging")]
+        let param_vals = param_vals
+                .as_ref()
+                .map(|string| string.as_str())
+                .unwrap_or("");
+        #[cfg(not(feature = "params_logging"))]
+        let param_vals = "";
+
+        let recursion_depth = recursion_depth.map_or_else(
+            || "".to_string(), // None -> "".
+            |recursion_depth| format!(" {} {}", RECURSION_DEPTH_LABEL, recursion_depth),
+        ); // Some() -> " recursion depth 40".
+
+        decorator_write!(
+            self,
+            "{}{}{}{}({}){}",
+            indents.0,
+            indents.1,
+            self.indent_step_call,
+            name,
+            param_vals,
+            recursion_depth,
+        ); // E.g."<thread_indent><indent>+-sibling()", "| | | | +-sibling() recursion depth 40"
+        self.line_end_pending = true; // '\n' pending. Won't be logged if there will be no nested calls (immediate " -> 5\n").
+    }
+}
+
+impl CoderunNotifiable for TreeLikeDecorator {
+    fn notify_flush(&mut self) {
+        self.end_pending_line(); // '\n' after "+-parent()" before an output of another thread.
+    }
+    fn notify_call(&mut self, call_depth: usize, name: &str, 
+        #[cfg(feature = "params_logging")]
+        param_vals: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        self.log_call(
+            call_depth,
+            name,
+            #[cfg(feature = "params_logging")]
+            param_vals,
+            None,
+        );
+    }
+    fn notify_recursive_call(&mut self, call_depth: usize, name: &str,
+        #[cfg(feature = "params_logging")]
+        param_vals: &Option<String>,
+        recursion_depth: usize,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        self.log_call(
+            call_depth,
+            name,
+            #[cfg(feature = "params_logging")]
+            param_vals,
+            Some(recursion_depth),
+        );
+    }
+    fn notify_return(
+        &mut self,
+        call_depth: usize,
+        _name: &str,
+        has_nested_calls: bool,
+        #[cfg(feature = "ret_val_logging")]
+        ret_val: &Option<String>,
+        #[cfg(feature = "call_timing")]
+        duration: Option<Duration>,
+    ) {
+        #[cfg(feature = "ret_val_logging")]
+        let ret_val_str = ret_val.as_ref().map_or_else(
+            || "".to_string(), // None -> "".
+            |output| format!(" -> {}", output),
+        ); // Some() -> " -> Value".
+        #[cfg(not(feature = "ret_val_logging"))]
+        let ret_val_str = "";
+
+        #[cfg(feature = "call_timing")]
+        let duration_str = duration.map_or_else(
+            || "".to_string(), // None -> "".
+            |duration| format!(", {:.1?}", duration),
+        ); // Some() -> ", 1.2ms".
+        #[cfg(not(feature = "call_timing"))]
+        let duration_str = "";
+
+        if !has_nested_calls && self.line_end_pending {
+            decorator_write!(self, "{}{}\n", ret_val_str, duration_str); // "\n", " -> RetVal\n", or " -> RetVal, 1.2ms\n".
+        } else {
+            self.end_pending_line();
+            let indents = self.get_indents(call_depth + 1); // The return is the last child of the call.
+            decorator_write!(
+                self,
+                "{}{}{}return{}{}\n", // E.g. "<thread_indent><indent>`-return -> RetVal, 1.2ms\n".
+                indents.0,
+                indents.1,
+                self.indent_step_last,
+                ret_val_str,
+                duration_str,
+            );
+        }
+        self.line_end_pending = false;
+    }
+    fn notify_repeat_count(
+        &mut self,
+        call_depth: usize,
+        kind: &ItemKind,
+        count: RepeatCountCategory,
+        #[cfg(feature = "call_timing")]
+        timing: &DurationStats,
+    ) {
+        let item_name = match kind {
+            ItemKind::Call { name, .. } => name.clone(),
+            ItemKind::Loopbody { .. } => String::from(LOOPBODY_NAME), // "Loop body",
+        };
+        #[cfg(feature = "call_timing")]
+        let timing_str = timing_string(timing); // E.g. ", total 12.3ms, min 10.1µs, avg 12.3µs, max 400.2µs".
+        #[cfg(not(feature = "call_timing"))]
+        let timing_str = "";
+        self.end_pending_line();
+        let indents = self.get_indents(call_depth); // TODO: Consider -> `let (thread_indent, func_indent) =`.
+        decorator_write!(
+            self,
+            "{}{}{}{} repeats {} time(s){}.\n", // E.g. "<thread_indent><indent> sibling repeats 8 time(s).\n"
+            indents.0,
+            indents.1,
+            self.indent_step_noncall,
+            item_name,
+            count.to_string(),
+            timing_str,
+        );
+    }
+    fn notify_sequence_repeat_count(
+        &mut self,
+        call_depth: usize,
+        kinds: &[ItemKind],
+        count: RepeatCountCategory,
+    ) {
+        let item_names = kinds
+            .iter()
+            .map(|kind| match kind {
+                ItemKind::Call { name, .. } => name.clone(),
+                ItemKind::Loopbody { .. } => String::from(LOOPBODY_NAME), // "Loop body",
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+        self.end_pending_line();
+        let indents = self.get_indents(call_depth); // TODO: Consider -> `let (thread_indent, func_indent) =`.
+        decorator_write!(
+            self,
+            "{}{}{}({}) repeats {} time(s).\n", // E.g. "<thread_indent><indent>  (a, b) repeats 8 time(s).\n"
+            indents.0,
+            indents.1,
+            self.indent_step_noncall,
+            item_names,
+            count.to_string()
+        );
+    }
+    fn notify_loopbody_start(&mut self, call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        _start: Option<Instant>,
+    ) {
+        self.end_pending_line();
+        let indents = self.get_indents(call_depth); // TODO: Consider -> `let (thread_indent, func_indent) =`.
+        decorator_write!(
+            self,
+            "{}{}{}{}\n",
+            indents.0,
+            indents.1,
+            self.indent_step_call,
+            LOOPBODY_NAME,
+        ); // E.g."<thread_indent><indent>+-Loop body", "| | | | +-Loop body"
+    }
+    fn notify_loopbody_end(&mut self, call_depth: usize,
+        #[cfg(feature = "call_timing")]
+        duration: Option<Duration>,
+    ) {
+        #[cfg(feature = "call_timing")]
+        let duration_str = duration.map_or_else(
+            || "".to_string(), // None -> "".
+            |duration| format!(", {:.1?}", duration),
+        ); // Some() -> ", 1.2ms".
+        #[cfg(not(feature = "call_timing"))]
+        let duration_str = "";
+        self.end_pending_line();
+        let indents = self.get_indents(call_depth + 1); // The loop body end is the last child of the loop body.
+        decorator_write!(
+            self,
+            "{}{}{}{} end{}\n", // E.g. "<thread_indent><indent>`-Loop body end, 1.2ms\n".
+            indents.0,
+            indents.1,
+            self.indent_step_last,
+            LOOPBODY_NAME,
+            duration_str,
+        );
+    }
+    fn notify_folding_suspended(&mut self, call_depth: usize) {
+        self.end_pending_line();
+        let indents = self.get_indents(call_depth); // TODO: Consider -> `let (thread_indent, func_indent) =`.
+        decorator_write!(
+            self,
+            "{}{}{}{}\n", // E.g. "<thread_indent><indent>  FCL: repeat folding suspended (node budget reached)\n".
+            indents.0,
+            indents.1,
+            self.indent_step_noncall,
+            FOLDING_SUSPENDED_NOTE,
+        );
+    }
+}
+
+impl ThreadSpecific for TreeLikeDecorator {
+    fn set_thread_indent(&mut self, thread_indent: String) {
+        self.common.set_thread_indent(thread_indent);
+    }
+    fn notify_thread_start(&mut self, thread: &std::thread::Thread) {
+        self.log_thread_header(thread, "started");
+    }
+    fn notify_thread_end(&mut self, thread: &std::thread::Thread, call_count: usize) {
+        self.log_thread_header(thread, &format!("ended, {} call(s)", call_count));
+    }
+}
+
+impl WriterPossessor for TreeLikeDecorator {
+    fn set_writer(&mut self, writer: Rc<RefCell<dyn Write>>) {
+        self.common.set_writer(writer);
+    }
+}
+
+impl LogDecorator for TreeLikeDecorator {}
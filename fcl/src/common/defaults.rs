@@ -0,0 +1,67 @@
+//! The defaults of the FCL (the settings, decorators, writers, and configuration).
+
+use std::io::{Write, stdout};
+#[cfg(any(unix, feature = "parallel_logging"))]
+use std::time::Duration;
+
+use crate::common::decorators::{AnsiColors, DecoratorKind, TreeStyle};
+
+/// The kind of the log decorator created for each thread.
+pub(crate) const DECORATOR_KIND: DecoratorKind = DecoratorKind::CodeLike;
+/// Whether the ANSI colors are used in the log.
+pub(crate) const ANSI_COLORS: AnsiColors = AnsiColors::Never;
+/// The style of the `TreeLikeDecorator`'s log.
+pub(crate) const TREE_STYLE: TreeStyle = TreeStyle::Ascii;
+/// The pattern of the per-thread log file names used if the "log_merger" feature is off.
+pub(crate) const THREAD_LOG_FILE_PATTERN: &str = "fcl_{name}_{id}.log";
+/// The thread indent step used by the `CALL_LOGGER_ARBITER` (50 spaces).
+pub(crate) const THREAD_INDENT_STEP: &str = "                                                  ";
+
+/// Returns the writer used by the decorators and the `THREAD_SHARED_WRITER` if no other one is specified.
+pub(crate) fn writer() -> Box<dyn Write> {
+    Box::new(stdout())
+}
+
+/// The function indent step of the `CodeLikeDecorator` (2 spaces).
+pub(crate) const INDENT_STEP: &str = "  ";
+/// The string that prepends the function call in the `TreeLikeDecorator`'s log.
+pub(crate) const TREE_INDENT_STEP_CALL: &str = "+-";
+/// The string that prepends the repeat count in the `TreeLikeDecorator`'s log.
+pub(crate) const TREE_INDENT_STEP_NONCALL: &str = "  ";
+/// The string that shows the call depth in the `TreeLikeDecorator`'s log.
+pub(crate) const TREE_INDENT_STEP_PARENT: &str = "| ";
+/// The ANSI foreground color codes assigned to the threads in turn: cyan, yellow, magenta, green, blue, red.
+pub(crate) const THREAD_COLORS: [u8; 6] = [36, 33, 35, 32, 34, 31];
+
+/// The string used to name loop bodies in the log.
+pub(crate) const LOOPBODY_NAME: &str = "Loop body";
+/// The note logged upon the repeat folding suspension (when the call graph's node budget is reached).
+pub(crate) const FOLDING_SUSPENDED_NOTE: &str = "FCL: repeat folding suspended (node budget reached)";
+/// The label preceding the recursion depth of a recursion-folded call (e.g. `fib() recursion depth 40 {`).
+pub(crate) const RECURSION_DEPTH_LABEL: &str = "recursion depth";
+/// The maximum number of the latest children whose aggregates are retained by the `FoldedStackDecorator`
+/// (the maximum length of a repeated sequence that can be multiplied by its repeat count).
+pub(crate) const FOLDED_STACK_LATEST_CHILDREN_MAX: usize = 64;
+
+/// The maximum size of the `RotatingFileWriter`'s file (10 MiB).
+pub(crate) const ROTATING_FILE_MAX_SIZE: u64 = 10 * 1024 * 1024;
+/// The number of the `RotatingFileWriter`'s rotated files (generations) to keep.
+pub(crate) const ROTATING_FILE_GENERATIONS: usize = 5;
+/// The time the drop of the `UnixSocketWriter` may wait for its viewer to take the rest of the log.
+#[cfg(unix)]
+pub(crate) const VIEWER_WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+/// The number of bytes the `UnixSocketWriter` specified in the configuration keeps while no viewer is connected (1 MiB).
+#[cfg(all(unix, feature = "log_merger"))]
+pub(crate) const UNIX_SOCKET_BACKLOG_CAPACITY: usize = 1024 * 1024;
+/// The number of bytes of a thread's unmerged log upon reaching which the thread merges the logs of all the threads
+/// (see `CallLoggerArbiter::merge_thread_logs()`).
+#[cfg(feature = "parallel_logging")]
+pub(crate) const MERGE_THRESHOLD: usize = 64 * 1024;
+/// The time after the thread's latest merge upon reaching which the thread's next update merges the logs
+/// of all the threads (such that the log doesn't lag behind the threads making few updates).
+#[cfg(feature = "parallel_logging")]
+pub(crate) const MERGE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The name of the configuration file looked for in the current directory
+/// (unless another path is specified with the `FCL_CONFIG` environment variable).
+pub(crate) const CONFIG_FILE_NAME: &str = "fcl.toml";
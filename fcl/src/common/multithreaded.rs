@@ -120,6 +120,24 @@ impl ThreadGatekeeper {
     pub fn remove_thread_logger(&mut self) {
         self.call_logger_arbiter.borrow_mut().remove_thread_logger()
     }
+    /// Adds to the `CallLoggerArbiter` the pointers to the thread's infrastructure
+    /// updated by the thread in parallel with the other threads, and to the thread's update log
+    /// (with the "parallel_logging" feature).
+    #[cfg(feature = "parallel_logging")]
+    pub(crate) fn add_parallel_thread_logger(
+        &mut self,
+        infra: crate::common::parallel::ParallelInfraPtr,
+        update_log: crate::common::parallel::UpdateLogPtr,
+    ) {
+        self.call_logger_arbiter
+            .borrow_mut()
+            .add_parallel_thread_logger(infra, update_log)
+    }
+    /// Merges the logs of the threads updating their call graphs in parallel (with the "parallel_logging" feature).
+    #[cfg(feature = "parallel_logging")]
+    pub(crate) fn merge_thread_logs(&mut self) {
+        self.call_logger_arbiter.borrow_mut().merge_thread_logs()
+    }
 }
 // TODO: Add `CallLogger for ThreadGatekeeper` to the chart or remove `CallLogger for ThreadGatekeeper`.
 impl CallLogger for ThreadGatekeeper {
@@ -144,6 +162,27 @@ impl CallLogger for ThreadGatekeeper {
             .borrow_mut()
             .set_thread_indent(_thread_indent)
     }
+    fn set_node_budget(&mut self, node_budget: Option<usize>) {
+        self.call_logger_arbiter
+            .borrow_mut()
+            .set_node_budget(node_budget)
+    }
+    fn set_max_sequence_len(&mut self, max_sequence_len: usize) {
+        self.call_logger_arbiter
+            .borrow_mut()
+            .set_max_sequence_len(max_sequence_len)
+    }
+    fn set_recursion_threshold(&mut self, recursion_threshold: Option<usize>) {
+        self.call_logger_arbiter
+            .borrow_mut()
+            .set_recursion_threshold(recursion_threshold)
+    }
+    #[cfg(feature = "call_timing")]
+    fn set_call_timing(&mut self, call_timing: bool) {
+        self.call_logger_arbiter
+            .borrow_mut()
+            .set_call_timing(call_timing)
+    }
     fn log_call(&mut self, name: &str, 
         #[cfg(feature = "params_logging")]
         param_vals: Option<String>
@@ -170,6 +209,9 @@ impl CallLogger for ThreadGatekeeper {
     fn maybe_flush(&mut self) {
         self.call_logger_arbiter.borrow_mut().maybe_flush()
     }
+    fn dump_ring(&mut self) {
+        self.call_logger_arbiter.borrow_mut().dump_ring()
+    }
     fn log_loopbody_start(&mut self) {
         self.call_logger_arbiter.borrow_mut().log_loopbody_start()
     }
@@ -186,9 +228,11 @@ impl CallLogger for ThreadGatekeeper {
 
 /// Arbiter per-thread adapter used for synchronizing the thread access to the arbiter
 /// and destruction of the thread's logging infrastructure upon thread termination.
+#[cfg(not(feature = "parallel_logging"))]
 pub(crate) struct ThreadGateAdapter { // TODO: Consider -> ArbiterAdapter
     gatekeeper: Arc<Mutex<ThreadGatekeeper>>, // TODO: Consider -> Arc<Mutex<dyn CallLogger>>
 }
+#[cfg(not(feature = "parallel_logging"))]
 impl ThreadGateAdapter {
     /// Creates a new arbiter adapter pointing to the gatekeeper passed as an argument.
     pub fn new(gatekeeper: Arc<Mutex<ThreadGatekeeper>>) -> Self {
@@ -211,6 +255,7 @@ impl ThreadGateAdapter {
         }
     }
 }
+#[cfg(not(feature = "parallel_logging"))]
 impl Drop for ThreadGateAdapter {
     /// Removes the thread's logging infrastructure from the gatekeeper/arbiter
     /// (to destroy the thread's logging heap data upon thread termination).
@@ -218,6 +263,7 @@ impl Drop for ThreadGateAdapter {
         self.get_gatekeeper().remove_thread_logger();
     }
 }
+#[cfg(not(feature = "parallel_logging"))]
 impl CallLogger for ThreadGateAdapter {
     fn push_logging_is_on(&mut self, is_on: bool) {
         self.get_gatekeeper().push_logging_is_on(is_on);
@@ -235,6 +281,19 @@ impl CallLogger for ThreadGateAdapter {
     fn set_thread_indent(&mut self, thread_indent: String) {
         self.get_gatekeeper().set_thread_indent(thread_indent)
     }
+    fn set_node_budget(&mut self, node_budget: Option<usize>) {
+        self.get_gatekeeper().set_node_budget(node_budget)
+    }
+    fn set_max_sequence_len(&mut self, max_sequence_len: usize) {
+        self.get_gatekeeper().set_max_sequence_len(max_sequence_len)
+    }
+    fn set_recursion_threshold(&mut self, recursion_threshold: Option<usize>) {
+        self.get_gatekeeper().set_recursion_threshold(recursion_threshold)
+    }
+    #[cfg(feature = "call_timing")]
+    fn set_call_timing(&mut self, call_timing: bool) {
+        self.get_gatekeeper().set_call_timing(call_timing)
+    }
 
     fn log_call(&mut self, name: &str, 
         #[cfg(feature = "params_logging")]
@@ -260,6 +319,9 @@ impl CallLogger for ThreadGateAdapter {
     fn flush(&mut self) {
         self.get_gatekeeper().flush();
     }
+    fn dump_ring(&mut self) {
+        self.get_gatekeeper().dump_ring();
+    }
     fn log_loopbody_start(&mut self) {
         self.get_gatekeeper().log_loopbody_start()
     }
@@ -16,6 +16,9 @@ pub struct StdOutputRedirector {
     /// and the FCL can read the redirected output from for subsequent flushing
     /// at the right moment of the call log.
     tmpfile_for_fcl_to_read_from: File,
+    /// The destination the buffered std output is flushed to instead of the original std output file descriptor
+    /// (e.g. the FCL's log file). If `None` then the buffered std output is flushed to the original std output.
+    destination: Option<Box<dyn Write>>,
 }
 
 impl StdOutputRedirector {
@@ -45,6 +48,7 @@ impl StdOutputRedirector {
             original_std_output_fd,
             stdio,
             tmpfile_for_fcl_to_read_from,
+            destination: None,
         })
     }
     /// Creates the `stdout` output redirector.
@@ -67,6 +71,19 @@ impl StdOutputRedirector {
     pub fn get_original_writer(&mut self) -> &mut dyn Write {
         &mut self.original_std_output_fd
     }
+    /// Sets the destination the buffered std output is flushed to
+    /// instead of the original std output file descriptor.
+    pub fn set_destination(&mut self, destination: Box<dyn Write>) {
+        self.destination = Some(destination);
+    }
+    /// Returns the reference to the `Write` trait of the destination the buffered std output is flushed to
+    /// (the one set with `set_destination()`, otherwise the original std output file descriptor).
+    pub fn get_destination_writer(&mut self) -> &mut dyn Write {
+        match &mut self.destination {
+            Some(destination) => destination.as_mut(),
+            None => &mut self.original_std_output_fd,
+        }
+    }
     /// Returns the creation result of the wrapper containting
     /// the cloned original std output file descriptor.
     pub fn clone_original_writer(&self) -> filedescriptor::Result<File> {
@@ -78,7 +95,7 @@ impl StdOutputRedirector {
         &mut self.tmpfile_for_fcl_to_read_from
     }
     /// Reads the content of the temporary file (the redirected user's std output) since last read,
-    /// and writes, if any, to the original std output file descriptor.
+    /// and writes, if any, to the destination (by default the original std output file descriptor).
     pub fn flush(&mut self) {
         let mut buf_content = String::new();
         let read_result = self.get_buffer_reader().read_to_string(&mut buf_content);
@@ -86,14 +103,14 @@ impl StdOutputRedirector {
             && size != 0
         {
             let _ignore_error = self // redirector
-                .get_original_writer()
+                .get_destination_writer()
                 .write_all(buf_content.as_bytes());
         }
     }
 }
 
 impl Drop for StdOutputRedirector {
-    /// Flushes the data from the temporary file to the original std output file descriptor
+    /// Flushes the data from the temporary file to the destination (by default the original std output file descriptor)
     /// and recovers the std output to a state before the redirection.
     fn drop(&mut self) {
         // Flush the buffered data:
@@ -102,7 +119,7 @@ impl Drop for StdOutputRedirector {
         let flush_error = match read_result {
             Ok(size) => {
                 if size != 0 {
-                    let write_result = self.get_original_writer().write_all(buf_content.as_bytes());
+                    let write_result = self.get_destination_writer().write_all(buf_content.as_bytes());
                     if let Err(e) = write_result {
                         Some(e)
                     } else {
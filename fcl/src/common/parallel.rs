@@ -0,0 +1,405 @@
+use std::{
+    cell::RefCell,
+    io::{self, Write},
+    sync::{
+        Arc, Mutex, MutexGuard, TryLockError,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Instant,
+};
+
+use crate::common::CallLogger;
+use crate::common::defaults;
+use crate::common::call_log_infra::CallLogInfra;
+use crate::common::multithreaded::ThreadGatekeeper;
+
+
+/// The global thread-shared counter of the call graph updates.
+static NEXT_UPDATE_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Returns the ID of the next call graph update (by any thread).
+/// The IDs grow in the order the updates take place.
+pub fn next_update_id() -> u64 {
+    NEXT_UPDATE_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// The thread's decorator output not yet written to the log,
+/// split into chunks by the ID of the call graph update that produced the chunk.
+pub struct UpdateLog {
+    /// The ID of the latest call graph update.
+    update_id: u64,
+    /// Whether the latest call graph update is in progress.
+    update_is_in_progress: bool,
+    /// The chunks of the output (the oldest first) with the IDs of the updates that produced them.
+    chunks: Vec<(u64, Vec<u8>)>,
+    /// The total number of bytes in the chunks.
+    len: usize,
+}
+
+impl UpdateLog {
+    /// Creates a new empty `UpdateLog`.
+    pub fn new() -> Self {
+        Self {
+            update_id: 0,
+            update_is_in_progress: false,
+            chunks: Vec::new(),
+            len: 0,
+        }
+    }
+    /// Sets the ID of the call graph update in progress (the subsequent output is attributed to that update).
+    pub fn set_update_id(&mut self, update_id: u64) {
+        self.update_id = update_id;
+    }
+    /// Starts the call graph update with a new update ID (the subsequent output is attributed to that update).
+    ///
+    /// The ID is acquired under the update log's mutex, such that the merge holding the mutexes of all the threads'
+    /// update logs sees either the update in progress or none, the latter getting the ID higher than all acquired.
+    pub fn begin_update(&mut self) {
+        self.update_id = next_update_id();
+        self.update_is_in_progress = true;
+    }
+    /// Marks the call graph update in progress as complete.
+    pub fn end_update(&mut self) {
+        self.update_is_in_progress = false;
+    }
+    /// Returns the ID of the call graph update in progress, if any.
+    pub fn update_in_progress(&self) -> Option<u64> {
+        self.update_is_in_progress.then_some(self.update_id)
+    }
+    /// Returns the total number of bytes not yet written to the log.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Returns whether there is no output not yet written to the log.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Takes the chunks of the output (the oldest first) with the IDs of the updates that produced them.
+    pub fn take_chunks(&mut self) -> Vec<(u64, Vec<u8>)> {
+        self.len = 0;
+        std::mem::take(&mut self.chunks)
+    }
+    /// Takes the chunks of the output (the oldest first) produced by the updates with the IDs lower than `update_id`,
+    /// keeps the rest.
+    pub fn take_chunks_before(&mut self, update_id: u64) -> Vec<(u64, Vec<u8>)> {
+        let (taken, kept) = std::mem::take(&mut self.chunks)
+            .into_iter()
+            .partition(|(chunk_update_id, _chunk)| *chunk_update_id < update_id);
+        self.chunks = kept;
+        self.len = self.chunks.iter().map(|(_update_id, chunk)| chunk.len()).sum();
+        taken
+    }
+}
+
+impl Default for UpdateLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Write for UpdateLog {
+    /// Appends the bytes to the chunk of the update in progress.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.chunks.last_mut() {
+            Some((update_id, chunk)) if *update_id == self.update_id => chunk.extend_from_slice(buf),
+            _ => self.chunks.push((self.update_id, buf.to_vec())),
+        }
+        self.len += buf.len();
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Merges the chunks of the threads' output in the order of the IDs of the updates that produced them.
+///
+/// Each thread's chunks are expected in the growing order of IDs (as taken from `UpdateLog::take_chunks()`).
+pub fn merge_chunks(threads_chunks: Vec<Vec<(u64, Vec<u8>)>>) -> Vec<u8> {
+    let mut chunks: Vec<(u64, Vec<u8>)> = threads_chunks.into_iter().flatten().collect();
+    chunks.sort_by_key(|(update_id, _chunk)| *update_id); // Stable, preserves the order within the update.
+    chunks.into_iter().flat_map(|(_update_id, chunk)| chunk).collect()
+}
+
+/// The pointer to the thread's `UpdateLog` shared by the thread's decorator writer and the thread's `ParallelInfra`.
+pub(crate) type UpdateLogPtr = Arc<Mutex<UpdateLog>>;
+
+/// The writer of the thread's decorator that writes to the thread's `UpdateLog`.
+struct UpdateLogWriter {
+    /// The thread's update log.
+    update_log: UpdateLogPtr,
+}
+
+impl Write for UpdateLogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        lock(&self.update_log).write(buf)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+thread_local! {
+    /// The calling thread's update log created upon the `THREAD_DECORATOR` creation
+    /// and taken upon the `THREAD_LOGGER` creation.
+    static THREAD_UPDATE_LOG: RefCell<Option<UpdateLogPtr>> = const { RefCell::new(None) };
+}
+
+/// Creates the calling thread's update log and returns the writer to it (for the thread's decorator).
+pub(crate) fn new_thread_update_log_writer() -> Box<dyn Write> {
+    let update_log = Arc::new(Mutex::new(UpdateLog::new()));
+    let _ignore_tls_destruction =
+        THREAD_UPDATE_LOG.try_with(|thread_update_log| *thread_update_log.borrow_mut() = Some(update_log.clone()));
+    Box::new(UpdateLogWriter { update_log })
+}
+
+/// Takes the calling thread's update log (or creates a new one if the thread's decorator has not created it).
+pub(crate) fn take_thread_update_log() -> UpdateLogPtr {
+    THREAD_UPDATE_LOG
+        .try_with(|thread_update_log| thread_update_log.borrow_mut().take())
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| Arc::new(Mutex::new(UpdateLog::new())))
+}
+
+/// Locks the mutex recovering it if poisoned (the FCL's data are still usable for logging).
+pub(crate) fn lock<T: ?Sized>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// The thread's call logging infrastructure that the thread updates in parallel with the other threads
+/// (under the thread's own mutex), and the thread's output not yet written to the log.
+pub(crate) struct ParallelInfra {
+    /// The thread's call logging infrastructure.
+    infra: CallLogInfra,
+    /// The thread's decorator output not yet written to the log.
+    update_log: UpdateLogPtr,
+    /// Whether the thread's logger has been removed from the `CallLoggerArbiter`
+    /// (e.g. by the FCL's panic hook), after which the updates are ignored.
+    is_removed: bool,
+}
+
+impl ParallelInfra {
+    /// Creates a new `ParallelInfra` from the thread's call logging infrastructure and update log.
+    pub(crate) fn new(infra: CallLogInfra, update_log: UpdateLogPtr) -> Self {
+        Self {
+            infra,
+            update_log,
+            is_removed: false,
+        }
+    }
+    /// Makes the call graph update (with a new update ID) unless the thread's logger has been removed.
+    /// Returns the number of bytes of the thread's output not yet written to the log.
+    fn update(&mut self, update: impl FnOnce(&mut CallLogInfra)) -> usize {
+        if self.is_removed {
+            return 0;
+        }
+        lock(&self.update_log).begin_update();
+        update(&mut self.infra);
+        let mut update_log = lock(&self.update_log);
+        update_log.end_update();
+        update_log.len()
+    }
+    /// Flushes the cache of the thread's call graph (as an update with a new update ID).
+    pub(crate) fn flush_cache(&mut self) {
+        lock(&self.update_log).begin_update();
+        self.infra.flush();
+        lock(&self.update_log).end_update();
+    }
+    /// Marks the thread's logger as removed from the `CallLoggerArbiter`.
+    pub(crate) fn set_removed(&mut self) {
+        self.is_removed = true;
+    }
+}
+
+/// The pointer to the thread's `ParallelInfra` shared by the thread's `THREAD_LOGGER` and the `CallLoggerArbiter`.
+pub(crate) type ParallelInfraPtr = Arc<Mutex<ParallelInfra>>;
+
+/// The `CallLogger` forwarding to the thread's `CallLogInfra` (under the thread's mutex),
+/// registered in the `CallLoggerArbiter` (for the thread indent assignment).
+pub(crate) struct ParallelInfraAdapter {
+    /// The thread's infrastructure.
+    infra: ParallelInfraPtr,
+}
+
+impl ParallelInfraAdapter {
+    /// Creates a new `ParallelInfraAdapter` pointing to the thread's infrastructure.
+    pub(crate) fn new(infra: ParallelInfraPtr) -> Self {
+        Self { infra }
+    }
+}
+
+impl CallLogger for ParallelInfraAdapter {
+    fn push_logging_is_on(&mut self, is_on: bool) {
+        lock(&self.infra).infra.push_logging_is_on(is_on)
+    }
+    fn pop_logging_is_on(&mut self) {
+        lock(&self.infra).infra.pop_logging_is_on()
+    }
+    fn logging_is_on(&self) -> bool {
+        lock(&self.infra).infra.logging_is_on()
+    }
+    fn set_logging_is_on(&mut self, is_on: bool) {
+        lock(&self.infra).infra.set_logging_is_on(is_on)
+    }
+    fn set_thread_indent(&mut self, thread_indent: String) {
+        lock(&self.infra).infra.set_thread_indent(thread_indent)
+    }
+    fn log_thread_start(&mut self) {
+        lock(&self.infra).update(|infra| infra.log_thread_start());
+    }
+    fn log_thread_end(&mut self) {
+        // The panicking thread can hold its own mutex (if panicking amid an update),
+        // in which case the thread end is not logged:
+        match self.infra.try_lock() {
+            Ok(mut infra) => infra.update(|infra| infra.log_thread_end()),
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner().update(|infra| infra.log_thread_end()),
+            Err(TryLockError::WouldBlock) => 0,
+        };
+    }
+    fn log_call(&mut self, name: &str, #[cfg(feature = "params_logging")] param_vals: Option<String>) {
+        lock(&self.infra).update(|infra| {
+            infra.log_call(
+                name,
+                #[cfg(feature = "params_logging")]
+                param_vals,
+            )
+        });
+    }
+    fn log_ret(&mut self, #[cfg(feature = "ret_val_logging")] ret_val: Option<String>) {
+        lock(&self.infra).update(|infra| {
+            infra.log_ret(
+                #[cfg(feature = "ret_val_logging")]
+                ret_val,
+            )
+        });
+    }
+    fn flush(&mut self) {
+        lock(&self.infra).flush_cache()
+    }
+    fn maybe_flush(&mut self) {}
+    fn log_loopbody_start(&mut self) {
+        lock(&self.infra).update(|infra| infra.log_loopbody_start());
+    }
+    fn log_loopbody_end(&mut self) {
+        lock(&self.infra).update(|infra| infra.log_loopbody_end());
+    }
+    fn log_loop_end(&mut self) {
+        lock(&self.infra).update(|infra| infra.log_loop_end());
+    }
+}
+
+/// The thread's logger (`THREAD_LOGGER`) with the "parallel_logging" feature.
+///
+/// Updates the thread's call graph under the thread's own mutex, in parallel with the other threads,
+/// and acquires the `ThreadGatekeeper`'s mutex only for merging the logs of all the threads
+/// (when the thread's unmerged output grows large, before the user's standard output, and upon flush).
+pub(crate) struct ParallelThreadLogger {
+    /// The thread's infrastructure.
+    infra: ParallelInfraPtr,
+    /// The gatekeeper of the `CallLoggerArbiter` merging the logs.
+    gatekeeper: Arc<Mutex<ThreadGatekeeper>>,
+    /// The time of the latest merge initiated by the thread.
+    last_merge: Instant,
+}
+
+impl ParallelThreadLogger {
+    /// Creates a new `ParallelThreadLogger` for the thread's infrastructure.
+    pub(crate) fn new(infra: ParallelInfraPtr, gatekeeper: Arc<Mutex<ThreadGatekeeper>>) -> Self {
+        Self {
+            infra,
+            gatekeeper,
+            last_merge: Instant::now(),
+        }
+    }
+    /// Makes the call graph update and merges the logs of all the threads
+    /// if the thread's unmerged output has reached the threshold or has been waiting for the merge interval.
+    fn update(&mut self, update: impl FnOnce(&mut CallLogInfra)) {
+        // The thread's mutex is released before locking the gatekeeper's one,
+        // since the merge locks the mutexes of all the threads.
+        let unmerged_len = lock(&self.infra).update(update);
+        if unmerged_len >= defaults::MERGE_THRESHOLD
+            || (unmerged_len != 0 && self.last_merge.elapsed() >= defaults::MERGE_INTERVAL)
+        {
+            lock(&self.gatekeeper).merge_thread_logs();
+            self.last_merge = Instant::now();
+        }
+    }
+}
+
+impl Drop for ParallelThreadLogger {
+    /// Removes the thread's logging infrastructure from the gatekeeper/arbiter
+    /// (after merging the thread's log).
+    fn drop(&mut self) {
+        lock(&self.gatekeeper).remove_thread_logger();
+    }
+}
+
+impl CallLogger for ParallelThreadLogger {
+    fn push_logging_is_on(&mut self, is_on: bool) {
+        lock(&self.infra).infra.push_logging_is_on(is_on)
+    }
+    fn pop_logging_is_on(&mut self) {
+        lock(&self.infra).infra.pop_logging_is_on()
+    }
+    fn logging_is_on(&self) -> bool {
+        lock(&self.infra).infra.logging_is_on()
+    }
+    fn set_logging_is_on(&mut self, is_on: bool) {
+        lock(&self.infra).infra.set_logging_is_on(is_on)
+    }
+    fn set_thread_indent(&mut self, thread_indent: String) {
+        lock(&self.infra).infra.set_thread_indent(thread_indent)
+    }
+    fn set_node_budget(&mut self, node_budget: Option<usize>) {
+        lock(&self.infra).infra.set_node_budget(node_budget)
+    }
+    fn set_max_sequence_len(&mut self, max_sequence_len: usize) {
+        lock(&self.infra).infra.set_max_sequence_len(max_sequence_len)
+    }
+    fn set_recursion_threshold(&mut self, recursion_threshold: Option<usize>) {
+        lock(&self.infra).infra.set_recursion_threshold(recursion_threshold)
+    }
+    #[cfg(feature = "call_timing")]
+    fn set_call_timing(&mut self, call_timing: bool) {
+        lock(&self.infra).infra.set_call_timing(call_timing)
+    }
+    fn log_call(&mut self, name: &str, #[cfg(feature = "params_logging")] param_vals: Option<String>) {
+        self.update(|infra| {
+            infra.log_call(
+                name,
+                #[cfg(feature = "params_logging")]
+                param_vals,
+            )
+        })
+    }
+    fn log_ret(&mut self, #[cfg(feature = "ret_val_logging")] ret_val: Option<String>) {
+        self.update(|infra| {
+            infra.log_ret(
+                #[cfg(feature = "ret_val_logging")]
+                ret_val,
+            )
+        })
+    }
+    fn flush(&mut self) {
+        lock(&self.gatekeeper).flush()
+    }
+    fn maybe_flush(&mut self) {
+        lock(&self.gatekeeper).maybe_flush()
+    }
+    fn dump_ring(&mut self) {
+        lock(&self.gatekeeper).dump_ring()
+    }
+    fn log_loopbody_start(&mut self) {
+        self.update(|infra| infra.log_loopbody_start())
+    }
+    fn log_loopbody_end(&mut self) {
+        self.update(|infra| infra.log_loopbody_end())
+    }
+    fn log_loop_end(&mut self) {
+        self.update(|infra| infra.log_loop_end())
+    }
+}
@@ -0,0 +1,147 @@
+use std::{
+    collections::VecDeque,
+    fs::OpenOptions,
+    io::{self, Write},
+    path::PathBuf,
+    sync::{Arc, Mutex, TryLockError},
+};
+
+/// The bytes of the ring buffer and its settings.
+struct RingBuffer {
+    /// The bytes logged most recently (the oldest first).
+    bytes: VecDeque<u8>,
+    /// The maximum number of bytes kept.
+    capacity: usize,
+    /// Whether any bytes have been overwritten (i.e. the first line can be incomplete).
+    overwritten: bool,
+    /// The file the ring buffer is dumped to, `None` for `stderr`.
+    dump_path: Option<PathBuf>,
+}
+
+/// The writer that keeps the last `capacity` bytes written to it in memory,
+/// such that the oldest log entries are overwritten with the new ones.
+///
+/// To be used as the FCL's log writer (see `fcl::common::settings::set_fcl_writer()`),
+/// in which case the ring buffer is dumped by the FCL's panic hook and by `fcl::dump_ring()`
+/// (to the file specified upon creation or to `stderr`), and the user's standard output
+/// (synchronized with the log) lands in the same ring buffer.
+///
+/// The clones share the same ring buffer.
+/// #### Examples
+/// ```rs
+/// use fcl::common::ring_buffer_writer::RingBufferWriter;
+/// let writer = RingBufferWriter::new(4 * 1024 * 1024, Some("fcl_ring.log".into())); // The last 4 MiB.
+/// ```
+#[derive(Clone)]
+pub struct RingBufferWriter {
+    /// The ring buffer shared by the clones.
+    ring: Arc<Mutex<RingBuffer>>,
+}
+
+impl RingBufferWriter {
+    /// Creates a new `RingBufferWriter` keeping the last `capacity` bytes
+    /// and dumping them to the file at `dump_path` (appending each dump) or, if `None`, to `stderr`.
+    pub fn new(capacity: usize, dump_path: Option<PathBuf>) -> Self {
+        Self {
+            ring: Arc::new(Mutex::new(RingBuffer {
+                bytes: VecDeque::with_capacity(capacity),
+                capacity,
+                overwritten: false,
+                dump_path,
+            })),
+        }
+    }
+    /// Returns the file path the ring buffer is dumped to, `None` for `stderr`.
+    pub fn dump_path(&self) -> Option<PathBuf> {
+        match self.ring.lock() {
+            Ok(ring) => ring.dump_path.clone(),
+            Err(poisoned) => poisoned.into_inner().dump_path.clone(),
+        }
+    }
+    /// Writes the content of the ring buffer (starting with the first complete line)
+    /// between the dump start and end notes to the `writer`.
+    ///
+    /// Fails (rather than blocks) if the ring buffer is being written to,
+    /// such that it is safe to call from the panic hook.
+    pub fn dump(&self, writer: &mut dyn Write) -> io::Result<()> {
+        let ring = match self.ring.try_lock() {
+            Ok(ring) => ring,
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+            Err(TryLockError::WouldBlock) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::WouldBlock,
+                    "The FCL ring buffer is busy",
+                ));
+            }
+        };
+        let (front, back) = ring.bytes.as_slices();
+        let mut content = [front, back].concat();
+        if ring.overwritten {
+            // Skip the incomplete first line:
+            let first_line_len = content
+                .iter()
+                .position(|byte| *byte == b'\n')
+                .map_or(content.len(), |position| position + 1);
+            content.drain(..first_line_len);
+        }
+        writeln!(writer, "// FCL: ring buffer dump start.")?;
+        writer.write_all(&content)?;
+        if content.last().is_some_and(|byte| *byte != b'\n') {
+            writeln!(writer)?;
+        }
+        writeln!(writer, "// FCL: ring buffer dump end.")?;
+        writer.flush()
+    }
+    /// Dumps the ring buffer to the file at the dump path, if any, otherwise to the `stderr` writer passed.
+    /// The dump is appended to the file such that the earlier dumps (e.g. by `fcl::dump_ring()` before a panic)
+    /// are preserved.
+    pub(crate) fn dump_to_destination(&self, stderr: &mut dyn Write) -> io::Result<()> {
+        match self.dump_path() {
+            Some(path) => self.dump(&mut OpenOptions::new().create(true).append(true).open(path)?),
+            None => self.dump(stderr),
+        }
+    }
+}
+
+impl Write for RingBufferWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut ring = match self.ring.lock() {
+            Ok(ring) => ring,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        // Keep only the tail of the buffer if it is larger than the ring buffer:
+        let tail = &buf[buf.len().saturating_sub(ring.capacity)..];
+        // Overwrite the oldest bytes:
+        let excess = (ring.bytes.len() + tail.len()).saturating_sub(ring.capacity);
+        if excess != 0 || tail.len() != buf.len() {
+            ring.bytes.drain(..excess);
+            ring.overwritten = true;
+        }
+        ring.bytes.extend(tail);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The ring buffer used as the FCL's log writer, if any (dumped by the FCL's panic hook and `fcl::dump_ring()`).
+static FCL_RING: Mutex<Option<RingBufferWriter>> = Mutex::new(None);
+
+/// Remembers the ring buffer used as the FCL's log writer.
+#[cfg(any(feature = "log_merger", feature = "std_output_sync"))]
+pub(crate) fn set_fcl_ring(ring: RingBufferWriter) {
+    match FCL_RING.lock() {
+        Ok(mut fcl_ring) => *fcl_ring = Some(ring),
+        Err(poisoned) => *poisoned.into_inner() = Some(ring),
+    }
+}
+
+/// Returns the ring buffer used as the FCL's log writer, if any.
+pub fn fcl_ring() -> Option<RingBufferWriter> {
+    match FCL_RING.try_lock() {
+        Ok(fcl_ring) => fcl_ring.clone(),
+        Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner().clone(),
+        Err(TryLockError::WouldBlock) => None,
+    }
+}
@@ -0,0 +1,141 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use crate::common::defaults;
+
+/// The rotation policy of the `RotatingFileWriter`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FileRotation {
+    /// The maximum size (in bytes) of the current file (e.g. `fcl.log`), `None` for unlimited.
+    /// The file is rotated before the line that would exceed the size.
+    pub max_size: Option<u64>,
+    /// The maximum age of the current file (since the file has been opened), `None` for unlimited.
+    /// The file is rotated before the first line logged after the age has been reached.
+    pub max_age: Option<Duration>,
+    /// The number of the rotated files (generations) to keep (e.g. `fcl.log.1`, `fcl.log.2`, ...).
+    /// If `0` then the current file is truncated upon rotation.
+    pub generations: usize,
+}
+
+impl Default for FileRotation {
+    /// 10 MiB, unlimited age, 5 generations.
+    fn default() -> Self {
+        Self {
+            max_size: Some(defaults::ROTATING_FILE_MAX_SIZE),
+            max_age: None,
+            generations: defaults::ROTATING_FILE_GENERATIONS,
+        }
+    }
+}
+
+/// The writer that writes to a file (e.g. `fcl.log`) and rotates it by size and/or age,
+/// i.e. renames the file to `fcl.log.1` (the previous `fcl.log.1` to `fcl.log.2`, etc.) and starts a new `fcl.log`.
+/// The rotated files beyond the `FileRotation::generations` are removed.
+///
+/// The rotation happens only at the line starts, such that a line is never split between the files
+/// (a line can be written with multiple `write()` calls).
+///
+/// To be used as the FCL's log writer (see `fcl::common::settings::set_fcl_writer()`),
+/// in which case the user's standard output (synchronized with the log) lands in the same files,
+/// or as the writer of a decorator (e.g. see `fcl::common::settings::add_tee_output()`).
+/// #### Examples
+/// ```rs
+/// use fcl::common::rotating_file_writer::{FileRotation, RotatingFileWriter};
+/// let writer = RotatingFileWriter::new("fcl.log", FileRotation { max_size: Some(1_000_000), ..Default::default() })?;
+/// ```
+pub struct RotatingFileWriter {
+    /// The path of the current file (e.g. `fcl.log`).
+    path: PathBuf,
+    /// The rotation policy.
+    rotation: FileRotation,
+    /// The current file.
+    file: File,
+    /// The size of the current file.
+    size: u64,
+    /// The moment the current file has been opened.
+    opened: Instant,
+    /// Whether the next byte starts a line (the rotation is possible).
+    at_line_start: bool,
+}
+
+impl RotatingFileWriter {
+    /// Creates a new `RotatingFileWriter` that appends to the file at `path` (the file is created if absent)
+    /// and rotates it according to the `rotation` policy.
+    pub fn new(path: impl AsRef<Path>, rotation: FileRotation) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self {
+            size: file.metadata()?.len(),
+            path,
+            rotation,
+            file,
+            opened: Instant::now(),
+            at_line_start: true,
+        })
+    }
+    /// Returns the path of the rotated file of the `generation` (e.g. `fcl.log.2` for the generation 2).
+    fn generation_path(&self, generation: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{}", generation));
+        PathBuf::from(path)
+    }
+    /// Returns whether the current file needs to be rotated before writing `length` more bytes.
+    fn rotation_is_due(&self, length: usize) -> bool {
+        let size_exceeded = self
+            .rotation
+            .max_size
+            .is_some_and(|max_size| self.size > 0 && self.size + length as u64 > max_size);
+        let age_reached = self
+            .rotation
+            .max_age
+            .is_some_and(|max_age| self.opened.elapsed() >= max_age);
+        size_exceeded || age_reached
+    }
+    /// Rotates the files and opens the new current file.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        if self.rotation.generations > 0 {
+            // fcl.log.4 -> fcl.log.5, ..., fcl.log.1 -> fcl.log.2, fcl.log -> fcl.log.1:
+            let _ignore_absence = std::fs::remove_file(self.generation_path(self.rotation.generations));
+            for generation in (1..self.rotation.generations).rev() {
+                let _ignore_absence = std::fs::rename(
+                    self.generation_path(generation),
+                    self.generation_path(generation + 1),
+                );
+            }
+            std::fs::rename(&self.path, self.generation_path(1))?;
+        }
+        self.file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.path)?;
+        self.size = 0;
+        self.opened = Instant::now();
+        Ok(())
+    }
+}
+
+impl Write for RotatingFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.at_line_start && self.rotation_is_due(buf.len()) {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        if written > 0 {
+            self.at_line_start = buf[written - 1] == b'\n';
+        }
+        Ok(written)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
@@ -0,0 +1,328 @@
+use std::{
+    borrow::Cow,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+#[cfg(feature = "log_merger")]
+use crate::common::call_log_infra::{FclWriter, set_pending_fcl_writer};
+use crate::common::defaults;
+use crate::common::decorators::{
+    AnsiColors, DecoratorFactory, DecoratorKind, DotGraphWriterFactory, LinePrefixes, TeeWriterFactory, TreeStyle,
+};
+
+/// The process-wide settings of the FCL (set with the `set_*()` functions below or with `fcl::Builder`).
+///
+/// The settings are read (rather than observed) by the logging infrastructure, hence take effect as follows:
+/// * the per-thread settings (the decorator kind, factory, indent step, ANSI colors, line prefixes, tree style,
+///   thread headers, additional outputs, and thread log file pattern) are read upon each thread's first logged call
+///   (that creates the thread's decorator), i.e. affect the threads that make their first logged call afterwards;
+/// * the infrastructure settings (the thread indent step, panic sync, and standard output sync)
+///   are read upon the first logged call of the process (that creates the logging infrastructure)
+///   and have no effect afterwards;
+/// * the static call graph output is read upon the drop of the last `DotGraphDecorator`.
+///
+/// In other words, the settings are to be set at the beginning of a non-loggable `main()`, before the first logged call.
+pub(crate) struct Settings {
+    /// The kind of the log decorator that is created for each thread.
+    pub(crate) decorator_kind: DecoratorKind,
+    /// The function creating the log decorator for each thread instead of the one of the `decorator_kind`, if any.
+    pub(crate) decorator_factory: Option<DecoratorFactory>,
+    /// The function indent step of the `CodeLikeDecorator` created for each thread (`None` for the default one).
+    pub(crate) indent_step: Option<String>,
+    /// Whether the ANSI colors are used in the log.
+    pub(crate) ansi_colors: AnsiColors,
+    /// The prefixes of each line logged by the text decorators.
+    pub(crate) line_prefixes: LinePrefixes,
+    /// The style of the `TreeLikeDecorator`'s log.
+    pub(crate) tree_style: TreeStyle,
+    /// Tells whether the thread start header and thread end summary lines are logged.
+    pub(crate) thread_headers: bool,
+    /// The additional log outputs (the decorator kind and writer factory of each one).
+    pub(crate) tee_outputs: Vec<(DecoratorKind, TeeWriterFactory)>,
+    /// The writer factory of the static call graph's output (`None` for the writer of the last dropped decorator).
+    pub(crate) dot_graph_output: Option<DotGraphWriterFactory>,
+    /// The pattern of the per-thread log file names used if the "log_merger" feature is off
+    /// (`None` for logging to `stdout`).
+    pub(crate) thread_log_file_pattern: Option<Cow<'static, str>>,
+    /// The thread indent step used by the `CALL_LOGGER_ARBITER` (`None` for the default one).
+    pub(crate) thread_indent_step: Option<String>,
+    /// Whether the `CALL_LOGGER_ARBITER` replaces the default panic hook with its own one.
+    pub(crate) panic_sync_is_on: bool,
+    /// Whether the `CALL_LOGGER_ARBITER` redirects the user's standard output for synchronizing it with the log.
+    #[cfg(feature = "std_output_sync")]
+    pub(crate) std_output_sync_is_on: bool,
+    /// Whether the configuration (see `fcl::common::config::Config`) has been applied to the settings.
+    config_is_applied: bool,
+    /// Whether the logging infrastructure has been created (upon the first logged call),
+    /// i.e. whether it is too late for `fcl::Builder::init()`.
+    pub(crate) infra_is_created: bool,
+}
+
+/// The FCL settings.
+static SETTINGS: Mutex<Settings> = Mutex::new(Settings {
+    decorator_kind: defaults::DECORATOR_KIND,
+    decorator_factory: None,
+    indent_step: None,
+    ansi_colors: defaults::ANSI_COLORS,
+    line_prefixes: LinePrefixes {
+        elapsed: false,
+        wall_clock: false,
+        thread: false,
+    },
+    tree_style: defaults::TREE_STYLE,
+    thread_headers: false,
+    tee_outputs: Vec::new(),
+    dot_graph_output: None,
+    thread_log_file_pattern: Some(Cow::Borrowed(defaults::THREAD_LOG_FILE_PATTERN)),
+    thread_indent_step: None,
+    panic_sync_is_on: true,
+    #[cfg(feature = "std_output_sync")]
+    std_output_sync_is_on: true,
+    config_is_applied: false,
+    infra_is_created: false,
+});
+
+/// Returns the locked FCL settings (a panic while the settings were locked doesn't make them unusable).
+///
+/// Upon the first access applies the configuration (see `fcl::common::config::Config`) to the settings,
+/// such that the settings made in the code afterwards take precedence over the configuration.
+pub(crate) fn settings() -> MutexGuard<'static, Settings> {
+    let mut settings = SETTINGS.lock().unwrap_or_else(PoisonError::into_inner);
+    if !settings.config_is_applied {
+        settings.config_is_applied = true;
+        crate::common::config::config().apply(&mut settings);
+    }
+    settings
+}
+
+/// Sets the kind of the log decorator that is created for each thread.
+/// #### Examples
+/// ```rs
+/// fcl::common::settings::set_decorator_kind(fcl::common::decorators::DecoratorKind::JsonLines);
+/// ```
+pub fn set_decorator_kind(decorator_kind: DecoratorKind) {
+    settings().decorator_kind = decorator_kind;
+}
+
+/// Returns the kind of the log decorator that is created for each thread.
+pub fn decorator_kind() -> DecoratorKind {
+    settings().decorator_kind
+}
+
+/// Sets the function creating the log decorator for each thread
+/// (`None` for the decorator of the kind returned by `decorator_kind()`).
+/// The function gets the writer to the FCL's log (or `None` for the decorator's default writer).
+/// #### Examples
+/// ```rs
+/// use fcl::common::{decorators::CodeLikeDecorator, settings::set_decorator_factory};
+/// set_decorator_factory(Some(|writer| {
+///     std::rc::Rc::new(std::cell::RefCell::new(CodeLikeDecorator::new(writer, Some("\t"))))
+/// }));
+/// ```
+pub fn set_decorator_factory(factory: Option<DecoratorFactory>) {
+    settings().decorator_factory = factory;
+}
+
+/// Returns the function creating the log decorator for each thread, if any.
+pub fn decorator_factory() -> Option<DecoratorFactory> {
+    settings().decorator_factory
+}
+
+/// Sets the function indent step of the `CodeLikeDecorator` created for each thread
+/// (`None` for the default one, 2 spaces).
+/// #### Examples
+/// ```rs
+/// fcl::common::settings::set_indent_step(Some(String::from("    ")));
+/// ```
+pub fn set_indent_step(indent_step: Option<String>) {
+    settings().indent_step = indent_step;
+}
+
+/// Returns the function indent step of the `CodeLikeDecorator` created for each thread (`None` for the default one).
+pub fn indent_step() -> Option<String> {
+    settings().indent_step.clone()
+}
+
+/// Sets whether the ANSI colors are used in the log, in particular to color each thread's lines
+/// in a distinct color (in addition to or instead of the thread indent).
+/// #### Examples
+/// ```rs
+/// fcl::common::settings::set_ansi_colors(fcl::common::decorators::AnsiColors::Auto);
+/// ```
+pub fn set_ansi_colors(colors: AnsiColors) {
+    settings().ansi_colors = colors;
+}
+
+/// Returns whether the ANSI colors are used in the log.
+pub fn ansi_colors() -> AnsiColors {
+    settings().ansi_colors
+}
+
+/// Sets the prefixes of each line logged by the text decorators (`CodeLikeDecorator`, `TreeLikeDecorator`).
+/// #### Examples
+/// ```rs
+/// use fcl::common::{decorators::LinePrefixes, settings::set_line_prefixes};
+/// set_line_prefixes(LinePrefixes { elapsed: true, thread: true, ..Default::default() });
+/// ```
+pub fn set_line_prefixes(prefixes: LinePrefixes) {
+    settings().line_prefixes = prefixes;
+}
+
+/// Returns the prefixes of each line logged by the text decorators.
+pub fn line_prefixes() -> LinePrefixes {
+    settings().line_prefixes
+}
+
+/// Sets the style of the `TreeLikeDecorator`'s log.
+/// #### Examples
+/// ```rs
+/// use fcl::common::{decorators::TreeStyle, settings::set_tree_style};
+/// set_tree_style(TreeStyle::Unicode);
+/// ```
+pub fn set_tree_style(style: TreeStyle) {
+    settings().tree_style = style;
+}
+
+/// Returns the style of the `TreeLikeDecorator`'s log.
+pub fn tree_style() -> TreeStyle {
+    settings().tree_style
+}
+
+/// Enables or disables logging the thread start header (e.g. `==== thread 'worker-3' (#4) started ====`)
+/// and thread end summary line (e.g. `==== thread 'worker-3' (#4) ended, 15 call(s) ====`)
+/// in the thread's "column" by the text decorators (`CodeLikeDecorator`, `TreeLikeDecorator`),
+/// or the `"thread_start"` and `"thread_end"` events by the `JsonLinesDecorator`.
+/// #### Examples
+/// ```rs
+/// fcl::common::settings::set_thread_headers(true);
+/// ```
+pub fn set_thread_headers(headers: bool) {
+    settings().thread_headers = headers;
+}
+
+/// Returns whether the thread start header and thread end summary lines are logged.
+pub fn thread_headers() -> bool {
+    settings().thread_headers
+}
+
+/// Adds an additional log output, i.e. the decorator of the `kind` writing to the writer created by `make_writer`.
+/// If there are additional outputs, each thread's decorator is a `TeeDecorator` forwarding to the decorator
+/// of the kind returned by `decorator_kind()` and to each additional output's decorator.
+/// #### Examples
+/// ```rs
+/// use fcl::common::{decorators::DecoratorKind, settings::add_tee_output};
+/// // The code-like log on the standard output and the JSON lines log in a file:
+/// add_tee_output(DecoratorKind::JsonLines, || {
+///     Box::new(std::fs::OpenOptions::new().create(true).append(true).open("fcl.jsonl").unwrap())
+/// });
+/// ```
+pub fn add_tee_output(kind: DecoratorKind, make_writer: TeeWriterFactory) {
+    settings().tee_outputs.push((kind, make_writer));
+}
+
+/// Returns the additional log outputs (the decorator kind and writer factory of each one).
+pub fn tee_outputs() -> Vec<(DecoratorKind, TeeWriterFactory)> {
+    settings().tee_outputs.clone()
+}
+
+/// Sets the output of the static call graph written by the last dropped `DotGraphDecorator`,
+/// i.e. the writer created by `make_writer` (`None` for the writer of that decorator).
+/// #### Examples
+/// ```rs
+/// fcl::common::settings::set_dot_graph_output(Some(|| Box::new(std::fs::File::create("calls.dot").unwrap())));
+/// ```
+pub fn set_dot_graph_output(make_writer: Option<DotGraphWriterFactory>) {
+    settings().dot_graph_output = make_writer;
+}
+
+/// Returns the writer factory of the static call graph's output, if any.
+pub fn dot_graph_output() -> Option<DotGraphWriterFactory> {
+    settings().dot_graph_output
+}
+
+/// Sets the pattern of the per-thread log file names used if the "log_merger" feature is off
+/// (`"fcl_{name}_{id}.log"` by default), where
+/// * `{name}` is replaced with the thread name (`unnamed` for the threads without a name),
+/// * `{id}` is replaced with the number of the thread ID (e.g. `1` for `ThreadId(1)`).
+///
+/// If `None` then each thread logs to `stdout`.
+/// #### Examples
+/// ```rs
+/// fcl::common::settings::set_thread_log_file_pattern(Some(String::from("logs/{name}.log")));
+/// ```
+pub fn set_thread_log_file_pattern(pattern: Option<String>) {
+    settings().thread_log_file_pattern = pattern.map(Cow::Owned);
+}
+
+/// Returns the pattern of the per-thread log file names (`None` for logging to `stdout`).
+pub fn thread_log_file_pattern() -> Option<String> {
+    settings().thread_log_file_pattern.as_deref().map(String::from)
+}
+
+/// Sets the writer used by the FCL for logging (`FclWriter::Stdout` by default).
+///
+/// Unlike the `Settings`, the writer is kept by the calling thread (since it is not necessarily `Send`)
+/// and is taken upon the first logged call of the process, if that call is made by the same thread.
+/// I.e. the writer is to be set at the beginning of a non-loggable `main()`,
+/// and the first logged call is to be made by the `main()` thread
+/// (`fcl::Builder::writer()` has no such limitation). Takes precedence over the configured output.
+/// #### Examples
+/// ```rs
+/// use fcl::common::{call_log_infra::FclWriter, settings::set_fcl_writer};
+/// use fcl::common::rotating_file_writer::{FileRotation, RotatingFileWriter};
+/// set_fcl_writer(FclWriter::RotatingFile(RotatingFileWriter::new("fcl.log", FileRotation::default()).unwrap()));
+/// ```
+#[cfg(feature = "log_merger")]
+pub fn set_fcl_writer(fcl_writer: FclWriter) {
+    set_pending_fcl_writer(fcl_writer);
+}
+
+/// Sets the thread indent step - the string of white-spaces separating the logs of the different threads
+/// (`None` for the default one, 50 spaces).
+/// #### Examples
+/// ```rs
+/// fcl::common::settings::set_thread_indent_step(Some(" ".repeat(80)));
+/// ```
+pub fn set_thread_indent_step(thread_indent_step: Option<String>) {
+    settings().thread_indent_step = thread_indent_step;
+}
+
+/// Returns the thread indent step used by the `CALL_LOGGER_ARBITER` (`None` for the default one).
+pub fn thread_indent_step() -> Option<String> {
+    settings().thread_indent_step.clone()
+}
+
+/// Sets whether the `CALL_LOGGER_ARBITER` replaces the default panic hook with its own one (`true` by default)
+/// that flushes the FCL's cache (and dumps the ring buffer, if any) before the panic report.
+/// If `false` then the panic report can precede the latest part of the log, and the user's panic hook set
+/// before the first logged call is preserved.
+/// #### Examples
+/// ```rs
+/// fcl::common::settings::set_panic_sync_is_on(false);
+/// ```
+pub fn set_panic_sync_is_on(is_on: bool) {
+    settings().panic_sync_is_on = is_on;
+}
+
+/// Returns whether the `CALL_LOGGER_ARBITER` replaces the default panic hook with its own one.
+pub fn panic_sync_is_on() -> bool {
+    settings().panic_sync_is_on
+}
+
+/// Sets whether the `CALL_LOGGER_ARBITER` redirects the user's `stdout` and `stderr` output
+/// for synchronizing it with the log (`true` by default).
+/// If `false` then the user's output is not redirected and can appear out of order with the log.
+/// #### Examples
+/// ```rs
+/// fcl::common::settings::set_std_output_sync_is_on(false);
+/// ```
+#[cfg(feature = "std_output_sync")]
+pub fn set_std_output_sync_is_on(is_on: bool) {
+    settings().std_output_sync_is_on = is_on;
+}
+
+/// Returns whether the `CALL_LOGGER_ARBITER` redirects the user's standard output for synchronizing it with the log.
+#[cfg(feature = "std_output_sync")]
+pub fn std_output_sync_is_on() -> bool {
+    settings().std_output_sync_is_on
+}
@@ -0,0 +1,123 @@
+use std::{
+    cell::RefCell,
+    fs::File,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+#[cfg(not(feature = "log_merger"))]
+use crate::common::settings::thread_log_file_pattern;
+
+/// Returns the log file path for the `thread` according to the `pattern`
+/// (see `fcl::common::settings::set_thread_log_file_pattern()`).
+/// The characters of the thread name other than alphanumerics, `-`, `_`, and `.` are replaced with `_`.
+pub fn thread_log_file_path(pattern: &str, thread: &std::thread::Thread) -> PathBuf {
+    let name: String = thread
+        .name()
+        .unwrap_or("unnamed")
+        .chars()
+        .map(|character| {
+            if character.is_alphanumeric() || "-_.".contains(character) {
+                character
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let id = format!("{:?}", thread.id()) // "ThreadId(1)".
+        .trim_start_matches("ThreadId(")
+        .trim_end_matches(')') // "1".
+        .to_string();
+    PathBuf::from(pattern.replace("{name}", &name).replace("{id}", &id))
+}
+
+/// The writer to a thread's log file that can be closed explicitly
+/// (upon the thread's logger removal) while the decorator possessing the writer is still alive.
+///
+/// The clones share the same file. After the file has been closed the written data are discarded.
+#[derive(Clone)]
+pub struct ThreadLogFileWriter {
+    /// The log file, `None` after the file has been closed.
+    file: Rc<RefCell<Option<File>>>,
+}
+
+impl ThreadLogFileWriter {
+    /// Creates (or truncates) the log file at `path`.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self {
+            file: Rc::new(RefCell::new(Some(File::create(path)?))),
+        })
+    }
+    /// Closes the log file.
+    pub fn close(&self) {
+        self.file.borrow_mut().take();
+    }
+}
+
+impl Write for ThreadLogFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &mut *self.file.borrow_mut() {
+            Some(file) => file.write(buf),
+            None => Ok(buf.len()),
+        }
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut *self.file.borrow_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(not(feature = "log_merger"))]
+thread_local! {
+    /// The calling thread's log file (closed upon the thread's logger removal).
+    static THREAD_LOG_FILE: RefCell<Option<ThreadLogFileWriter>> = const { RefCell::new(None) };
+}
+
+/// Creates the calling thread's log file according to the pattern
+/// (see `fcl::common::settings::set_thread_log_file_pattern()`)
+/// and returns the writer to it.
+///
+/// Returns `None` (for logging to `stdout`) if the pattern is `None` or the file creation has failed
+/// (the failure is reported to `stderr`).
+#[cfg(not(feature = "log_merger"))]
+pub(crate) fn open_thread_log_file() -> Option<Box<dyn Write>> {
+    let pattern = thread_log_file_pattern()?;
+    let path = thread_log_file_path(&pattern, &std::thread::current());
+    match ThreadLogFileWriter::create(&path) {
+        Ok(writer) => {
+            let _ignore_tls_destruction =
+                THREAD_LOG_FILE.try_with(|thread_log_file| *thread_log_file.borrow_mut() = Some(writer.clone()));
+            Some(Box::new(writer))
+        }
+        Err(error) => {
+            eprintln!(
+                "Warning: Failed to create the FCL thread log file '{}', logging to `stdout`: '{}'",
+                path.display(),
+                error
+            );
+            None
+        }
+    }
+}
+
+/// Returns whether the calling thread logs to its own log file
+/// (in which case the thread indent that separates the threads' logs in the common log is not needed).
+#[cfg(not(feature = "log_merger"))]
+pub(crate) fn thread_has_log_file() -> bool {
+    THREAD_LOG_FILE
+        .try_with(|thread_log_file| thread_log_file.borrow().is_some())
+        .unwrap_or(false)
+}
+
+/// Closes the calling thread's log file, if any.
+#[cfg(not(feature = "log_merger"))]
+pub(crate) fn close_thread_log_file() {
+    let _ignore_tls_destruction = THREAD_LOG_FILE.try_with(|thread_log_file| {
+        if let Some(writer) = thread_log_file.borrow_mut().take() {
+            writer.close();
+        }
+    });
+}
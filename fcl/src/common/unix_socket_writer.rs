@@ -0,0 +1,228 @@
+use std::{
+    collections::VecDeque,
+    io::{self, Write},
+    os::unix::{
+        fs::{FileTypeExt, MetadataExt},
+        net::{UnixListener, UnixStream},
+    },
+    path::{Path, PathBuf},
+};
+
+use crate::common::defaults;
+
+
+/// The writer that streams the log over a Unix domain socket to a viewer (e.g. the `fcl-tail` binary of the workspace).
+///
+/// The writer listens on the socket and accepts a viewer at a line start
+/// (upon `write()` or `flush()`), such that the viewer never gets an incomplete first line.
+/// If the viewer disconnects (e.g. restarts) then the writer accepts the next one.
+/// While no viewer is connected the writer keeps the last `backlog_capacity` bytes of the log
+/// (the older bytes are dropped) and sends them to the next viewer connected.
+/// The `backlog_capacity` of `0` means dropping the log while no viewer is connected.
+///
+/// The writer never blocks on the viewer: the bytes the viewer doesn't take at once are kept in the backlog
+/// and sent upon the subsequent `write()` and `flush()`. The viewer falling behind by more than `backlog_capacity` bytes
+/// is considered stuck and disconnected (and the backlog is kept for the next viewer).
+///
+/// To be used as the FCL's log writer (see `fcl::common::settings::set_fcl_writer()`),
+/// in which case the user's standard output (synchronized with the log) is streamed to the same viewer.
+/// #### Examples
+/// ```rs
+/// use fcl::common::unix_socket_writer::UnixSocketWriter;
+/// let writer = UnixSocketWriter::new("/tmp/fcl.sock", 1024 * 1024)?; // Keep up to 1 MiB while no viewer.
+/// ```
+/// ```sh
+/// cargo run -p fcl_tail -- /tmp/fcl.sock
+/// ```
+pub struct UnixSocketWriter {
+    /// The path of the socket (removed upon drop).
+    path: PathBuf,
+    /// The device and inode numbers of the socket file created, such that upon drop the socket file is removed
+    /// only if it is still the one created by the writer (rather than, e.g., the one of the next writer).
+    socket_file_id: (u64, u64),
+    /// The listener accepting the viewers.
+    listener: UnixListener,
+    /// The connected viewer, if any (non-blocking).
+    viewer: Option<UnixStream>,
+    /// The log not sent to a viewer yet (the oldest first).
+    backlog: VecDeque<u8>,
+    /// The maximum number of bytes in the backlog.
+    backlog_capacity: usize,
+    /// Whether any bytes of the backlog have been dropped (i.e. the first backlog line can be incomplete).
+    backlog_overwritten: bool,
+    /// Whether the next byte starts a line (a viewer can be accepted).
+    at_line_start: bool,
+    /// Whether the bytes sent to the viewer end at a line end
+    /// (otherwise, upon the viewer disconnection, the first backlog line is incomplete).
+    sent_at_line_start: bool,
+}
+
+impl UnixSocketWriter {
+    /// Creates a new `UnixSocketWriter` listening on the socket at `path`
+    /// (the stale socket file, if any, is removed) and keeping up to `backlog_capacity` bytes while no viewer is connected.
+    ///
+    /// Fails with `io::ErrorKind::AlreadyExists` if something other than a socket exists at `path`.
+    pub fn new(path: impl AsRef<Path>, backlog_capacity: usize) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        match std::fs::symlink_metadata(&path) {
+            Ok(metadata) if metadata.file_type().is_socket() => std::fs::remove_file(&path)?,
+            Ok(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("{} exists and is not a socket", path.display()),
+                ));
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+            Err(error) => return Err(error),
+        }
+        let listener = UnixListener::bind(&path)?;
+        listener.set_nonblocking(true)?;
+        let metadata = std::fs::symlink_metadata(&path)?;
+        Ok(Self {
+            path,
+            socket_file_id: (metadata.dev(), metadata.ino()),
+            listener,
+            viewer: None,
+            backlog: VecDeque::new(),
+            backlog_capacity,
+            backlog_overwritten: false,
+            at_line_start: true,
+            sent_at_line_start: true,
+        })
+    }
+    /// Returns the path of the socket.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    /// Accepts a pending viewer, if any, and sends it the backlog.
+    fn accept_viewer(&mut self) {
+        let Ok((viewer, _address)) = self.listener.accept() else {
+            return;
+        };
+        if viewer.set_nonblocking(true).is_err() {
+            return;
+        }
+        if self.backlog_overwritten {
+            // Skip the incomplete first line:
+            let first_line_len = self
+                .backlog
+                .iter()
+                .position(|byte| *byte == b'\n')
+                .map_or(self.backlog.len(), |position| position + 1);
+            self.backlog.drain(..first_line_len);
+            self.backlog_overwritten = false;
+        }
+        self.viewer = Some(viewer);
+        self.sent_at_line_start = true;
+        self.send_backlog();
+    }
+    /// Sends to the viewer, if connected, as much of the backlog as it takes without blocking.
+    fn send_backlog(&mut self) {
+        let Some(viewer) = &mut self.viewer else {
+            return;
+        };
+        let (sent, is_connected) = send(viewer, self.backlog.make_contiguous());
+        let last_sent_byte = sent.checked_sub(1).map(|index| self.backlog[index]);
+        self.backlog.drain(..sent);
+        self.settle_send(last_sent_byte, is_connected);
+    }
+    /// Records the result of a send to the viewer.
+    fn settle_send(&mut self, last_sent_byte: Option<u8>, is_connected: bool) {
+        if let Some(byte) = last_sent_byte {
+            self.sent_at_line_start = byte == b'\n';
+        }
+        if !is_connected {
+            self.disconnect_viewer();
+        }
+    }
+    /// Drops the viewer (that has disconnected or is stuck), the backlog is kept for the next viewer.
+    fn disconnect_viewer(&mut self) {
+        self.viewer = None;
+        if !self.sent_at_line_start {
+            // The backlog starts in the middle of a line (the line start has been sent to the viewer).
+            self.backlog_overwritten = true;
+        }
+    }
+    /// Appends the bytes to the backlog dropping the oldest ones beyond the capacity
+    /// (after disconnecting the viewer, if any, that hasn't taken them).
+    fn add_to_backlog(&mut self, buf: &[u8]) {
+        let tail = &buf[buf.len().saturating_sub(self.backlog_capacity)..];
+        let excess = (self.backlog.len() + tail.len()).saturating_sub(self.backlog_capacity);
+        if excess != 0 || tail.len() != buf.len() {
+            if self.viewer.is_some() {
+                self.disconnect_viewer();
+            }
+            self.backlog.drain(..excess);
+            self.backlog_overwritten = true;
+        }
+        self.backlog.extend(tail);
+    }
+}
+
+/// Sends the bytes to the non-blocking viewer until it would block.
+/// Returns the number of bytes sent and whether the viewer is still connected.
+fn send(viewer: &mut UnixStream, buf: &[u8]) -> (usize, bool) {
+    let mut sent = 0;
+    while sent < buf.len() {
+        match viewer.write(&buf[sent..]) {
+            Ok(0) => return (sent, false),
+            Ok(count) => sent += count,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => break,
+            Err(_) => return (sent, false),
+        }
+    }
+    (sent, true)
+}
+
+impl Write for UnixSocketWriter {
+    /// Sends the bytes to the viewer, if connected, and adds the bytes not sent to the backlog.
+    /// Never fails or blocks, such that the logging goes on regardless of the viewer.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.viewer.is_none() && self.at_line_start {
+            self.accept_viewer();
+        }
+        self.send_backlog();
+        let mut sent = 0;
+        if self.backlog.is_empty()
+            && let Some(viewer) = &mut self.viewer
+        {
+            let is_connected;
+            (sent, is_connected) = send(viewer, buf);
+            self.settle_send(sent.checked_sub(1).map(|index| buf[index]), is_connected);
+        }
+        self.add_to_backlog(&buf[sent..]);
+        self.at_line_start = buf[buf.len() - 1] == b'\n';
+        Ok(buf.len())
+    }
+    /// Accepts a pending viewer, if any, and sends it as much of the backlog as it takes without blocking.
+    fn flush(&mut self) -> io::Result<()> {
+        if self.viewer.is_none() && self.at_line_start {
+            self.accept_viewer();
+        }
+        self.send_backlog();
+        Ok(())
+    }
+}
+
+impl Drop for UnixSocketWriter {
+    /// Sends the rest of the backlog to the viewer, if connected (waiting for it up to `VIEWER_WRITE_TIMEOUT`),
+    /// and removes the socket file, if it is still the one created by the writer.
+    fn drop(&mut self) {
+        if let Some(viewer) = &mut self.viewer
+            && viewer.set_nonblocking(false).is_ok()
+            && viewer.set_write_timeout(Some(defaults::VIEWER_WRITE_TIMEOUT)).is_ok()
+        {
+            let _ignore_error = viewer.write_all(self.backlog.make_contiguous());
+        }
+        if let Ok(metadata) = std::fs::symlink_metadata(&self.path)
+            && metadata.file_type().is_socket()
+            && (metadata.dev(), metadata.ino()) == self.socket_file_id
+        {
+            let _ignore_error = std::fs::remove_file(&self.path);
+        }
+    }
+}
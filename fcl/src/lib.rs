@@ -11,6 +11,30 @@ macro_rules! set_thread_indent {
     ($expr:expr) => {};
 }
 
+#[cfg(not(feature = "common"))]
+#[macro_export]
+macro_rules! set_node_budget {
+    ($expr:expr) => {};
+}
+
+#[cfg(not(feature = "common"))]
+#[macro_export]
+macro_rules! set_max_sequence_len {
+    ($expr:expr) => {};
+}
+
+#[cfg(not(feature = "common"))]
+#[macro_export]
+macro_rules! set_recursion_threshold {
+    ($expr:expr) => {};
+}
+
+#[cfg(not(feature = "call_timing"))]
+#[macro_export]
+macro_rules! set_call_timing {
+    ($expr:expr) => {};
+}
+
 #[cfg(not(feature = "common"))]
 #[macro_export]
 macro_rules! push_logging_is_on {
@@ -37,3 +61,26 @@ macro_rules! set_logging_is_on {
 
 #[cfg(feature = "common")]
 pub mod common;
+
+#[cfg(feature = "common")]
+pub use common::builder::Builder;
+
+/// Flushes the FCL's cache and dumps the content of the FCL's ring buffer writer (the last logged calls), if any,
+/// to the file specified upon the ring buffer writer creation or to `stderr`.
+/// Does nothing if the FCL does not log to a ring buffer writer.
+/// #### Examples
+/// ```rs
+/// use fcl::common::{call_log_infra::FclWriter, settings::set_fcl_writer};
+/// use fcl::common::ring_buffer_writer::RingBufferWriter;
+/// set_fcl_writer(FclWriter::RingBuffer(RingBufferWriter::new(4 * 1024 * 1024, None))); // At the beginning of a non-loggable `main()`.
+/// // . . .
+/// fcl::dump_ring(); // E.g. upon a failure detected.
+/// ```
+pub fn dump_ring() {
+    #[cfg(feature = "common")]
+    common::call_log_infra::instances::THREAD_LOGGER.with(|logger| {
+        #[cfg(feature = "single_threaded")]
+        use common::CallLogger;
+        extra_borrow_mut!(logger).borrow_mut().dump_ring()
+    })
+}
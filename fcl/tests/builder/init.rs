@@ -0,0 +1,32 @@
+use fcl::Builder;
+use fcl::common::decorators::DecoratorKind;
+use fcl::common::settings::{decorator_kind, indent_step};
+
+// High-level logic to test:
+// The `fcl::Builder` settings are applied only before the first logged call (in any thread),
+// afterwards `init()` fails and applies nothing.
+// The successful `init()` is not tested here since the tests share the process
+// and make the logged calls in parallel.
+//
+// Test cases:
+//
+// A: `init_after_first_logged_call`:
+// The thread's decorator is created (as upon the first logged call), then the builder with the settings is initialized.
+// Assert: `init()` fails, the settings are not changed.
+
+#[test]
+fn init_after_first_logged_call() {
+    // Create the logging infrastructure (as upon the first logged call):
+    fcl::common::call_log_infra::instances::THREAD_DECORATOR.with(|_decorator| {});
+    assert!(fcl::common::builder::infra_is_created());
+
+    let result = Builder::new()
+        .decorator_kind(DecoratorKind::ChromeTrace)
+        .indent_step("\t")
+        .panic_sync(false)
+        .init();
+    assert!(result.is_err());
+    assert_eq!(decorator_kind(), DecoratorKind::CodeLike);
+    assert_eq!(indent_step(), None);
+    assert!(fcl::common::settings::panic_sync_is_on());
+}
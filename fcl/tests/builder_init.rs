@@ -0,0 +1,85 @@
+// The separate test binary (process) for the successful `fcl::Builder::init()`,
+// that must precede the first logged call of the process
+// (the tests of `tests/mod.rs` share the process and make the logged calls in parallel).
+#![cfg(feature = "log_merger")]
+
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+};
+
+use fcl::Builder;
+use fcl::common::call_log_infra::{FclWriter, instances::THREAD_LOGGER};
+use fcl::common::decorators::DecoratorKind;
+use fcl_proc_macros::loggable;
+
+// High-level logic to test:
+// The `fcl::Builder` settings applied before the first logged call configure the logging infrastructure,
+// in particular the writer shared by all the threads and every thread's decorator.
+//
+// Test cases:
+//
+// A: `init_before_first_logged_call`:
+// The builder with the writer and the decorator kind is initialized, then the logged calls are made
+// by the main thread and a spawned thread.
+// Assert: `init()` succeeds, the calls of both threads are logged by the `TreeLikeDecorator` to the writer.
+
+/// The writer to the log shared by the test and the FCL.
+#[derive(Clone, Default)]
+struct SharedLog(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedLog {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Flushes the calling thread's log (to log the cached calls).
+fn flush_log() {
+    THREAD_LOGGER.with(|logger| {
+        #[cfg(feature = "single_threaded")]
+        let logger = logger.borrow_mut();
+
+        logger.borrow_mut().flush();
+    });
+}
+
+#[test]
+fn init_before_first_logged_call() {
+    #[loggable]
+    fn f() {
+        g();
+    }
+    #[loggable]
+    fn g() {}
+
+    let log = SharedLog::default();
+    let result = Builder::new()
+        .writer(FclWriter::Other(Box::new(log.clone())))
+        .decorator_kind(DecoratorKind::TreeLike)
+        .thread_indent_step(String::new())
+        .init();
+    assert!(result.is_ok());
+
+    f();
+    flush_log();
+    std::thread::spawn(|| {
+        g();
+        flush_log();
+    })
+    .join()
+    .unwrap();
+
+    let log = String::from_utf8(log.0.lock().unwrap().clone()).unwrap();
+    #[rustfmt::skip]
+    assert_eq!(log, concat!(
+        "+-f()\n",
+        "| +-g()\n",
+        "| `-return\n",
+        "+-g()\n",
+    ));
+}
@@ -0,0 +1,134 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use fcl_proc_macros::loggable;
+
+use fcl::common::call_log_infra::instances::THREAD_DECORATOR;
+
+use crate::common::*;
+
+// High-level logic to test:
+// With the "call_timing" feature and the call timing on (it is off by default) the returns and loop body ends are logged with the wall-clock duration,
+// and the repeat counts are logged with the duration statistics of the folded repeats
+// (e.g. `// g() repeats 2 time(s), total 3.1ms, min 1.0ms, avg 1.5ms, max 2.1ms.`).
+// The durations are not deterministic, the tests check the log structure.
+// With the call timing off the log is the same as without the "call_timing" feature.
+//
+// Test cases:
+//
+// A: `repeated_call_timing`:
+// f() {
+//   g() {} // 1.1ms.
+//   // g() repeats 2 time(s), total 2.2ms, min 1.1ms, avg 1.1ms, max 1.1ms.  // Assert: The statistics are logged.
+// } // f(), 3.3ms.                                                           // Assert: The duration is logged.
+//
+// B: `repeated_loopbody_timing`:
+// { // Loop body start.
+//   g() {} // 1.1ms.
+// } // Loop body end, 1.1ms.
+// // Loop body repeats 1 time(s), total 1.1ms, min 1.1ms, avg 1.1ms, max 1.1ms.
+//
+// C: `call_timing_is_off_by_default`:
+// f() {
+//   g() {}                       // Assert: No durations are logged.
+//   // g() repeats 2 time(s).
+// } // f().
+
+#[loggable]
+fn g() {
+    std::thread::sleep(Duration::from_millis(1));
+}
+
+/// Returns the log lines.
+fn log_lines(log: &Rc<RefCell<Vec<u8>>>) -> Vec<String> {
+    String::from_utf8_lossy(&log.borrow())
+        .lines()
+        .map(String::from)
+        .collect()
+}
+
+/// Asserts that the line has the expected beginning and ends with a duration, e.g. `1.1ms.`.
+fn assert_ends_with_duration(line: &str, beginning: &str) {
+    assert!(line.starts_with(beginning), "{:?} does not start with {:?}", line, beginning);
+    assert!(line.ends_with("s."), "{:?} does not end with a duration", line);
+}
+
+#[test]
+fn repeated_call_timing() {
+    #[loggable]
+    fn f() {
+        g();
+        g();
+        g();
+    }
+
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    THREAD_DECORATOR.with(|decorator| decorator.borrow_mut().set_writer(log.clone()));
+
+    fcl::set_call_timing!(true);
+    f();
+    flush_log();
+
+    let lines = log_lines(&log);
+    assert_eq!(lines.len(), 4, "{:?}", lines);
+    assert_eq!(lines[0], "f() {");
+    assert_ends_with_duration(&lines[1], "  g() {} // ");
+    assert_ends_with_duration(&lines[2], "  // g() repeats 2 time(s), total ");
+    for statistic in [", min ", ", avg ", ", max "] {
+        assert!(lines[2].contains(statistic), "{:?} lacks {:?}", lines[2], statistic);
+    }
+    assert_ends_with_duration(&lines[3], "} // f(), ");
+}
+
+#[test]
+fn repeated_loopbody_timing() {
+    #[loggable]
+    fn f() {
+        for _ in 0..2 {
+            g();
+        }
+    }
+
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    THREAD_DECORATOR.with(|decorator| decorator.borrow_mut().set_writer(log.clone()));
+
+    fcl::set_call_timing!(true);
+    f();
+    flush_log();
+
+    let lines = log_lines(&log);
+    assert_eq!(lines.len(), 6, "{:?}", lines);
+    assert_eq!(lines[1], "  { // Loop body start.");
+    assert_ends_with_duration(&lines[2], "    g() {} // ");
+    assert_ends_with_duration(&lines[3], "  } // Loop body end, ");
+    assert_ends_with_duration(&lines[4], "  // Loop body repeats 1 time(s), total ");
+    assert_ends_with_duration(&lines[5], "} // f(), ");
+}
+
+#[test]
+fn call_timing_is_off_by_default() {
+    #[loggable]
+    fn f() {
+        g();
+        g();
+        g();
+    }
+
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    THREAD_DECORATOR.with(|decorator| decorator.borrow_mut().set_writer(log.clone()));
+
+    f();
+    flush_log();
+
+    #[rustfmt::skip]
+    test_assert!(log, concat!(
+        "f() {\n",
+        "  g() {}\n",
+        "  // g() repeats 2 time(s).\n",
+        "} // f().\n",
+    ));
+}
@@ -0,0 +1,179 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use code_commons::CallGraph;
+use fcl_proc_macros::loggable;
+
+use fcl::common::call_log_infra::instances::THREAD_DECORATOR;
+use fcl::common::decorators::{CodeLikeDecorator, WriterPossessor};
+
+use crate::common::*;
+
+// High-level logic to test:
+// The long-living function (`daemon()`) is not logged (logging is off by the moment of its call),
+// its nested calls and loop bodies are logged as the pseudoroot's children.
+// The pseudoroot's children that can no longer participate in the subtree comparison are removed,
+// but those that still can participate are retained.
+//
+// Test cases:
+//
+// A: `childless_iterations_between_repeated_calls`:
+// init() {}
+// { // Childless loop bodies. Removed.
+// }
+// init() {}  // Assert: Is compared to the previous `init()` (is not removed by the loop bodies),
+//            // increments its repeat count.
+//
+// B: `logged_iterations_between_repeated_calls`:
+// init() {}
+// { // Loop body start. // Upon the first nested call the previous `init()` is removed.
+//     work() {}
+// } // Loop body end.
+// // Loop body repeats 2 time(s).
+// init() {}  // Assert: Is logged in full.
+//
+// C: `node_count_stays_bounded`:
+// f0() { g() {} }  // 1000 pseudoroot's children (the names alternate between 7 ones, nothing is folded).
+// f1() { g() {} }
+// . . .
+// { // Loop body start.  // 1000 loop bodies.
+//   work() {}
+// } // Loop body end.
+// // Loop body repeats 999 time(s).
+// Assert: After each iteration the pseudoroot retains at most 2 children (of at most 2 nodes each).
+//
+// D: `node_count_stays_bounded_while_sequence_is_cached`:
+// f0() {}  // 100 pseudoroot's children (the names alternate between 7 ones, longer than the maximum sequence).
+// . . .
+// a() {}
+// b() {}
+// // (a(), b()) repeats 999 time(s).
+// Assert: After each iteration the pseudoroot retains at most 8 children
+// (up to 4 preceding the model sequence, the model sequence, and the repeat being cached).
+//
+// The call graph is driven directly (rather than by the `#[loggable]` functions) to query the counts.
+
+#[loggable]
+fn init() {}
+#[loggable]
+fn work() {}
+
+/// The long-living function that is called with logging off,
+/// but logs its nested calls and loop bodies (as the pseudoroot's children).
+#[loggable]
+fn daemon(do_work: bool) {
+    fcl::push_logging_is_on!(true);
+
+    init();
+    for _ in 0..3 {
+        if do_work {
+            work();
+        }
+    }
+    init();
+
+    fcl::pop_logging_is_on!();
+}
+
+#[test]
+fn childless_iterations_between_repeated_calls() {
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    THREAD_DECORATOR.with(|decorator| decorator.borrow_mut().set_writer(log.clone()));
+
+    fcl::push_logging_is_on!(false); // Do not log the `daemon()` itself.
+    daemon(false);
+    fcl::pop_logging_is_on!();
+    flush_log();
+
+    #[rustfmt::skip]
+    test_assert!(log, concat!(
+        "init() {}\n",
+        // The childless loop bodies are removed.
+        "// init() repeats 1 time(s).\n", // The repeated `init()` is compared to the previous one.
+    ));
+}
+
+#[test]
+fn logged_iterations_between_repeated_calls() {
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    THREAD_DECORATOR.with(|decorator| decorator.borrow_mut().set_writer(log.clone()));
+
+    fcl::push_logging_is_on!(false); // Do not log the `daemon()` itself.
+    daemon(true);
+    fcl::pop_logging_is_on!();
+    flush_log();
+
+    #[rustfmt::skip]
+    test_assert!(log, concat!(
+        "init() {}\n",
+        "{ // Loop body start.\n",
+        "  work() {}\n",
+        "} // Loop body end.\n",
+        "// Loop body repeats 2 time(s).\n",
+        "init() {}\n", // The previous `init()` has been removed. The repeated `init()` is logged in full.
+    ));
+}
+
+#[test]
+fn node_count_stays_bounded() {
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(64 * 1024)));
+    let decorator = Rc::new(RefCell::new(CodeLikeDecorator::new(None, None)));
+    decorator.borrow_mut().set_writer(log.clone());
+    let mut call_graph = CallGraph::new(decorator);
+    call_graph.set_max_sequence_len(1); // No periodic sequence folding (retaining more of the latest children).
+
+    for iteration in 0..1000 {
+        call_graph.add_call(&format!("f{}", iteration % 7), None);
+        call_graph.add_call("g", None);
+        call_graph.add_ret(None);
+        call_graph.add_ret(None);
+
+        assert!(call_graph.pseudoroot_child_count() <= 2, "Iteration {}", iteration);
+        assert!(call_graph.node_count() <= 4, "Iteration {}", iteration);
+    }
+    for iteration in 0..1000 {
+        call_graph.add_loopbody_start();
+        call_graph.add_call("work", None);
+        call_graph.add_ret(None);
+        call_graph.add_loopbody_end();
+
+        assert!(call_graph.pseudoroot_child_count() <= 2, "Iteration {}", iteration);
+        assert!(call_graph.node_count() <= 4, "Iteration {}", iteration);
+    }
+    call_graph.add_loop_end();
+    call_graph.flush(true);
+
+    let log = String::from_utf8(log.borrow().clone()).unwrap();
+    assert!(log.ends_with("// Loop body repeats 999 time(s).\n"), "{}", log);
+}
+
+#[test]
+fn node_count_stays_bounded_while_sequence_is_cached() {
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(64 * 1024)));
+    let decorator = Rc::new(RefCell::new(CodeLikeDecorator::new(None, None)));
+    decorator.borrow_mut().set_writer(log.clone());
+    let mut call_graph = CallGraph::new(decorator);
+    call_graph.set_max_sequence_len(4);
+
+    for iteration in 0..100 {
+        call_graph.add_call(&format!("f{}", iteration % 7), None);
+        call_graph.add_ret(None);
+    }
+    for iteration in 0..1000 {
+        for name in ["a", "b"] {
+            call_graph.add_call(name, None);
+            call_graph.add_ret(None);
+        }
+
+        assert!(call_graph.pseudoroot_child_count() <= 8, "Iteration {}", iteration);
+        assert!(call_graph.node_count() <= 8, "Iteration {}", iteration);
+    }
+    call_graph.flush(true);
+
+    let log = String::from_utf8(log.borrow().clone()).unwrap();
+    assert!(log.ends_with("a() {}\nb() {}\n// (a(), b()) repeats 999 time(s).\n"), "{}", log);
+}
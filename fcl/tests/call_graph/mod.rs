@@ -3,4 +3,10 @@ mod add_loop_end;
 mod add_loopbody_end;
 mod add_loopbody_start;
 mod add_ret;
-mod basics;
\ No newline at end of file
+mod basics;
+mod endless_logging;
+mod node_budget;
+mod sequence_folding;
+mod recursion_folding;
+#[cfg(feature = "call_timing")]
+mod call_timing;
\ No newline at end of file
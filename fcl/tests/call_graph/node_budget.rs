@@ -0,0 +1,129 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use fcl_proc_macros::loggable;
+
+use fcl::common::call_log_infra::instances::THREAD_DECORATOR;
+
+use crate::common::*;
+
+// High-level logic to test:
+// Upon reaching the node budget the call graph flushes what it has cached, logs the
+// `// FCL: repeat folding suspended (node budget reached)` note, and degrades to plain streaming
+// (the repeated calls and loop bodies are logged in full instead of being folded).
+//
+// Test cases:
+//
+// A: `budget_is_not_reached`:
+// work() {
+//   { // Loop body start.
+//     f() {}
+//     g() {}
+//   } // Loop body end.
+//   // Loop body repeats 2 time(s).   // Assert: The repeats are folded.
+// } // work().
+//
+// B: `budget_is_reached_in_long_running_function`:
+// work() {                 // Node 1.
+//   { // Loop body start.  // Node 2.
+//     f() {}               // Node 3.
+//     // FCL: repeat folding suspended (node budget reached)   // Assert: Is logged upon the 4th node.
+//     g() {}
+//   } // Loop body end.
+//   { // Loop body start.  // Assert: The repeats are logged in full.
+//     f() {}
+//     g() {}
+//   } // Loop body end.
+//   ...
+// } // work().
+//
+// C: `repeated_calls_after_budget_is_reached`:
+// f() {}
+// // FCL: repeat folding suspended (node budget reached)
+// f() {}   // Assert: Is logged in full (instead of `// f() repeats 2 time(s).`).
+// f() {}
+
+#[loggable]
+fn f() {}
+#[loggable]
+fn g() {}
+#[loggable]
+fn work() {
+    for _ in 0..3 {
+        f();
+        g();
+    }
+}
+
+#[test]
+fn budget_is_not_reached() {
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    THREAD_DECORATOR.with(|decorator| decorator.borrow_mut().set_writer(log.clone()));
+
+    fcl::set_node_budget!(Some(100));
+    work();
+    flush_log();
+
+    #[rustfmt::skip]
+    test_assert!(log, concat!(
+        "work() {\n",
+        "  { // Loop body start.\n",
+        "    f() {}\n",
+        "    g() {}\n",
+        "  } // Loop body end.\n",
+        "  // Loop body repeats 2 time(s).\n",
+        "} // work().\n",
+    ));
+}
+
+#[test]
+fn budget_is_reached_in_long_running_function() {
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    THREAD_DECORATOR.with(|decorator| decorator.borrow_mut().set_writer(log.clone()));
+
+    fcl::set_node_budget!(Some(3));
+    work();
+    flush_log();
+
+    #[rustfmt::skip]
+    test_assert!(log, concat!(
+        "work() {\n",
+        "  { // Loop body start.\n",
+        "    f() {}\n",
+        "    // FCL: repeat folding suspended (node budget reached)\n",
+        "    g() {}\n",
+        "  } // Loop body end.\n",
+        "  { // Loop body start.\n",
+        "    f() {}\n",
+        "    g() {}\n",
+        "  } // Loop body end.\n",
+        "  { // Loop body start.\n",
+        "    f() {}\n",
+        "    g() {}\n",
+        "  } // Loop body end.\n",
+        "} // work().\n",
+    ));
+}
+
+#[test]
+fn repeated_calls_after_budget_is_reached() {
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    THREAD_DECORATOR.with(|decorator| decorator.borrow_mut().set_writer(log.clone()));
+
+    fcl::set_node_budget!(Some(1));
+    f();
+    f();
+    f();
+    flush_log();
+
+    #[rustfmt::skip]
+    test_assert!(log, concat!(
+        "f() {}\n",
+        "// FCL: repeat folding suspended (node budget reached)\n",
+        "f() {}\n",
+        "f() {}\n",
+    ));
+}
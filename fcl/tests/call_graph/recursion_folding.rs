@@ -0,0 +1,167 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use fcl_proc_macros::loggable;
+
+use fcl::common::call_log_infra::instances::THREAD_DECORATOR;
+
+use crate::common::*;
+
+// High-level logic to test:
+// If a function calls itself (directly) deeper than the configurable recursion threshold,
+// the deeper calls are recursion-folded, i.e. logged at the indent of their parent
+// along with their recursion depth (e.g. `down() recursion depth 3 {`),
+// such that the deep recursion doesn't make the indent grow further.
+// Of a chain of the recursion-folded calls only the innermost frame is logged, the middle frames are hidden.
+// The nested calls of a recursion-folded call are indented relative to it.
+//
+// Test cases:
+//
+// A: `deep_recursion_is_folded`:
+// down(n: 3) {
+//   down(n: 2) {
+//   down(n: 0) recursion depth 4 {   // Assert: Beyond the threshold the indent doesn't grow,
+//                                    // the middle frame `down(n: 1)` is hidden.
+//     leaf() {}                      // Assert: The nested calls are indented relative to the folded call.
+//   } // down().
+//   } // down().
+// } // down().
+//
+// B: `repeated_folded_calls`:
+// twice(n: 2) {
+// twice(n: 0) recursion depth 3 {}  // Assert: The middle frame `twice(n: 1)` is hidden.
+// // twice() repeats 1 time(s).     // Assert: The repeat count is logged at the folded call's indent.
+// // twice() repeats 1 time(s).     // Assert: The hidden frame's repeat count is still logged.
+// } // twice().
+//
+// C: `hidden_frame_with_later_call`:
+// after(n: 2) {
+// after(n: 0) recursion depth 3 {}  // Assert: The middle frame `after(n: 1)` is hidden
+//                                   // while its nested items are the recursion-folded calls.
+// after(n: 1) recursion depth 2 {   // Assert: The middle frame is logged once it makes a different call,
+//   leaf() {}                       // such that the call is logged with its parent.
+// } // after().                     // Assert: The logged middle frame's return is logged.
+//   leaf() {}
+// } // after().
+//
+// D: `recursion_folding_is_disabled`:
+// down(n: 2) {
+//   down(n: 1) {
+//     down(n: 0) {                   // Assert: The indent grows with the recursion depth.
+//       leaf() {}
+//     } // down().
+//   } // down().
+// } // down().
+
+#[loggable]
+fn leaf() {}
+#[loggable]
+fn down(n: u8) {
+    if n > 0 {
+        down(n - 1);
+    } else {
+        leaf();
+    }
+}
+
+#[test]
+fn deep_recursion_is_folded() {
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    THREAD_DECORATOR.with(|decorator| decorator.borrow_mut().set_writer(log.clone()));
+
+    fcl::set_recursion_threshold!(Some(2));
+    down(3);
+    flush_log();
+
+    #[rustfmt::skip]
+    test_assert!(log, concat!(
+        "down(n: 3) {\n",
+        "  down(n: 2) {\n",
+        "  down(n: 0) recursion depth 4 {\n",
+        "    leaf() {}\n",
+        "  } // down().\n",
+        "  } // down().\n",
+        "} // down().\n",
+    ));
+}
+
+#[test]
+fn repeated_folded_calls() {
+    #[loggable]
+    fn twice(n: u8) {
+        if n > 0 {
+            twice(n - 1);
+            twice(n - 1);
+        }
+    }
+
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    THREAD_DECORATOR.with(|decorator| decorator.borrow_mut().set_writer(log.clone()));
+
+    fcl::set_recursion_threshold!(Some(1));
+    twice(2);
+    flush_log();
+
+    #[rustfmt::skip]
+    test_assert!(log, concat!(
+        "twice(n: 2) {\n",
+        "twice(n: 0) recursion depth 3 {}\n",
+        "// twice() repeats 1 time(s).\n",
+        "// twice() repeats 1 time(s).\n",
+        "} // twice().\n",
+    ));
+}
+
+#[test]
+fn hidden_frame_with_later_call() {
+    #[loggable]
+    fn after(n: u8) {
+        if n > 0 {
+            after(n - 1);
+            leaf();
+        }
+    }
+
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    THREAD_DECORATOR.with(|decorator| decorator.borrow_mut().set_writer(log.clone()));
+
+    fcl::set_recursion_threshold!(Some(1));
+    after(2);
+    flush_log();
+
+    #[rustfmt::skip]
+    test_assert!(log, concat!(
+        "after(n: 2) {\n",
+        "after(n: 0) recursion depth 3 {}\n",
+        "after(n: 1) recursion depth 2 {\n",
+        "  leaf() {}\n",
+        "} // after().\n",
+        "  leaf() {}\n",
+        "} // after().\n",
+    ));
+}
+
+#[test]
+fn recursion_folding_is_disabled() {
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    THREAD_DECORATOR.with(|decorator| decorator.borrow_mut().set_writer(log.clone()));
+
+    fcl::set_recursion_threshold!(None);
+    down(2);
+    flush_log();
+
+    #[rustfmt::skip]
+    test_assert!(log, concat!(
+        "down(n: 2) {\n",
+        "  down(n: 1) {\n",
+        "    down(n: 0) {\n",
+        "      leaf() {}\n",
+        "    } // down().\n",
+        "  } // down().\n",
+        "} // down().\n",
+    ));
+}
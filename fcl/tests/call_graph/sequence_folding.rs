@@ -0,0 +1,222 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use fcl_proc_macros::loggable;
+
+use fcl::common::call_log_infra::instances::THREAD_DECORATOR;
+
+use crate::common::*;
+
+// High-level logic to test:
+// If a sequence of siblings (of length 2 up to the configurable maximum) starts repeating,
+// the first occurrence of the sequence is logged, and the subsequent occurrences are cached
+// and folded into the sequence's repeat count (e.g. `// (a(), b()) repeats 2 time(s).`).
+// If the repeat breaks, the cached siblings are logged in full.
+// The periodic sequence folding is off by default (enabled with `fcl::set_max_sequence_len!(4)`).
+//
+// Test cases:
+//
+// A: `repeated_call_sequence`:
+// f() {
+//   a() {}
+//   b() {}
+//   // (a(), b()) repeats 2 time(s).    // Assert: The repeats are folded.
+//   c() {}
+// } // f().
+//
+// B: `repeated_call_sequence_with_repeated_calls`:
+// f() {
+//   a() {}
+//   // a() repeats 1 time(s).
+//   b() {}
+//   // (a(), b()) repeats 1 time(s).    // Assert: The repeat counts of the siblings are also compared.
+// } // f().
+//
+// C: `broken_call_sequence`:
+// f() {
+//   a() {}
+//   b() {}
+//   a() {}  // Assert: The cached sibling is logged in full upon `c()`.
+//   c() {}
+// } // f().
+//
+// D: `repeated_loopbody_sequence`:
+// f() {
+//   { // Loop body start.
+//     a() {}
+//   } // Loop body end.
+//   { // Loop body start.
+//     b() {}
+//   } // Loop body end.
+//   // (Loop body, Loop body) repeats 1 time(s).   // Assert: The alternating iterations are folded.
+// } // f().
+//
+// E: `sequence_folding_is_off_by_default`:
+// f() {
+//   a() {}
+//   b() {}
+//   a() {}  // Assert: The repeats are logged in full.
+//   b() {}
+// } // f().
+
+#[loggable]
+fn a() {}
+#[loggable]
+fn b() {}
+#[loggable]
+fn c() {}
+
+#[test]
+fn repeated_call_sequence() {
+    #[loggable]
+    fn f() {
+        a();
+        b();
+        a();
+        b();
+        a();
+        b();
+        c();
+    }
+
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    THREAD_DECORATOR.with(|decorator| decorator.borrow_mut().set_writer(log.clone()));
+
+    fcl::set_max_sequence_len!(4);
+    f();
+    flush_log();
+
+    #[rustfmt::skip]
+    test_assert!(log, concat!(
+        "f() {\n",
+        "  a() {}\n",
+        "  b() {}\n",
+        "  // (a(), b()) repeats 2 time(s).\n",
+        "  c() {}\n",
+        "} // f().\n",
+    ));
+}
+
+#[test]
+fn repeated_call_sequence_with_repeated_calls() {
+    #[loggable]
+    fn f() {
+        a();
+        a();
+        b();
+        a();
+        a();
+        b();
+    }
+
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    THREAD_DECORATOR.with(|decorator| decorator.borrow_mut().set_writer(log.clone()));
+
+    fcl::set_max_sequence_len!(4);
+    f();
+    flush_log();
+
+    #[rustfmt::skip]
+    test_assert!(log, concat!(
+        "f() {\n",
+        "  a() {}\n",
+        "  // a() repeats 1 time(s).\n",
+        "  b() {}\n",
+        "  // (a(), b()) repeats 1 time(s).\n",
+        "} // f().\n",
+    ));
+}
+
+#[test]
+fn broken_call_sequence() {
+    #[loggable]
+    fn f() {
+        a();
+        b();
+        a();
+        c();
+    }
+
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    THREAD_DECORATOR.with(|decorator| decorator.borrow_mut().set_writer(log.clone()));
+
+    fcl::set_max_sequence_len!(4);
+    f();
+    flush_log();
+
+    #[rustfmt::skip]
+    test_assert!(log, concat!(
+        "f() {\n",
+        "  a() {}\n",
+        "  b() {}\n",
+        "  a() {}\n",
+        "  c() {}\n",
+        "} // f().\n",
+    ));
+}
+
+#[test]
+fn repeated_loopbody_sequence() {
+    #[loggable]
+    fn f() {
+        for i in 0..4 {
+            if i % 2 == 0 {
+                a();
+            } else {
+                b();
+            }
+        }
+    }
+
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    THREAD_DECORATOR.with(|decorator| decorator.borrow_mut().set_writer(log.clone()));
+
+    fcl::set_max_sequence_len!(4);
+    f();
+    flush_log();
+
+    #[rustfmt::skip]
+    test_assert!(log, concat!(
+        "f() {\n",
+        "  { // Loop body start.\n",
+        "    a() {}\n",
+        "  } // Loop body end.\n",
+        "  { // Loop body start.\n",
+        "    b() {}\n",
+        "  } // Loop body end.\n",
+        "  // (Loop body, Loop body) repeats 1 time(s).\n",
+        "} // f().\n",
+    ));
+}
+
+#[test]
+fn sequence_folding_is_off_by_default() {
+    #[loggable]
+    fn f() {
+        a();
+        b();
+        a();
+        b();
+    }
+
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    THREAD_DECORATOR.with(|decorator| decorator.borrow_mut().set_writer(log.clone()));
+
+    f();
+    flush_log();
+
+    #[rustfmt::skip]
+    test_assert!(log, concat!(
+        "f() {\n",
+        "  a() {}\n",
+        "  b() {}\n",
+        "  a() {}\n",
+        "  b() {}\n",
+        "} // f().\n",
+    ));
+}
@@ -2,9 +2,11 @@
 
 use std::cell::RefCell;
 use std::rc::Rc;
+use code_commons::CallGraph;
 #[cfg(feature = "single_threaded")]
 use fcl::common::CallLogger;
 use fcl::common::call_log_infra::instances::THREAD_LOGGER;
+use fcl::common::decorators::LogDecorator;
 
 // TODO: Doc-comment.
 #[cfg(feature = "closure_coords_logging")]
@@ -20,6 +22,22 @@ pub(crate) fn substitute_log_writer() -> Rc<RefCell<Vec<u8>>> {
     log
 }
 
+/// Creates the call graph notifying the `decorator` that writes to the returned mock log
+/// (for the tests driving the call graph directly rather than by the `#[loggable]` functions).
+pub(crate) fn decorator_call_graph(decorator: impl LogDecorator + 'static) -> (CallGraph, Rc<RefCell<Vec<u8>>>) {
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    let decorator = Rc::new(RefCell::new(decorator));
+    decorator.borrow_mut().set_writer(log.clone());
+    (CallGraph::new(decorator), log)
+}
+
+/// Returns the number in the current thread's `ThreadId` (e.g. `"2"` for `ThreadId(2)`).
+pub(crate) fn current_thread_number() -> String {
+    let thread_id = format!("{:?}", std::thread::current().id());
+    thread_id.trim_start_matches("ThreadId(").trim_end_matches(')').to_string()
+}
+
 /// Replaces in the passed argument the closure coordiantes in the [`COORDS_RE_SLICE`] format 
 /// with the `"0,0:0,0"`, returns the result as a `String`.
 // TODO: Consider coverting to a macro to preserve the error coordinates in `panic`.
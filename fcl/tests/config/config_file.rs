@@ -0,0 +1,78 @@
+use fcl::common::config::Config;
+use fcl::common::decorators::DecoratorKind;
+
+// High-level logic to test:
+// The `fcl.toml` keys update the corresponding settings of the configuration,
+// the syntax errors, unknown keys, and invalid values are reported (and ignored).
+//
+// Test cases:
+//
+// A: `all_settings`:
+// All the keys with the string, integer, boolean, and array values.
+// Assert: Each setting is updated.
+//
+// B: `errors_reported`:
+// An unknown key, an invalid value, an unsupported value type, and a valid key.
+// Assert: 3 errors are reported, the valid setting is updated.
+//
+// C: `syntax_error`:
+// The content that is not TOML.
+// Assert: 1 error is reported, the configuration is default.
+
+#[test]
+fn all_settings() {
+    let mut config = Config::default();
+    let errors = config.update_from_toml(
+        r#"
+        decorator = "json_lines"
+        output = "stderr"
+        indent_step = 4
+        thread_indent_step = 40
+        logging_is_on = false
+        thread_headers = true
+        exclude = ["log_*", "*::closure*"]
+        "#,
+    );
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(
+        config,
+        Config {
+            decorator_kind: Some(DecoratorKind::JsonLines),
+            output: Some(String::from("stderr")),
+            indent_step: Some(4),
+            thread_indent_step: Some(40),
+            logging_is_on: Some(false),
+            thread_headers: Some(true),
+            exclude: vec![String::from("log_*"), String::from("*::closure*")],
+        }
+    );
+}
+
+#[test]
+fn errors_reported() {
+    let mut config = Config::default();
+    let errors = config.update_from_toml(
+        r#"
+        unknown = 1
+        decorator = "unknown"
+        output = { path = "fcl.log" }
+        indent_step = 4
+        "#,
+    );
+    assert_eq!(errors.len(), 3, "{:?}", errors);
+    assert_eq!(
+        config,
+        Config {
+            indent_step: Some(4),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn syntax_error() {
+    let mut config = Config::default();
+    let errors = config.update_from_toml("decorator = ");
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert_eq!(config, Config::default());
+}
@@ -0,0 +1,87 @@
+use fcl::common::config::Config;
+use fcl::common::decorators::DecoratorKind;
+
+// High-level logic to test:
+// The `FCL_*` environment variables update the corresponding settings of the configuration,
+// the other variables are ignored, and the unknown `FCL_*` variables and invalid values are reported
+// (and ignored).
+// The variables are passed explicitly (rather than set in the process environment)
+// since the tests run in parallel.
+//
+// Test cases:
+//
+// A: `all_settings`:
+// FCL_DECORATOR=tree_like FCL_OUTPUT=fcl.log FCL_INDENT_STEP=4 FCL_THREAD_INDENT_STEP=40
+// FCL_LOGGING_IS_ON=false FCL_EXCLUDE="log_*, *::closure*"
+// Assert: Each setting is updated.
+//
+// B: `other_vars_ignored`:
+// PATH=/bin FCL_CONFIG=my.toml
+// Assert: The configuration is default, no errors.
+//
+// C: `errors_reported`:
+// FCL_DECORATOR=unknown FCL_INDENT_STEP=two FCL_LOGGING_IS_ON=maybe FCL_UNKNOWN=1 FCL_OUTPUT=stderr
+// Assert: 4 errors are reported, the valid setting is updated.
+
+/// Returns the variables as `(String, String)` pairs.
+fn vars(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+    vars.iter()
+        .map(|(name, value)| (String::from(*name), String::from(*value)))
+        .collect()
+}
+
+#[test]
+fn all_settings() {
+    let mut config = Config::default();
+    let errors = config.update_from_env_vars(vars(&[
+        ("FCL_DECORATOR", "tree_like"),
+        ("FCL_OUTPUT", "fcl.log"),
+        ("FCL_INDENT_STEP", "4"),
+        ("FCL_THREAD_INDENT_STEP", "40"),
+        ("FCL_LOGGING_IS_ON", "false"),
+        ("FCL_THREAD_HEADERS", "true"),
+        ("FCL_EXCLUDE", "log_*, *::closure*"),
+    ]));
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(
+        config,
+        Config {
+            decorator_kind: Some(DecoratorKind::TreeLike),
+            output: Some(String::from("fcl.log")),
+            indent_step: Some(4),
+            thread_indent_step: Some(40),
+            logging_is_on: Some(false),
+            thread_headers: Some(true),
+            exclude: vec![String::from("log_*"), String::from("*::closure*")],
+        }
+    );
+}
+
+#[test]
+fn other_vars_ignored() {
+    let mut config = Config::default();
+    let errors = config.update_from_env_vars(vars(&[("PATH", "/bin"), ("FCL_CONFIG", "my.toml")]));
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(config, Config::default());
+}
+
+#[test]
+fn errors_reported() {
+    let mut config = Config::default();
+    let errors = config.update_from_env_vars(vars(&[
+        ("FCL_DECORATOR", "unknown"),
+        ("FCL_INDENT_STEP", "two"),
+        ("FCL_LOGGING_IS_ON", "maybe"),
+        ("FCL_UNKNOWN", "1"),
+        ("FCL_OUTPUT", "stderr"),
+    ]));
+    assert_eq!(errors.len(), 4, "{:?}", errors);
+    assert!(errors[0].starts_with("FCL_DECORATOR: "));
+    assert_eq!(
+        config,
+        Config {
+            output: Some(String::from("stderr")),
+            ..Default::default()
+        }
+    );
+}
@@ -0,0 +1,102 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use fcl::common::CallLogger;
+use fcl::common::call_log_infra::CallLogInfra;
+use fcl::common::config::name_matches;
+use fcl::common::decorators::{CodeLikeDecorator, LogDecorator, WriterPossessor};
+
+use crate::common::*;
+
+// High-level logic to test:
+// The calls of the functions and closures whose names match the exclusion patterns
+// (where `*` matches any characters) are not logged along with the calls they make and their loops.
+//
+// Test cases:
+//
+// A: `name_matching`:
+// Assert: The names match (or not) the patterns with `*` at the start, middle, end, or without `*`.
+//
+// B: `excluded_subtree`:
+// f() {
+//   log_value() {   // Excluded.
+//     g() {}        // Not logged.
+//     { // Loop body start.
+//       h() {}      // Not logged.
+//     } // Loop body end.
+//   }
+//   g() {}
+// }
+// Assert: The excluded call and its subtree are not logged, the rest is.
+
+#[test]
+fn name_matching() {
+    assert!(name_matches("f", "f"));
+    assert!(!name_matches("f", "fg"));
+    assert!(name_matches("log_*", "log_value"));
+    assert!(!name_matches("log_*", "my_log_value"));
+    assert!(name_matches("*_value", "log_value"));
+    assert!(name_matches("*::closure*", "f()::closure{1,1:1,0}"));
+    assert!(!name_matches("*::closure*", "f"));
+    assert!(name_matches("a*b*c", "abc"));
+    assert!(name_matches("a*b*c", "a_b_b_c"));
+    assert!(!name_matches("a*b*c", "ac"));
+    assert!(!name_matches("ab*ba", "aba"));
+    assert!(name_matches("*", ""));
+}
+
+/// Logs the call of `name`.
+fn call(logger: &mut CallLogInfra, name: &str) {
+    logger.log_call(
+        name,
+        #[cfg(feature = "params_logging")]
+        None,
+    );
+}
+
+/// Logs the return.
+fn ret(logger: &mut CallLogInfra) {
+    logger.log_ret(
+        #[cfg(feature = "ret_val_logging")]
+        None,
+    );
+}
+
+#[test]
+fn excluded_subtree() {
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    let decorator = Rc::new(RefCell::new(CodeLikeDecorator::new(None, None)));
+    decorator.borrow_mut().set_writer(log.clone());
+    let decorator: Rc<RefCell<dyn LogDecorator>> = decorator;
+
+    let mut logger = CallLogInfra::new(decorator);
+    logger.set_exclude(vec![String::from("log_*")]);
+    call(&mut logger, "f");
+    {
+        call(&mut logger, "log_value");
+        {
+            call(&mut logger, "g");
+            ret(&mut logger);
+            logger.log_loopbody_start();
+            {
+                call(&mut logger, "h");
+                ret(&mut logger);
+            }
+            logger.log_loopbody_end();
+            logger.log_loop_end();
+        }
+        ret(&mut logger);
+        call(&mut logger, "g");
+        ret(&mut logger);
+    }
+    ret(&mut logger);
+    logger.flush();
+
+    test_assert!(
+        log,
+        "f() {\n\
+        \x20 g() {}\n\
+        } // f().\n"
+    );
+}
@@ -0,0 +1,4 @@
+mod env_vars;
+mod exclude;
+#[cfg(feature = "config_file")]
+mod config_file;
@@ -0,0 +1,58 @@
+// The separate test binary (process) for the log output specified in the configuration,
+// that is read once per process from the environment variables set before the first access to the settings
+// (the tests of `tests/mod.rs` share the process and access the settings in parallel).
+#![cfg(feature = "log_merger")]
+
+use std::path::PathBuf;
+
+use fcl::common::call_log_infra::instances::THREAD_LOGGER;
+use fcl::common::decorators::DecoratorKind;
+use fcl::common::settings::set_decorator_kind;
+use fcl_proc_macros::loggable;
+
+// High-level logic to test:
+// The log output specified in the configuration (`FCL_OUTPUT`) is used regardless of which thread
+// makes the first access to the settings and which thread makes the first logged call.
+//
+// Test cases:
+//
+// A: `first_logged_call_by_other_thread`:
+// `FCL_OUTPUT` is a file path, the main thread makes a setting (the first access to the settings),
+// a spawned thread makes the first logged call.
+// Assert: The call is logged to the file.
+
+/// Returns the path of the configured log file.
+fn log_file_path() -> PathBuf {
+    std::env::temp_dir().join(format!("fcl_config_output_{}.log", std::process::id()))
+}
+
+/// Flushes the calling thread's log (to log the cached calls).
+fn flush_log() {
+    THREAD_LOGGER.with(|logger| {
+        #[cfg(feature = "single_threaded")]
+        let logger = logger.borrow_mut();
+
+        logger.borrow_mut().flush();
+    });
+}
+
+#[test]
+fn first_logged_call_by_other_thread() {
+    #[loggable]
+    fn f() {}
+
+    // The only test of the process, no other thread reads the environment:
+    unsafe { std::env::set_var("FCL_OUTPUT", log_file_path()) };
+    set_decorator_kind(DecoratorKind::CodeLike);
+
+    std::thread::spawn(|| {
+        f();
+        flush_log();
+    })
+    .join()
+    .unwrap();
+
+    let log = std::fs::read_to_string(log_file_path()).unwrap();
+    let _ignore_error = std::fs::remove_file(log_file_path());
+    assert!(log.contains("f() {}\n"), "The log:\n{}", log);
+}
@@ -0,0 +1,60 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use code_commons::CallGraph;
+use fcl::common::decorators::{CodeLikeDecorator, WriterPossessor};
+
+use crate::common::*;
+
+// High-level logic to test:
+// If the thread color is set then the `CodeLikeDecorator` colors each line in the thread color,
+// the function and closure names are bold, the parameter values are italic,
+// the return values are underlined, and the repeat count comments are faint.
+// The call graph is driven directly (rather than by the `#[loggable]` functions)
+// since the `THREAD_DECORATOR` colors are off by default.
+//
+// Test cases:
+//
+// A: `colored_lines`:
+// f(x: 1) {
+//   { // Loop body start.
+//     h() {}
+//   } // Loop body end.
+//   g() {}
+//   // g() repeats 2 time(s).
+// } -> 5 // f().
+// Assert: The lines are colored in cyan (`\x1b[36m`), the color is reset at the end of each line.
+
+#[test]
+fn colored_lines() {
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    let decorator = Rc::new(RefCell::new(CodeLikeDecorator::new(None, None)));
+    decorator.borrow_mut().set_writer(log.clone());
+    decorator.borrow_mut().set_thread_color(Some(36)); // Cyan.
+    let mut call_graph = CallGraph::new(decorator);
+
+    call_graph.add_call("f", Some(String::from("x: 1")));
+    call_graph.add_loopbody_start();
+    call_graph.add_call("h", None);
+    call_graph.add_ret(None);
+    call_graph.add_loopbody_end();
+    call_graph.add_loop_end();
+    for _ in 0..3 {
+        call_graph.add_call("g", None);
+        call_graph.add_ret(None);
+    }
+    call_graph.add_ret(Some(String::from("5")));
+    call_graph.flush(true);
+
+    #[rustfmt::skip]
+    test_assert!(log, concat!(
+        "\x1b[36m\x1b[1mf\x1b[22m(\x1b[3mx: 1\x1b[23m) {\x1b[0m\n",
+        "  \x1b[36m{ // Loop body start.\x1b[0m\n",
+        "    \x1b[36m\x1b[1mh\x1b[22m() {\x1b[0m\x1b[36m}\x1b[0m\n",
+        "  \x1b[36m} // Loop body end.\x1b[0m\n",
+        "  \x1b[36m\x1b[1mg\x1b[22m() {\x1b[0m\x1b[36m}\x1b[0m\n",
+        "  \x1b[36m\x1b[2m// g() repeats 2 time(s).\x1b[22m\x1b[0m\n",
+        "\x1b[36m} -> \x1b[4m5\x1b[24m // f().\x1b[0m\n",
+    ));
+}
@@ -0,0 +1,106 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use code_commons::CallGraph;
+use fcl::common::binary_trace::decode_binary_trace;
+use fcl::common::decorators::{BinaryTraceDecorator, CodeLikeDecorator, WriterPossessor};
+
+use crate::common::*;
+
+// High-level logic to test:
+// The `BinaryTraceDecorator` logs the calls as a binary trace that is decoded by `decode_binary_trace()`
+// to the notifications of another decorator, e.g. the `CodeLikeDecorator`.
+//
+// Test cases:
+//
+// A: `decoded_to_code_like`:
+// f(x: 1) {
+//   g() {} -> 2
+//   // g() repeats 1 time(s).
+//   h() {}
+//   { // Loop body start.
+//     g() {} -> 2
+//   } // Loop body end.
+//   // Loop body repeats 1 time(s).
+// } -> 5 // f().
+// Assert: The trace starts with the magic and version, the decoded trace is logged by the `CodeLikeDecorator`
+// the same way as if the `CodeLikeDecorator` was notified directly.
+//
+// B: `invalid_trace`:
+// Assert: The truncated trace and the unknown record tag are reported as the `InvalidData` errors.
+
+/// Notifies the decorator of the `call_graph` about the calls of the test case A.
+fn notify_calls(mut call_graph: CallGraph) {
+    call_graph.add_call("f", Some(String::from("x: 1")));
+    for _ in 0..2 {
+        call_graph.add_call("g", None);
+        call_graph.add_ret(Some(String::from("2")));
+    }
+    call_graph.add_call("h", None); // Avoids the loop body right after the repeated call (panics in `CallGraph::add_loopbody_start()`).
+    call_graph.add_ret(None);
+    for _ in 0..2 {
+        call_graph.add_loopbody_start();
+        call_graph.add_call("g", None);
+        call_graph.add_ret(Some(String::from("2")));
+        call_graph.add_loopbody_end();
+    }
+    call_graph.add_loop_end();
+    call_graph.add_ret(Some(String::from("5")));
+    call_graph.flush(true);
+}
+
+#[test]
+fn decoded_to_code_like() {
+    let (call_graph, trace) = decorator_call_graph(BinaryTraceDecorator::new(None));
+    notify_calls(call_graph);
+    assert!(trace.borrow().starts_with(b"FCLB\x01"));
+
+    // The expected log:
+    let (call_graph, expected_log) = decorator_call_graph(CodeLikeDecorator::new(None, None));
+    notify_calls(call_graph);
+
+    // Decoding:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    let mut thread_names = Vec::new();
+    decode_binary_trace(&mut trace.borrow().as_slice(), |_thread_id, thread_name| {
+        thread_names.push(thread_name.to_string());
+        let decorator = Rc::new(RefCell::new(CodeLikeDecorator::new(None, None)));
+        decorator.borrow_mut().set_writer(log.clone());
+        decorator
+    })
+    .unwrap();
+
+    assert_eq!(thread_names, [std::thread::current().name().unwrap()]);
+    test_assert!(log, String::from_utf8(expected_log.borrow().clone()).unwrap());
+    #[rustfmt::skip]
+    test_assert!(log, concat!(
+        "f(x: 1) {\n",
+        "  g() {} -> 2\n",
+        "  // g() repeats 1 time(s).\n",
+        "  h() {}\n",
+        "  { // Loop body start.\n",
+        "    g() {} -> 2\n",
+        "  } // Loop body end.\n",
+        "  // Loop body repeats 1 time(s).\n",
+        "} -> 5 // f().\n",
+    ));
+}
+
+#[test]
+fn invalid_trace() {
+    let (call_graph, trace) = decorator_call_graph(BinaryTraceDecorator::new(None));
+    notify_calls(call_graph);
+
+    let mut truncated_trace = trace.borrow().clone();
+    truncated_trace.pop();
+    let mut unknown_tag_trace = Vec::from(&b"FCLB\x01"[..]);
+    unknown_tag_trace.extend_from_slice(&[9, 0, 0, 0, 0xFF, 1, 0, 0, 0, 0, 0, 0, 0]);
+
+    for invalid_trace in [truncated_trace, unknown_tag_trace] {
+        let error = decode_binary_trace(&mut invalid_trace.as_slice(), |_thread_id, _thread_name| {
+            Rc::new(RefCell::new(CodeLikeDecorator::new(None, None)))
+        })
+        .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
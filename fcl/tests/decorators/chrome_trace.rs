@@ -0,0 +1,171 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use fcl::common::decorators::ChromeTraceDecorator;
+
+use crate::common::*;
+
+// High-level logic to test:
+// The `ChromeTraceDecorator` logs the calls and loop bodies as the Chrome Trace duration events
+// (`"ph":"B"`/`"ph":"E"`), and the repeat counts as the instant events (`"ph":"i"`) carrying the count.
+// The log starts with the array start `[` and the thread name metadata event.
+// The timestamps vary from run to run, so they are checked for the growth and then masked.
+//
+// Test cases:
+//
+// A: `calls_returns_and_repeat_count`:
+// f(x: 1) {
+//   g() {}
+//   // g() repeats 2 time(s).
+// } -> 5 // f().
+// Assert: The calls are the `B`/`E` pairs, the repeat count is an instant event.
+//
+// B: `loop_bodies_and_sequence`:
+// { // Loop body start.
+//   a() {}
+// } // Loop body end.
+// { // Loop body start.
+//   b() {}
+// } // Loop body end.
+// // (Loop body, Loop body) repeats 1 time(s).
+// Assert: The loop bodies are the `B`/`E` pairs, the sequence repeat count is an instant event.
+//
+// C: `cached_call_timestamps` (with the call timing on):
+// g() {}
+// g() {         // Is cached as a potential repeat of the previous `g()`.
+//   h() {}
+//               // (Delay.)
+// } // g().     // Is not a repeat, is logged with `h()`.
+// Assert: The cached calls are logged at the moments of the call and return rather than of the notification,
+// i.e. the `h()` return is logged earlier than the `g()` return by the delay.
+
+/// Returns the `"pid":..,"tid":..` fragment expected for the current thread.
+fn thread_fields() -> String {
+    format!("\"pid\":{},\"tid\":{}", std::process::id(), current_thread_number())
+}
+
+/// Returns the thread name metadata event expected for the current thread.
+fn thread_name_event() -> String {
+    format!(
+        "{{\"name\":\"thread_name\",\"ph\":\"M\",{},\"args\":{{\"name\":\"{}\"}}}},\n",
+        thread_fields(),
+        std::thread::current().name().unwrap_or_default()
+    )
+}
+
+/// Returns the log contents with each `"ts":<value>` replaced with `"ts":_`.
+/// Asserts that the timestamps do not decrease.
+fn masked_timestamps(log: &Rc<RefCell<Vec<u8>>>) -> String {
+    let log_contents = String::from_utf8(log.borrow().clone()).unwrap();
+    let mut masked = String::new();
+    let mut prev_timestamp = 0.0;
+    let mut rest = log_contents.as_str();
+    while let Some(ts_idx) = rest.find("\"ts\":") {
+        let value_start = ts_idx + "\"ts\":".len();
+        let value_len = rest[value_start..].find(',').unwrap();
+        let timestamp: f64 = rest[value_start..value_start + value_len].parse().unwrap();
+        assert!(prev_timestamp <= timestamp);
+        prev_timestamp = timestamp;
+        masked.push_str(&rest[..value_start]);
+        masked.push('_');
+        rest = &rest[value_start + value_len..];
+    }
+    masked.push_str(rest);
+    masked
+}
+
+/// Returns the name, phase, and timestamp of each duration event (e.g. `("h", "E", 93.885)`) in the log order.
+#[cfg(feature = "call_timing")]
+fn duration_event_timestamps(log: &Rc<RefCell<Vec<u8>>>) -> Vec<(String, String, f64)> {
+    let log_contents = String::from_utf8(log.borrow().clone()).unwrap();
+    log_contents
+        .lines()
+        .filter(|line| line.contains("\"ph\":\"B\"") || line.contains("\"ph\":\"E\""))
+        .map(|line| {
+            let field = |key: &str| {
+                let value_start = line.find(key).unwrap() + key.len();
+                let value_len = line[value_start..].find(['"', ',']).unwrap();
+                line[value_start..value_start + value_len].to_string()
+            };
+            (field("\"name\":\""), field("\"ph\":\""), field("\"ts\":").parse().unwrap())
+        })
+        .collect()
+}
+
+#[test]
+fn calls_returns_and_repeat_count() {
+    let (mut call_graph, log) = decorator_call_graph(ChromeTraceDecorator::new(None));
+
+    call_graph.add_call("f", Some(String::from("x: 1")));
+    for _ in 0..3 {
+        call_graph.add_call("g", None);
+        call_graph.add_ret(None);
+    }
+    call_graph.add_ret(Some(String::from("5")));
+    call_graph.flush(true);
+
+    let mut expected = String::from("[\n") + &thread_name_event();
+    #[rustfmt::skip]
+    expected.push_str(&format!(concat!(
+        "{{\"name\":\"f\",\"cat\":\"call\",\"ph\":\"B\",\"ts\":_,{0},\"args\":{{\"params\":\"x: 1\"}}}},\n",
+        "{{\"name\":\"g\",\"cat\":\"call\",\"ph\":\"B\",\"ts\":_,{0},\"args\":{{\"params\":null}}}},\n",
+        "{{\"name\":\"g\",\"cat\":\"call\",\"ph\":\"E\",\"ts\":_,{0},\"args\":{{\"ret_val\":null}}}},\n",
+        "{{\"name\":\"g() repeats 2 time(s)\",\"cat\":\"repeat_count\",\"ph\":\"i\",\"s\":\"t\",\"ts\":_,{0},\"args\":{{\"category\":\"exact\",\"count\":2}}}},\n",
+        "{{\"name\":\"f\",\"cat\":\"call\",\"ph\":\"E\",\"ts\":_,{0},\"args\":{{\"ret_val\":\"5\"}}}},\n",
+    ), thread_fields()));
+    assert_eq!(masked_timestamps(&log), expected);
+}
+
+#[test]
+fn loop_bodies_and_sequence() {
+    let (mut call_graph, log) = decorator_call_graph(ChromeTraceDecorator::new(None));
+    call_graph.set_max_sequence_len(4); // The periodic sequence folding is off by default.
+
+    for _ in 0..2 {
+        for name in ["a", "b"] {
+            call_graph.add_loopbody_start();
+            call_graph.add_call(name, None);
+            call_graph.add_ret(None);
+            call_graph.add_loopbody_end();
+        }
+    }
+    call_graph.add_loop_end();
+    call_graph.flush(true);
+
+    let mut expected = String::from("[\n") + &thread_name_event();
+    #[rustfmt::skip]
+    expected.push_str(&format!(concat!(
+        "{{\"name\":\"Loop body\",\"cat\":\"loopbody\",\"ph\":\"B\",\"ts\":_,{0},\"args\":{{}}}},\n",
+        "{{\"name\":\"a\",\"cat\":\"call\",\"ph\":\"B\",\"ts\":_,{0},\"args\":{{\"params\":null}}}},\n",
+        "{{\"name\":\"a\",\"cat\":\"call\",\"ph\":\"E\",\"ts\":_,{0},\"args\":{{\"ret_val\":null}}}},\n",
+        "{{\"name\":\"Loop body\",\"cat\":\"loopbody\",\"ph\":\"E\",\"ts\":_,{0},\"args\":{{}}}},\n",
+        "{{\"name\":\"Loop body\",\"cat\":\"loopbody\",\"ph\":\"B\",\"ts\":_,{0},\"args\":{{}}}},\n",
+        "{{\"name\":\"b\",\"cat\":\"call\",\"ph\":\"B\",\"ts\":_,{0},\"args\":{{\"params\":null}}}},\n",
+        "{{\"name\":\"b\",\"cat\":\"call\",\"ph\":\"E\",\"ts\":_,{0},\"args\":{{\"ret_val\":null}}}},\n",
+        "{{\"name\":\"Loop body\",\"cat\":\"loopbody\",\"ph\":\"E\",\"ts\":_,{0},\"args\":{{}}}},\n",
+        "{{\"name\":\"(Loop body, Loop body) repeats 1 time(s)\",\"cat\":\"sequence_repeat_count\",\"ph\":\"i\",\"s\":\"t\",\"ts\":_,{0},\"args\":{{\"category\":\"exact\",\"count\":1}}}},\n",
+    ), thread_fields()));
+    assert_eq!(masked_timestamps(&log), expected);
+}
+
+#[cfg(feature = "call_timing")]
+#[test]
+fn cached_call_timestamps() {
+    const DELAY: std::time::Duration = std::time::Duration::from_millis(20);
+    let (mut call_graph, log) = decorator_call_graph(ChromeTraceDecorator::new(None));
+    call_graph.set_call_timing(true); // The call timing is off by default.
+
+    call_graph.add_call("g", None);
+    call_graph.add_ret(None);
+    call_graph.add_call("g", None); // Is cached as a potential repeat.
+    call_graph.add_call("h", None);
+    call_graph.add_ret(None);
+    std::thread::sleep(DELAY);
+    call_graph.add_ret(None); // Is not a repeat (has a nested call), is logged with the nested call.
+    call_graph.flush(true);
+
+    let timestamps = duration_event_timestamps(&log);
+    let h_end = timestamps.iter().find(|(name, phase, _)| name == "h" && phase == "E").unwrap().2;
+    let g_end = timestamps.last().unwrap().2;
+    assert!(g_end - h_end >= DELAY.as_micros() as f64, "{:?}", timestamps);
+}
@@ -0,0 +1,69 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use code_commons::CallGraph;
+use fcl::common::decorators::{DotGraphDecorator, WriterPossessor, write_dot_graph};
+
+// High-level logic to test:
+// The `DotGraphDecorator` aggregates the calls into the process-wide static call graph
+// with the caller-callee edges labeled with the call counts (the repeats included).
+// The thread's aggregates are merged into the graph upon flush.
+// The graph is process-wide (and the tests run in parallel), so the test uses the unique function names
+// and checks the presence of the expected lines rather than the whole graph.
+//
+// Test cases:
+//
+// A: `repeated_calls_are_counted`:
+// dot_f() {
+//   { // Loop body start.
+//     dot_g() {
+//       dot_h() {}
+//       // dot_h() repeats 1 time(s).
+//     } // dot_g().
+//   } // Loop body end.
+//   // Loop body repeats 2 time(s).
+// } // dot_f().
+// Assert: `dot_f` is called once by the thread, `dot_g` 3 times by `dot_f`, `dot_h` 6 times by `dot_g`.
+
+#[test]
+fn repeated_calls_are_counted() {
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    let decorator = Rc::new(RefCell::new(DotGraphDecorator::new(None)));
+    decorator.borrow_mut().set_writer(log.clone());
+    let mut call_graph = CallGraph::new(decorator);
+
+    call_graph.add_call("dot_f", None);
+    for _ in 0..3 {
+        call_graph.add_loopbody_start();
+        call_graph.add_call("dot_g", None);
+        for _ in 0..2 {
+            call_graph.add_call("dot_h", None);
+            call_graph.add_ret(None);
+        }
+        call_graph.add_ret(None);
+        call_graph.add_loopbody_end();
+    }
+    call_graph.add_loop_end();
+    call_graph.add_ret(None);
+    call_graph.flush(true);
+
+    let mut graph = Vec::new();
+    write_dot_graph(&mut graph).unwrap();
+    let graph = String::from_utf8(graph).unwrap();
+
+    let thread_node = format!("\"thread {}\"", std::thread::current().name().unwrap_or_default());
+    assert!(graph.starts_with("digraph fcl {\n"));
+    assert!(graph.ends_with("}\n"));
+    for expected_line in [
+        String::from("  \"dot_f\" [label=\"dot_f\\n1 call(s)\"];\n"),
+        String::from("  \"dot_g\" [label=\"dot_g\\n3 call(s)\"];\n"),
+        String::from("  \"dot_h\" [label=\"dot_h\\n6 call(s)\"];\n"),
+        format!("  {} [shape=ellipse];\n", thread_node),
+        format!("  {} -> \"dot_f\" [label=\"1\"];\n", thread_node),
+        String::from("  \"dot_f\" -> \"dot_g\" [label=\"3\"];\n"),
+        String::from("  \"dot_g\" -> \"dot_h\" [label=\"6\"];\n"),
+    ] {
+        assert!(graph.contains(&expected_line), "{} is not in {}", expected_line, graph);
+    }
+}
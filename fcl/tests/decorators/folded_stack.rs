@@ -0,0 +1,118 @@
+use fcl::common::decorators::FoldedStackDecorator;
+
+use crate::common::*;
+
+// High-level logic to test:
+// The `FoldedStackDecorator` aggregates the calls into the folded stacks weighted by the call count.
+// The repeat counts multiply the aggregates of the repeated subtree, the loop bodies are transparent.
+// The aggregates are written upon flush.
+//
+// Test cases:
+//
+// A: `repeated_subtree`:
+// f() {
+//   work() {
+//     parse() {}
+//     // parse() repeats 1 time(s).
+//   } // work().
+//   // work() repeats 2 time(s).
+// } // f().
+// Assert: `f 1`, `f;work 3`, `f;work;parse 6`.
+//
+// B: `repeated_sequence_in_loop`:
+// f() {
+//   { // Loop body start.
+//     a() {}
+//   } // Loop body end.
+//   { // Loop body start.
+//     b() {}
+//   } // Loop body end.
+//   // (Loop body, Loop body) repeats 1 time(s).
+// } // f().
+// Assert: The loop bodies are not on the stack, `f;a 2`, `f;b 2`.
+//
+// C: `weight_unit_is_kept` (the call timing is switched after the first call):
+// f() {}
+// g() {}
+// Assert: With the call timing off upon `f()` the weights are the call counts: `f 1`, `g 1`.
+// With the call timing on upon `f()` the weights are the self-times: `f <ns>`, `g()` (untimed) adds no weight.
+
+#[test]
+fn repeated_subtree() {
+    let (mut call_graph, log) = decorator_call_graph(FoldedStackDecorator::new(None));
+
+    call_graph.add_call("f", None);
+    for _ in 0..3 {
+        call_graph.add_call("work", None);
+        for _ in 0..2 {
+            call_graph.add_call("parse", None);
+            call_graph.add_ret(None);
+        }
+        call_graph.add_ret(None);
+    }
+    call_graph.add_ret(None);
+    call_graph.flush(true);
+
+    #[rustfmt::skip]
+    test_assert!(log, concat!(
+        "f 1\n",
+        "f;work 3\n",
+        "f;work;parse 6\n",
+    ));
+}
+
+#[test]
+fn repeated_sequence_in_loop() {
+    let (mut call_graph, log) = decorator_call_graph(FoldedStackDecorator::new(None));
+
+    call_graph.add_call("f", None);
+    for _ in 0..2 {
+        for name in ["a", "b"] {
+            call_graph.add_loopbody_start();
+            call_graph.add_call(name, None);
+            call_graph.add_ret(None);
+            call_graph.add_loopbody_end();
+        }
+    }
+    call_graph.add_loop_end();
+    call_graph.add_ret(None);
+    call_graph.flush(true);
+
+    #[rustfmt::skip]
+    test_assert!(log, concat!(
+        "f 1\n",
+        "f;a 2\n",
+        "f;b 2\n",
+    ));
+}
+
+#[cfg(feature = "call_timing")]
+#[test]
+fn weight_unit_is_kept() {
+    const DURATION: std::time::Duration = std::time::Duration::from_millis(1);
+    for call_timing in [false, true] {
+        let (mut call_graph, log) = decorator_call_graph(FoldedStackDecorator::new(None));
+
+        call_graph.set_call_timing(call_timing);
+        call_graph.add_call("f", None);
+        std::thread::sleep(DURATION);
+        call_graph.add_ret(None);
+        call_graph.set_call_timing(!call_timing);
+        call_graph.add_call("g", None);
+        std::thread::sleep(DURATION);
+        call_graph.add_ret(None);
+        call_graph.flush(true);
+
+        if call_timing {
+            let log_contents = String::from_utf8(log.borrow().clone()).unwrap();
+            let weight: u64 = log_contents.strip_prefix("f ").unwrap().trim_end().parse().unwrap();
+            assert!(weight >= DURATION.as_nanos() as u64, "{}", log_contents);
+        } else {
+            #[rustfmt::skip]
+            test_assert!(log, concat!(
+                "f 1\n",
+                "g 1\n",
+            ));
+        }
+    }
+}
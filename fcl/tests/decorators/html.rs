@@ -0,0 +1,80 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use fcl::common::decorators::HtmlDecorator;
+
+use crate::common::*;
+
+// High-level logic to test:
+// The `HtmlDecorator` logs the HTML prolog (with the script building the per-thread trees)
+// followed by the thread (tab) start and a flat element per event, marked with the thread ID.
+//
+// Test cases:
+//
+// A: `calls_returns_and_repeat_count`:
+// f(x: 1) {
+//   g() {}
+//   // g() repeats 2 time(s).
+// } -> 5 // f().
+// Assert: The prolog is logged first, then the thread start and the events.
+//
+// B: `special_characters_are_escaped`:
+// f(s: "<a> & 'b'") {} -> "</div>"
+// Assert: The HTML special characters in the parameter and return values are escaped.
+
+/// The end of the HTML prolog.
+const PROLOG_END: &str = "<div id=\"fcl-events\">\n";
+
+/// Asserts that the log starts with the HTML prolog, returns the rest of the log.
+fn events(log: &Rc<RefCell<Vec<u8>>>) -> String {
+    let log_contents = String::from_utf8(log.borrow().clone()).unwrap();
+    assert!(log_contents.starts_with("<!DOCTYPE html>\n"));
+    let prolog_end_idx = log_contents.find(PROLOG_END).unwrap() + PROLOG_END.len();
+    log_contents[prolog_end_idx..].to_string()
+}
+
+/// Returns the thread (tab) start event expected for the current thread.
+fn thread_start() -> String {
+    format!(
+        "<div data-t=\"{}\" data-e=\"thread\">{}</div>\n",
+        current_thread_number(),
+        std::thread::current().name().unwrap_or_default()
+    )
+}
+
+#[test]
+fn calls_returns_and_repeat_count() {
+    let (mut call_graph, log) = decorator_call_graph(HtmlDecorator::new(None));
+
+    call_graph.add_call("f", Some(String::from("x: 1")));
+    for _ in 0..3 {
+        call_graph.add_call("g", None);
+        call_graph.add_ret(None);
+    }
+    call_graph.add_ret(Some(String::from("5")));
+    call_graph.flush(true);
+
+    #[rustfmt::skip]
+    assert_eq!(events(&log), thread_start() + &format!(concat!(
+        "<div data-t=\"{0}\" data-e=\"call\" data-p=\"x: 1\">f</div>\n",
+        "<div data-t=\"{0}\" data-e=\"call\">g</div>\n",
+        "<div data-t=\"{0}\" data-e=\"ret\"></div>\n",
+        "<div data-t=\"{0}\" data-e=\"repeat\">2</div>\n",
+        "<div data-t=\"{0}\" data-e=\"ret\" data-v=\"5\"></div>\n",
+    ), current_thread_number()));
+}
+
+#[test]
+fn special_characters_are_escaped() {
+    let (mut call_graph, log) = decorator_call_graph(HtmlDecorator::new(None));
+
+    call_graph.add_call("f", Some(String::from("s: \"<a> & 'b'\"")));
+    call_graph.add_ret(Some(String::from("\"</div>\"")));
+    call_graph.flush(true);
+
+    #[rustfmt::skip]
+    assert_eq!(events(&log), thread_start() + &format!(concat!(
+        "<div data-t=\"{0}\" data-e=\"call\" data-p=\"s: &quot;&lt;a&gt; &amp; &#39;b&#39;&quot;\">f</div>\n",
+        "<div data-t=\"{0}\" data-e=\"ret\" data-v=\"&quot;&lt;/div&gt;&quot;\"></div>\n",
+    ), current_thread_number()));
+}
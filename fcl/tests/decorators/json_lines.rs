@@ -0,0 +1,111 @@
+use fcl::common::decorators::JsonLinesDecorator;
+
+use crate::common::*;
+
+// High-level logic to test:
+// The `JsonLinesDecorator` logs each call graph event as a separate JSON object on a separate line,
+// marked with the ID and name of the thread that has created the decorator.
+//
+// Test cases:
+//
+// A: `calls_returns_and_repeat_count`:
+// f(x: 1) {
+//   g() {}
+//   // g() repeats 2 time(s).
+// } -> 5 // f().
+// Assert: Each of the call, return, and repeat count events is a separate JSON line.
+//
+// B: `loop_bodies_and_sequence`:
+// { // Loop body start.
+//   a() {}
+// } // Loop body end.
+// { // Loop body start.
+//   b() {}
+// } // Loop body end.
+// // (Loop body, Loop body) repeats 1 time(s).
+// Assert: The loop body and the sequence repeat count events are JSON lines.
+//
+// C: `special_characters_are_escaped`:
+// f(s: "a\b
+// ") {}
+// Assert: The quotes, backslashes, and line ends in the parameter and return values are escaped.
+
+/// Returns the `"thread_id":..,"thread_name":..` fragment expected for the current thread.
+fn thread_fields() -> String {
+    format!(
+        "\"thread_id\":{},\"thread_name\":\"{}\"",
+        current_thread_number(),
+        std::thread::current().name().unwrap_or_default()
+    )
+}
+
+#[test]
+fn calls_returns_and_repeat_count() {
+    let (mut call_graph, log) = decorator_call_graph(JsonLinesDecorator::new(None));
+
+    call_graph.add_call("f", Some(String::from("x: 1")));
+    for _ in 0..3 {
+        call_graph.add_call("g", None);
+        call_graph.add_ret(None);
+    }
+    call_graph.add_ret(Some(String::from("5")));
+    call_graph.flush(true);
+
+    let thread = thread_fields();
+    #[rustfmt::skip]
+    test_assert!(log, format!(concat!(
+        "{{{0},\"event\":\"call\",\"depth\":0,\"name\":\"f\",\"params\":\"x: 1\"}}\n",
+        "{{{0},\"event\":\"call\",\"depth\":1,\"name\":\"g\",\"params\":null}}\n",
+        "{{{0},\"event\":\"return\",\"depth\":1,\"name\":\"g\",\"has_nested_calls\":false,\"ret_val\":null}}\n",
+        "{{{0},\"event\":\"repeat_count\",\"depth\":1,\"kind\":\"call\",\"name\":\"g\",\"category\":\"exact\",\"count\":2}}\n",
+        "{{{0},\"event\":\"return\",\"depth\":0,\"name\":\"f\",\"has_nested_calls\":true,\"ret_val\":\"5\"}}\n",
+    ), thread));
+}
+
+#[test]
+fn loop_bodies_and_sequence() {
+    let (mut call_graph, log) = decorator_call_graph(JsonLinesDecorator::new(None));
+    call_graph.set_max_sequence_len(4); // The periodic sequence folding is off by default.
+
+    for _ in 0..2 {
+        for name in ["a", "b"] {
+            call_graph.add_loopbody_start();
+            call_graph.add_call(name, None);
+            call_graph.add_ret(None);
+            call_graph.add_loopbody_end();
+        }
+    }
+    call_graph.add_loop_end();
+    call_graph.flush(true);
+
+    let thread = thread_fields();
+    #[rustfmt::skip]
+    test_assert!(log, format!(concat!(
+        "{{{0},\"event\":\"loopbody_start\",\"depth\":0}}\n",
+        "{{{0},\"event\":\"call\",\"depth\":1,\"name\":\"a\",\"params\":null}}\n",
+        "{{{0},\"event\":\"return\",\"depth\":1,\"name\":\"a\",\"has_nested_calls\":false,\"ret_val\":null}}\n",
+        "{{{0},\"event\":\"loopbody_end\",\"depth\":0}}\n",
+        "{{{0},\"event\":\"loopbody_start\",\"depth\":0}}\n",
+        "{{{0},\"event\":\"call\",\"depth\":1,\"name\":\"b\",\"params\":null}}\n",
+        "{{{0},\"event\":\"return\",\"depth\":1,\"name\":\"b\",\"has_nested_calls\":false,\"ret_val\":null}}\n",
+        "{{{0},\"event\":\"loopbody_end\",\"depth\":0}}\n",
+        "{{{0},\"event\":\"sequence_repeat_count\",\"depth\":0,",
+            "\"items\":[{{\"kind\":\"loopbody\"}},{{\"kind\":\"loopbody\"}}],\"category\":\"exact\",\"count\":1}}\n",
+    ), thread));
+}
+
+#[test]
+fn special_characters_are_escaped() {
+    let (mut call_graph, log) = decorator_call_graph(JsonLinesDecorator::new(None));
+
+    call_graph.add_call("f", Some(String::from("s: \"a\\b\n\"")));
+    call_graph.add_ret(Some(String::from("\t")));
+    call_graph.flush(true);
+
+    let thread = thread_fields();
+    #[rustfmt::skip]
+    test_assert!(log, format!(concat!(
+        "{{{0},\"event\":\"call\",\"depth\":0,\"name\":\"f\",\"params\":\"s: \\\"a\\\\b\\n\\\"\"}}\n",
+        "{{{0},\"event\":\"return\",\"depth\":0,\"name\":\"f\",\"has_nested_calls\":false,\"ret_val\":\"\\t\"}}\n",
+    ), thread));
+}
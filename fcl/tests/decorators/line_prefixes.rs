@@ -0,0 +1,131 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use code_commons::CallGraph;
+use fcl::common::decorators::{CodeLikeDecorator, LinePrefixes, TreeLikeDecorator, WriterPossessor};
+
+use crate::common::*;
+
+// High-level logic to test:
+// If the line prefixes are set then each line logged by the text decorators starts with
+// the monotonic timestamp, wall-clock time, and/or the thread name and ID.
+// The continuation of a line (`}` in `f() {}`) is not prefixed.
+// The call graph is driven directly (rather than by the `#[loggable]` functions)
+// since the `THREAD_DECORATOR` line prefixes are off by default.
+//
+// Test cases:
+//
+// A: `thread_prefix`:
+// [<thread>] f() {
+// [<thread>]   g() {}
+// [<thread>]   // g() repeats 1 time(s).
+// [<thread>] } // f().
+// Assert: Each line (but not the line continuation) is prefixed with the thread name and ID.
+//
+// B: `tree_like_thread_prefix`:
+// [<thread>] +-f()
+// [<thread>] | +-g()
+// [<thread>] | `-return
+// Assert: Each line is prefixed with the thread name and ID.
+//
+// C: `timestamp_prefixes`:
+// [+0.000123s] [12:34:56.789] f() {}
+// Assert: The line starts with the monotonic timestamp and the wall-clock time.
+
+/// Returns the thread prefix expected for the current thread.
+fn expected_thread_prefix() -> String {
+    let thread = std::thread::current();
+    let thread_id = format!("{:?}", thread.id());
+    format!(
+        "[{}#{}] ",
+        thread.name().unwrap_or_default(),
+        thread_id.trim_start_matches("ThreadId(").trim_end_matches(')')
+    )
+}
+
+/// Returns the line prefixes with only the thread prefix on.
+fn thread_only() -> LinePrefixes {
+    LinePrefixes {
+        thread: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn thread_prefix() {
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    let decorator = Rc::new(RefCell::new(CodeLikeDecorator::new(None, None)));
+    decorator.borrow_mut().set_writer(log.clone());
+    decorator.borrow_mut().set_line_prefixes(thread_only());
+    let mut call_graph = CallGraph::new(decorator);
+
+    call_graph.add_call("f", None);
+    for _ in 0..2 {
+        call_graph.add_call("g", None);
+        call_graph.add_ret(None);
+    }
+    call_graph.add_ret(None);
+    call_graph.flush(true);
+
+    #[rustfmt::skip]
+    test_assert!(log, format!(concat!(
+        "{0}f() {{\n",
+        "{0}  g() {{}}\n",
+        "{0}  // g() repeats 1 time(s).\n",
+        "{0}}} // f().\n",
+    ), expected_thread_prefix()));
+}
+
+#[test]
+fn tree_like_thread_prefix() {
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    let decorator = Rc::new(RefCell::new(TreeLikeDecorator::new(None, None, None, None)));
+    decorator.borrow_mut().set_writer(log.clone());
+    decorator.borrow_mut().set_line_prefixes(thread_only());
+    let mut call_graph = CallGraph::new(decorator);
+
+    call_graph.add_call("f", None);
+    call_graph.add_call("g", None);
+    call_graph.add_ret(None);
+    call_graph.add_ret(None);
+    call_graph.flush(true);
+
+    #[rustfmt::skip]
+    test_assert!(log, format!(concat!(
+        "{0}+-f()\n",
+        "{0}| +-g()\n",
+        "{0}| `-return\n",
+    ), expected_thread_prefix()));
+}
+
+#[test]
+fn timestamp_prefixes() {
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    let decorator = Rc::new(RefCell::new(CodeLikeDecorator::new(None, None)));
+    decorator.borrow_mut().set_writer(log.clone());
+    decorator.borrow_mut().set_line_prefixes(LinePrefixes {
+        elapsed: true,
+        wall_clock: true,
+        thread: false,
+    });
+    let mut call_graph = CallGraph::new(decorator);
+
+    call_graph.add_call("f", None);
+    call_graph.add_ret(None);
+    call_graph.flush(true);
+
+    // E.g. "[+0.000123s] [12:34:56.789] f() {}\n".
+    let log_contents = String::from_utf8(log.borrow().clone()).unwrap();
+    let (elapsed, rest) = log_contents.split_once("s] ").unwrap();
+    let elapsed = elapsed.strip_prefix("[+").unwrap();
+    assert!(elapsed.parse::<f64>().is_ok(), "{}", log_contents);
+    assert_eq!(elapsed.split_once('.').unwrap().1.len(), 6);
+    let (wall_clock, rest) = rest.split_once("] ").unwrap();
+    let wall_clock = wall_clock.strip_prefix('[').unwrap();
+    assert_eq!(wall_clock.len(), "12:34:56.789".len(), "{}", log_contents);
+    assert!(wall_clock.chars().all(|character| character.is_ascii_digit() || ":.".contains(character)));
+    assert_eq!(rest, "f() {}\n");
+}
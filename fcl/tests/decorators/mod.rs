@@ -0,0 +1,14 @@
+mod json_lines;
+mod chrome_trace;
+mod folded_stack;
+mod html;
+mod ansi_colors;
+mod sequence_diagram;
+mod dot_graph;
+mod line_prefixes;
+mod tee;
+#[cfg(feature = "tracing_bridge")]
+mod tracing_bridge;
+mod tree_like;
+mod binary_trace;
+mod thread_headers;
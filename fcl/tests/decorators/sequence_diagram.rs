@@ -0,0 +1,97 @@
+use fcl::common::decorators::SequenceDiagramDecorator;
+
+use crate::common::*;
+
+// High-level logic to test:
+// The `SequenceDiagramDecorator` logs the Mermaid sequence diagram where the thread is a lifeline,
+// the calls and returns activate and deactivate the lifeline, and the repeat counts are the `loop` blocks.
+//
+// Test cases:
+//
+// A: `calls_returns_and_repeat_count`:
+// f(x: 1) {
+//   g() {}
+//   // g() repeats 2 time(s).
+// } -> 5 // f().
+// Assert: The diagram start and the participant are logged first, the repeats are a `loop` block.
+//
+// B: `loop_bodies_and_sequence`:
+// { // Loop body start.
+//   a() {}
+// } // Loop body end.
+// { // Loop body start.
+//   b() {}
+// } // Loop body end.
+// // (Loop body, Loop body) repeats 1 time(s).
+// Assert: The loop bodies are the notes, the sequence repeats are a `loop` block.
+
+/// Returns the diagram start and the participant declaration expected for the current thread.
+fn diagram_start() -> String {
+    format!(
+        "sequenceDiagram\n  participant {} as {}\n",
+        participant(),
+        std::thread::current().name().unwrap_or_default()
+    )
+}
+
+/// Returns the participant ID expected for the current thread.
+fn participant() -> String {
+    format!("T{}", current_thread_number())
+}
+
+#[test]
+fn calls_returns_and_repeat_count() {
+    let (mut call_graph, log) = decorator_call_graph(SequenceDiagramDecorator::new(None));
+
+    call_graph.add_call("f", Some(String::from("x: 1")));
+    for _ in 0..3 {
+        call_graph.add_call("g", None);
+        call_graph.add_ret(None);
+    }
+    call_graph.add_ret(Some(String::from("5")));
+    call_graph.flush(true);
+
+    #[rustfmt::skip]
+    test_assert!(log, diagram_start() + &format!(concat!(
+        "  {0}->>+{0}: f(x: 1)\n",
+        "    {0}->>+{0}: g()\n",
+        "    {0}-->>-{0}: return\n",
+        "    loop 2 more time(s)\n",
+        "      {0}->>{0}: g()\n",
+        "    end\n",
+        "  {0}-->>-{0}: -> 5\n",
+    ), participant()));
+}
+
+#[test]
+fn loop_bodies_and_sequence() {
+    let (mut call_graph, log) = decorator_call_graph(SequenceDiagramDecorator::new(None));
+    call_graph.set_max_sequence_len(4); // The periodic sequence folding is off by default.
+
+    for _ in 0..2 {
+        for name in ["a", "b"] {
+            call_graph.add_loopbody_start();
+            call_graph.add_call(name, None);
+            call_graph.add_ret(None);
+            call_graph.add_loopbody_end();
+        }
+    }
+    call_graph.add_loop_end();
+    call_graph.flush(true);
+
+    #[rustfmt::skip]
+    test_assert!(log, diagram_start() + &format!(concat!(
+        "  Note right of {0}: Loop body start\n",
+        "    {0}->>+{0}: a()\n",
+        "    {0}-->>-{0}: return\n",
+        "  Note right of {0}: Loop body end\n",
+        "  Note right of {0}: Loop body start\n",
+        "    {0}->>+{0}: b()\n",
+        "    {0}-->>-{0}: return\n",
+        "  Note right of {0}: Loop body end\n",
+        "  loop 1 more time(s)\n",
+        "    Note right of {0}: Loop body\n",
+        "    Note right of {0}: Loop body\n",
+        "  end\n",
+    ), participant()));
+}
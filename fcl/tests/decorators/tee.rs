@@ -0,0 +1,75 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use code_commons::CallGraph;
+use fcl::common::decorators::{
+    CodeLikeDecorator, JsonLinesDecorator, LogDecorator, TeeDecorator, WriterPossessor,
+};
+
+use crate::common::*;
+
+// High-level logic to test:
+// The `TeeDecorator` forwards each notification to each of its child decorators,
+// such that each child logs the same calls in its own format to its own writer.
+//
+// Test cases:
+//
+// A: `children_log_to_their_writers`:
+// f() {
+//   g() {}
+//   // g() repeats 1 time(s).
+// } // f().
+// Assert: The code-like child and the JSON lines child log the same calls to their own writers.
+//
+// B: `set_writer_is_forwarded`:
+// f() {}
+// Assert: The child added with `add_child()` logs to the writer set to the `TeeDecorator`.
+
+#[test]
+fn children_log_to_their_writers() {
+    // Mock log writers creation and substitution of the default ones:
+    let code_like_log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    let code_like = Rc::new(RefCell::new(CodeLikeDecorator::new(None, None)));
+    code_like.borrow_mut().set_writer(code_like_log.clone());
+    let json_lines_log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    let json_lines = Rc::new(RefCell::new(JsonLinesDecorator::new(None)));
+    json_lines.borrow_mut().set_writer(json_lines_log.clone());
+
+    let children: Vec<Rc<RefCell<dyn LogDecorator>>> = vec![code_like, json_lines];
+    let mut call_graph = CallGraph::new(Rc::new(RefCell::new(TeeDecorator::new(children))));
+
+    call_graph.add_call("f", None);
+    for _ in 0..2 {
+        call_graph.add_call("g", None);
+        call_graph.add_ret(None);
+    }
+    call_graph.add_ret(None);
+    call_graph.flush(true);
+
+    #[rustfmt::skip]
+    test_assert!(code_like_log, concat!(
+        "f() {\n",
+        "  g() {}\n",
+        "  // g() repeats 1 time(s).\n",
+        "} // f().\n",
+    ));
+    let json_lines = String::from_utf8(json_lines_log.borrow().clone()).unwrap();
+    let events: Vec<&str> = json_lines
+        .lines()
+        .map(|line| line.split("\"event\":\"").nth(1).unwrap().split('"').next().unwrap())
+        .collect();
+    assert_eq!(events, ["call", "call", "return", "repeat_count", "return"], "{}", json_lines);
+}
+
+#[test]
+fn set_writer_is_forwarded() {
+    let mut decorator = TeeDecorator::new(Vec::new());
+    decorator.add_child(Rc::new(RefCell::new(CodeLikeDecorator::new(None, None))));
+    let (mut call_graph, log) = decorator_call_graph(decorator);
+
+    call_graph.add_call("f", None);
+    call_graph.add_ret(None);
+    call_graph.flush(true);
+
+    test_assert!(log, "f() {}\n");
+}
@@ -0,0 +1,160 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use fcl::common::CallLogger;
+use fcl::common::call_log_infra::CallLogInfra;
+use fcl::common::decorators::{
+    CodeLikeDecorator, JsonLinesDecorator, LogDecorator, TreeLikeDecorator, WriterPossessor,
+};
+
+use crate::common::*;
+
+// High-level logic to test:
+// If the thread headers are on then the decorators notified about the thread start and end
+// log the thread start header and thread end summary line (with the number of the thread's logged calls)
+// in the thread's "column". The `CallLogInfra` is driven directly (rather than by the `#[loggable]` functions)
+// since the `THREAD_DECORATOR` thread headers are off by default.
+//
+// Test cases:
+//
+// A: `code_like_headers`:
+//     ==== thread '<name>' (#<id>) started ====
+//     f() {
+//       g() {}
+//       // g() repeats 1 time(s).
+//     } // f().
+//     ==== thread '<name>' (#<id>) ended, 3 call(s) ====
+// Assert: The header and summary line are logged with the thread indent.
+//
+// B: `tree_like_pending_line`:
+// ==== thread '<name>' (#<id>) started ====
+// +-f()
+// ==== thread '<name>' (#<id>) ended, 1 call(s) ====
+// Assert: The summary line of the thread ended amid `f()` (e.g. panicked) starts on a new line.
+//
+// C: `json_lines_events`:
+// Assert: The `"thread_start"` and `"thread_end"` events are logged, the latter with the `"call_count"`.
+//
+// D: `headers_off_by_default`:
+// Assert: Nothing but the calls is logged.
+
+/// Returns the `'<name>' (#<id>)` fragment expected for the current thread.
+fn expected_thread() -> String {
+    let thread = std::thread::current();
+    let thread_id = format!("{:?}", thread.id());
+    format!(
+        "'{}' (#{})",
+        thread.name().unwrap_or_default(),
+        thread_id.trim_start_matches("ThreadId(").trim_end_matches(')')
+    )
+}
+
+/// Logs the call of `name`.
+fn call(logger: &mut CallLogInfra, name: &str) {
+    logger.log_call(
+        name,
+        #[cfg(feature = "params_logging")]
+        None,
+    );
+}
+
+/// Logs the return.
+fn ret(logger: &mut CallLogInfra) {
+    logger.log_ret(
+        #[cfg(feature = "ret_val_logging")]
+        None,
+    );
+}
+
+#[test]
+fn code_like_headers() {
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    let decorator = Rc::new(RefCell::new(CodeLikeDecorator::new(None, None)));
+    decorator.borrow_mut().set_writer(log.clone());
+    decorator.borrow_mut().set_thread_headers(true);
+    let decorator: Rc<RefCell<dyn LogDecorator>> = decorator;
+
+    let mut logger = CallLogInfra::new(decorator);
+    logger.set_thread_indent(String::from("    "));
+    logger.log_thread_start();
+    call(&mut logger, "f");
+    for _ in 0..2 {
+        call(&mut logger, "g");
+        ret(&mut logger);
+    }
+    ret(&mut logger);
+    logger.flush();
+    logger.log_thread_end();
+
+    #[rustfmt::skip]
+    test_assert!(log, format!(concat!(
+        "    ==== thread {0} started ====\n",
+        "    f() {{\n",
+        "      g() {{}}\n",
+        "      // g() repeats 1 time(s).\n",
+        "    }} // f().\n",
+        "    ==== thread {0} ended, 3 call(s) ====\n",
+    ), expected_thread()));
+}
+
+#[test]
+fn tree_like_pending_line() {
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    let decorator = Rc::new(RefCell::new(TreeLikeDecorator::new(None, None, None, None)));
+    decorator.borrow_mut().set_writer(log.clone());
+    decorator.borrow_mut().set_thread_headers(true);
+    let decorator: Rc<RefCell<dyn LogDecorator>> = decorator;
+
+    let mut logger = CallLogInfra::new(decorator);
+    logger.log_thread_start();
+    call(&mut logger, "f");
+    logger.flush();
+    logger.log_thread_end();
+
+    #[rustfmt::skip]
+    test_assert!(log, format!(concat!(
+        "==== thread {0} started ====\n",
+        "+-f()\n",
+        "==== thread {0} ended, 1 call(s) ====\n",
+    ), expected_thread()));
+}
+
+#[test]
+fn json_lines_events() {
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    let decorator = Rc::new(RefCell::new(JsonLinesDecorator::new(None)));
+    decorator.borrow_mut().set_writer(log.clone());
+    decorator.borrow_mut().set_thread_headers(true);
+    let decorator: Rc<RefCell<dyn LogDecorator>> = decorator;
+
+    let mut logger = CallLogInfra::new(decorator);
+    logger.log_thread_start();
+    logger.log_thread_end();
+
+    let log = String::from_utf8(log.borrow().clone()).unwrap();
+    let lines: Vec<&str> = log.lines().collect();
+    assert_eq!(lines.len(), 2, "{}", log);
+    assert!(lines[0].ends_with(r#","event":"thread_start","depth":0}"#), "{}", log);
+    assert!(lines[1].ends_with(r#","event":"thread_end","depth":0,"call_count":0}"#), "{}", log);
+}
+
+#[test]
+fn headers_off_by_default() {
+    // Mock log writer creation and substitution of the default one:
+    let log = Rc::new(RefCell::new(Vec::with_capacity(1024)));
+    let decorator = Rc::new(RefCell::new(CodeLikeDecorator::new(None, None)));
+    decorator.borrow_mut().set_writer(log.clone());
+    let decorator: Rc<RefCell<dyn LogDecorator>> = decorator;
+
+    let mut logger = CallLogInfra::new(decorator);
+    logger.log_thread_start();
+    call(&mut logger, "f");
+    ret(&mut logger);
+    logger.flush();
+    logger.log_thread_end();
+
+    test_assert!(log, "f() {}\n");
+}
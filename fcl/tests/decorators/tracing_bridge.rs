@@ -0,0 +1,154 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicU64, Ordering},
+};
+
+use code_commons::CallGraph;
+use fcl::common::decorators::TracingDecorator;
+use tracing::{
+    Event, Metadata, Subscriber,
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+};
+
+// High-level logic to test:
+// The `TracingDecorator` turns the calls and loop bodies into the `tracing` spans
+// (entered upon the call and exited upon the return), and the repeat counts into the `tracing` events.
+// The `tracing` notifications are recorded by the mock subscriber.
+//
+// Test cases:
+//
+// A: `calls_become_spans`:
+// f(x: 1) {
+//   g() {}
+//   // g() repeats 1 time(s).
+// } -> 5 // f().
+// Assert: The calls are the nested spans with the parameters and the return value as fields,
+// the repeat count is an event.
+//
+// B: `loop_bodies_become_spans`:
+// { // Loop body start.
+//   a() {}
+// } // Loop body end.
+// Assert: The loop body is a span containing the call span.
+
+/// The mock subscriber recording the `tracing` notifications as strings.
+struct RecordingSubscriber {
+    /// The recorded notifications (e.g. `"new_span call name=f"`).
+    records: Arc<Mutex<Vec<String>>>,
+    /// The ID of the next span.
+    next_id: AtomicU64,
+}
+
+/// The visitor formatting the fields as ` name=value` pairs.
+struct FieldsFormatter(String);
+
+impl Visit for FieldsFormatter {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0 += &format!(" {}={}", field.name(), value);
+    }
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0 += &format!(" {}={:?}", field.name(), value);
+    }
+}
+
+impl Subscriber for RecordingSubscriber {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut fields = FieldsFormatter(String::new());
+        span.record(&mut fields);
+        self.records.lock().unwrap().push(format!(
+            "new_span {} target={}{}",
+            span.metadata().name(),
+            span.metadata().target(),
+            fields.0
+        ));
+        Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed))
+    }
+    fn record(&self, _span: &Id, values: &Record<'_>) {
+        let mut fields = FieldsFormatter(String::new());
+        values.record(&mut fields);
+        self.records.lock().unwrap().push(format!("record{}", fields.0));
+    }
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = FieldsFormatter(String::new());
+        event.record(&mut fields);
+        self.records.lock().unwrap().push(format!("event{}", fields.0));
+    }
+    fn enter(&self, span: &Id) {
+        self.records.lock().unwrap().push(format!("enter {}", span.into_u64()));
+    }
+    fn exit(&self, span: &Id) {
+        self.records.lock().unwrap().push(format!("exit {}", span.into_u64()));
+    }
+}
+
+/// Runs the `body` driving the call graph notifying the `TracingDecorator`,
+/// and returns the notifications recorded by the subscriber.
+fn recorded_notifications(body: impl FnOnce(&mut CallGraph)) -> Vec<String> {
+    let records = Arc::new(Mutex::new(Vec::new()));
+    let subscriber = RecordingSubscriber {
+        records: records.clone(),
+        next_id: AtomicU64::new(1),
+    };
+    tracing::subscriber::with_default(subscriber, || {
+        let mut call_graph = CallGraph::new(Rc::new(RefCell::new(TracingDecorator::new())));
+        body(&mut call_graph);
+        call_graph.flush(true);
+    });
+    records.lock().unwrap().clone()
+}
+
+#[test]
+fn calls_become_spans() {
+    let records = recorded_notifications(|call_graph| {
+        call_graph.add_call("f", Some(String::from("x: 1")));
+        for _ in 0..2 {
+            call_graph.add_call("g", None);
+            call_graph.add_ret(None);
+        }
+        call_graph.add_ret(Some(String::from("5")));
+    });
+
+    assert_eq!(
+        records,
+        [
+            "new_span call target=fcl name=f params=x: 1",
+            "enter 1",
+            "new_span call target=fcl name=g",
+            "enter 2",
+            "exit 2",
+            "event message=g() repeats 1 time(s). item=g() count=1",
+            "record ret_val=5",
+            "exit 1",
+        ]
+    );
+}
+
+#[test]
+fn loop_bodies_become_spans() {
+    let records = recorded_notifications(|call_graph| {
+        call_graph.add_loopbody_start();
+        call_graph.add_call("a", None);
+        call_graph.add_ret(None);
+        call_graph.add_loopbody_end();
+        call_graph.add_loop_end();
+    });
+
+    assert_eq!(
+        records,
+        [
+            "new_span loop_body target=fcl",
+            "enter 1",
+            "new_span call target=fcl name=a",
+            "enter 2",
+            "exit 2",
+            "exit 1",
+        ]
+    );
+}
@@ -0,0 +1,112 @@
+use fcl::common::decorators::{TreeLikeDecorator, TreeStyle};
+
+use crate::common::*;
+
+// High-level logic to test:
+// The `TreeLikeDecorator` logs the return values, the loop body boundaries, and the repeat counts.
+// The return (or loop body end) line is the last child of the call (or loop body) node,
+// the call with no nested calls is logged with a single line.
+//
+// Test cases:
+//
+// A: `returns_and_repeat_counts`:
+// +-f(x: 1)
+// | +-g() -> 2
+// |   g repeats 1 time(s).
+// | +-h()
+// | | +-g() -> 2
+// | `-return
+// | `-return -> 5
+// Assert: The return values and the repeat counts are logged, the returns are the last children.
+//
+// B: `loop_bodies`:
+// +-Loop body
+// | +-a()
+// | `-Loop body end
+//   Loop body repeats 1 time(s).
+// Assert: The loop body end is the last child of the loop body.
+//
+// C: `unicode_style`:
+// ├─f()
+// │ ├─g() -> 2
+// │ │ g repeats 1 time(s).
+// │ └─return -> 5
+// Assert: The box-drawing characters are used.
+
+/// Creates the `TreeLikeDecorator` of the `style`.
+fn tree_like_decorator(style: TreeStyle) -> TreeLikeDecorator {
+    let mut decorator = TreeLikeDecorator::new(None, None, None, None);
+    decorator.set_style(style);
+    decorator
+}
+
+#[test]
+fn returns_and_repeat_counts() {
+    let (mut call_graph, log) = decorator_call_graph(tree_like_decorator(TreeStyle::Ascii));
+
+    call_graph.add_call("f", Some(String::from("x: 1")));
+    for _ in 0..2 {
+        call_graph.add_call("g", None);
+        call_graph.add_ret(Some(String::from("2")));
+    }
+    call_graph.add_call("h", None);
+    call_graph.add_call("g", None);
+    call_graph.add_ret(Some(String::from("2")));
+    call_graph.add_ret(None);
+    call_graph.add_ret(Some(String::from("5")));
+    call_graph.flush(true);
+
+    #[rustfmt::skip]
+    test_assert!(log, concat!(
+        "+-f(x: 1)\n",
+        "| +-g() -> 2\n",
+        "|   g repeats 1 time(s).\n",
+        "| +-h()\n",
+        "| | +-g() -> 2\n",
+        "| | `-return\n",
+        "| `-return -> 5\n",
+    ));
+}
+
+#[test]
+fn loop_bodies() {
+    let (mut call_graph, log) = decorator_call_graph(tree_like_decorator(TreeStyle::Ascii));
+
+    for _ in 0..2 {
+        call_graph.add_loopbody_start();
+        call_graph.add_call("a", None);
+        call_graph.add_ret(None);
+        call_graph.add_loopbody_end();
+    }
+    call_graph.add_loop_end();
+    call_graph.flush(true);
+
+    #[rustfmt::skip]
+    test_assert!(log, concat!(
+        "+-Loop body\n",
+        "| +-a()\n",
+        "| `-Loop body end\n",
+        "  Loop body repeats 1 time(s).\n",
+    ));
+}
+
+#[test]
+fn unicode_style() {
+    let (mut call_graph, log) = decorator_call_graph(tree_like_decorator(TreeStyle::Unicode));
+
+    call_graph.add_call("f", None);
+    for _ in 0..2 {
+        call_graph.add_call("g", None);
+        call_graph.add_ret(Some(String::from("2")));
+    }
+    call_graph.add_ret(Some(String::from("5")));
+    call_graph.flush(true);
+
+    #[rustfmt::skip]
+    test_assert!(log, concat!(
+        "├─f()\n",
+        "│ ├─g() -> 2\n",
+        "│ │ g repeats 1 time(s).\n",
+        "│ └─return -> 5\n",
+    ));
+}
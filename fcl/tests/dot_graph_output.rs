@@ -0,0 +1,93 @@
+// The separate test binary (process) for writing the static call graph upon the drop of the last `DotGraphDecorator`,
+// that depends on the number of the `DotGraphDecorator`s alive in the process and on the settings shared by all the threads
+// (the tests of `tests/mod.rs` share the process and create the `DotGraphDecorator`s in parallel).
+
+use std::{
+    cell::RefCell,
+    fs::File,
+    io::Write,
+    path::PathBuf,
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+use code_commons::CallGraph;
+use fcl::common::decorators::DotGraphDecorator;
+use fcl::common::settings::set_dot_graph_output;
+
+// High-level logic to test:
+// The graph is written upon the drop of the last `DotGraphDecorator` alive,
+// to the output set with `set_dot_graph_output()` or (if not set) to the writer of that decorator.
+//
+// Test cases:
+//
+// A: `graph_is_written_upon_last_drop`:
+// 2 decorators (with their own writers) log `dot_f() {}` and `dot_g() {}`, are dropped one after another.
+// Assert: With the output not set, the graph is written to the writer of the last dropped decorator only.
+// Then the same with the output set to a file.
+// Assert: The graph is written to the file only.
+
+/// The writer to the log shared by the test and the decorator.
+#[derive(Clone, Default)]
+struct SharedLog(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedLog {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SharedLog {
+    /// Returns the log contents.
+    fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+/// Returns the path of the graph output's file.
+fn dot_graph_file_path() -> PathBuf {
+    std::env::temp_dir().join(format!("fcl_dot_graph_output_{}.dot", std::process::id()))
+}
+
+/// Creates the call graph notifying the `DotGraphDecorator` that writes to the returned log, logs the call.
+fn log_call(name: &str) -> (CallGraph, SharedLog) {
+    let log = SharedLog::default();
+    let mut call_graph = CallGraph::new(Rc::new(RefCell::new(DotGraphDecorator::new(Some(Box::new(log.clone()))))));
+    call_graph.add_call(name, None);
+    call_graph.add_ret(None);
+    call_graph.flush(true);
+    (call_graph, log)
+}
+
+#[test]
+fn graph_is_written_upon_last_drop() {
+    // The output is not set:
+    let (first_call_graph, first_log) = log_call("dot_f");
+    let (second_call_graph, second_log) = log_call("dot_g");
+    drop(second_call_graph);
+    assert_eq!(second_log.contents(), "");
+    drop(first_call_graph);
+    let graph = first_log.contents();
+    assert!(graph.starts_with("digraph fcl {\n"), "{}", graph);
+    assert!(graph.contains("  \"dot_f\" [label=\"dot_f\\n1 call(s)\"];\n"), "{}", graph);
+    assert!(graph.contains("  \"dot_g\" [label=\"dot_g\\n1 call(s)\"];\n"), "{}", graph);
+    assert_eq!(second_log.contents(), "");
+
+    // The output is set:
+    set_dot_graph_output(Some(|| Box::new(File::create(dot_graph_file_path()).unwrap())));
+    let (first_call_graph, first_log) = log_call("dot_f");
+    let (second_call_graph, second_log) = log_call("dot_g");
+    drop(first_call_graph);
+    drop(second_call_graph);
+    assert_eq!(first_log.contents(), "");
+    assert_eq!(second_log.contents(), "");
+    let graph = std::fs::read_to_string(dot_graph_file_path()).unwrap();
+    let _ignore_error = std::fs::remove_file(dot_graph_file_path());
+    assert!(graph.starts_with("digraph fcl {\n"), "{}", graph);
+    assert!(graph.contains("  \"dot_f\" [label=\"dot_f\\n2 call(s)\"];\n"), "{}", graph);
+    assert!(graph.contains("  \"dot_g\" [label=\"dot_g\\n2 call(s)\"];\n"), "{}", graph);
+}
@@ -2,4 +2,8 @@
 // //#![cfg(feature = "idle")]
 mod common;
 mod call_graph;
-mod proc_macros;
\ No newline at end of file
+mod proc_macros;
+mod decorators;
+mod writers;
+mod config;
+mod builder;
\ No newline at end of file
@@ -0,0 +1,101 @@
+// The separate test binary (process) for the merge of the logs of the threads updating their call graphs in parallel,
+// that writes to the writer shared by all the threads and set with `fcl::Builder::init()` before the first logged call
+// (the tests of `tests/mod.rs` share the process and make the logged calls in parallel).
+#![cfg(feature = "parallel_logging")]
+
+use std::{
+    io::Write,
+    sync::{
+        Arc, Mutex,
+        mpsc::{Receiver, Sender, channel},
+    },
+};
+
+use fcl::Builder;
+use fcl::common::call_log_infra::FclWriter;
+
+// High-level logic to test:
+// With the "parallel_logging" feature the threads update their own call graphs in parallel,
+// and their output is merged in the order of the IDs of the call graph updates that produced it.
+//
+// Test cases:
+//
+// A: `merge_in_update_order`:
+// 2 threads take turns (one turn at a time) making the logged calls, the second thread's turns produce enough output
+// for the threshold merge, the threads end.
+// Assert: The calls in the log are ordered by the turns, i.e. by the update IDs,
+// regardless of which thread's merge has written them.
+
+/// The writer to the log shared by the test and the FCL.
+#[derive(Clone, Default)]
+struct SharedLog(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedLog {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The logged calls made by the threads in their turns
+/// (the different functions, such that the thread's calls are not folded as repeated ones).
+#[rustfmt::skip]
+mod turns {
+    use fcl_proc_macros::loggable;
+
+    #[loggable] pub fn turn_0(_padding: &str) {}
+    #[loggable] pub fn turn_1(_padding: &str) {}
+    #[loggable] pub fn turn_2(_padding: &str) {}
+    #[loggable] pub fn turn_3(_padding: &str) {}
+    #[loggable] pub fn turn_4(_padding: &str) {}
+    #[loggable] pub fn turn_5(_padding: &str) {}
+    #[loggable] pub fn turn_6(_padding: &str) {}
+    #[loggable] pub fn turn_7(_padding: &str) {}
+}
+use turns::*;
+
+/// Makes the thread's turns: waits for the turn, makes the logged call, and passes the turn to the other thread.
+fn take_turns(turns: [fn(&str); 4], padding: String, my_turn: Receiver<()>, other_turn: Sender<()>) {
+    for turn in turns {
+        my_turn.recv().unwrap();
+        turn(&padding);
+        // The other thread may have ended after its last turn:
+        let _ignore_error = other_turn.send(());
+    }
+}
+
+#[test]
+fn merge_in_update_order() {
+    let log = SharedLog::default();
+    let result = Builder::new()
+        .writer(FclWriter::Other(Box::new(log.clone())))
+        .thread_indent_step(String::new())
+        .thread_headers(false)
+        .init();
+    assert!(result.is_ok());
+
+    let (to_even, even_turn) = channel();
+    let (to_odd, odd_turn) = channel();
+    let even_thread =
+        std::thread::spawn(move || take_turns([turn_0, turn_2, turn_4, turn_6], String::new(), even_turn, to_odd));
+    // The long parameter value makes the output of the odd turns large enough for the threshold merge:
+    let padding = "x".repeat(32 * 1024);
+    let odd_thread = std::thread::spawn({
+        let to_even = to_even.clone();
+        move || take_turns([turn_1, turn_3, turn_5, turn_7], padding, odd_turn, to_even)
+    });
+    to_even.send(()).unwrap();
+    even_thread.join().unwrap();
+    odd_thread.join().unwrap();
+
+    let log = String::from_utf8(log.0.lock().unwrap().clone()).unwrap();
+    let turns: Vec<usize> = log
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix("turn_"))
+        .map(|rest| rest[..1].parse().unwrap())
+        .collect();
+    assert_eq!(turns, (0..8).collect::<Vec<_>>(), "The log:\n{}", log);
+}
@@ -0,0 +1,68 @@
+// The separate test binary (process) for the colors of the additional log outputs,
+// that are added to the settings shared by all the threads
+// (the tests of `tests/mod.rs` share the process and would log to the additional outputs too).
+
+use std::{
+    fs::File,
+    io::Write,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use code_commons::CallGraph;
+use fcl::common::decorators::{AnsiColors, DecoratorKind, new_decorator};
+use fcl::common::settings::{add_tee_output, set_ansi_colors};
+
+// High-level logic to test:
+// With `AnsiColors::Auto` the colors are used only if the writer is a terminal.
+// The writers of the additional log outputs (e.g. files) are not terminals.
+//
+// Test cases:
+//
+// A: `tee_file_is_not_colored`:
+// f() {}
+// The decorator is created for a terminal writer, with an additional code-like output to a file.
+// Assert: The terminal writer's log is colored (unless `NO_COLOR` is set), the file's log contains no ANSI escapes.
+
+/// The writer to the log shared by the test and the decorator.
+#[derive(Clone, Default)]
+struct SharedLog(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedLog {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Returns the path of the additional output's file.
+fn tee_file_path() -> PathBuf {
+    std::env::temp_dir().join(format!("fcl_tee_colors_{}.log", std::process::id()))
+}
+
+#[test]
+fn tee_file_is_not_colored() {
+    set_ansi_colors(AnsiColors::Auto);
+    add_tee_output(DecoratorKind::CodeLike, || Box::new(File::create(tee_file_path()).unwrap()));
+
+    let log = SharedLog::default();
+    let decorator = new_decorator(Some(Box::new(log.clone())), true);
+    let mut call_graph = CallGraph::new(decorator);
+    call_graph.add_call("f", None);
+    call_graph.add_ret(None);
+    call_graph.flush(true);
+    drop(call_graph);
+
+    let log = String::from_utf8(log.0.lock().unwrap().clone()).unwrap();
+    if std::env::var_os("NO_COLOR").is_none() {
+        assert!(log.contains("\x1b["), "The log:\n{:?}", log);
+    }
+    let tee_log = std::fs::read_to_string(tee_file_path()).unwrap();
+    let _ignore_error = std::fs::remove_file(tee_file_path());
+    assert!(!tee_log.contains("\x1b["), "The additional output's log:\n{:?}", tee_log);
+    assert_eq!(tee_log, "f() {}\n");
+}
+
@@ -0,0 +1,7 @@
+mod ring_buffer_writer;
+mod rotating_file_writer;
+#[cfg(unix)]
+mod unix_socket_writer;
+mod thread_log_file;
+#[cfg(feature = "parallel_logging")]
+mod update_log;
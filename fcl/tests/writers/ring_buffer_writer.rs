@@ -0,0 +1,86 @@
+use std::io::Write;
+
+use fcl::common::ring_buffer_writer::RingBufferWriter;
+
+// High-level logic to test:
+// The `RingBufferWriter` keeps in memory the last `capacity` bytes written to it
+// (the oldest bytes are overwritten with the new ones), and dumps them starting with the first complete line
+// between the dump start and end notes.
+//
+// Test cases:
+//
+// A: `keeps_everything_within_capacity`:
+// 2 lines of 6 bytes with the 100-byte capacity.
+// Assert: The dump has both lines.
+//
+// B: `overwrites_oldest`:
+// 4 lines of 6 bytes with the 15-byte capacity.
+// Assert: The dump has the last 2 lines (the incomplete 3rd line from the end is skipped).
+//
+// C: `keeps_tail_of_large_write`:
+// A write larger than the capacity.
+// Assert: The dump has the complete lines of the tail.
+//
+// D: `clones_share_ring`:
+// A line written to a clone.
+// Assert: The dump of the original has the line.
+
+/// Returns the dump of the `ring` as a string.
+fn dump(ring: &RingBufferWriter) -> String {
+    let mut log = Vec::new();
+    ring.dump(&mut log).unwrap();
+    String::from_utf8(log).unwrap()
+}
+
+#[test]
+fn keeps_everything_within_capacity() {
+    let mut ring = RingBufferWriter::new(100, None);
+    ring.write_all(b"f() {}\n").unwrap();
+    ring.write_all(b"g() {}\n").unwrap();
+    assert_eq!(
+        dump(&ring),
+        "// FCL: ring buffer dump start.\n\
+        f() {}\n\
+        g() {}\n\
+        // FCL: ring buffer dump end.\n"
+    );
+}
+
+#[test]
+fn overwrites_oldest() {
+    let mut ring = RingBufferWriter::new(15, None);
+    for line in ["line1\n", "line2\n", "line3\n", "line4\n"] {
+        ring.write_all(line.as_bytes()).unwrap();
+    }
+    assert_eq!(
+        dump(&ring),
+        "// FCL: ring buffer dump start.\n\
+        line3\n\
+        line4\n\
+        // FCL: ring buffer dump end.\n"
+    );
+}
+
+#[test]
+fn keeps_tail_of_large_write() {
+    let mut ring = RingBufferWriter::new(10, None);
+    ring.write_all(b"line1\nline2\nline3\n").unwrap();
+    assert_eq!(
+        dump(&ring),
+        "// FCL: ring buffer dump start.\n\
+        line3\n\
+        // FCL: ring buffer dump end.\n"
+    );
+}
+
+#[test]
+fn clones_share_ring() {
+    let ring = RingBufferWriter::new(100, None);
+    ring.clone().write_all(b"f() {}\n").unwrap();
+    assert_eq!(
+        dump(&ring),
+        "// FCL: ring buffer dump start.\n\
+        f() {}\n\
+        // FCL: ring buffer dump end.\n"
+    );
+}
@@ -0,0 +1,152 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use fcl::common::rotating_file_writer::{FileRotation, RotatingFileWriter};
+
+// High-level logic to test:
+// The `RotatingFileWriter` writes to the current file (e.g. `fcl.log`) and, when the size or age limit is reached,
+// renames it to `fcl.log.1` (the previous `fcl.log.1` to `fcl.log.2`, etc.), keeping up to N generations.
+// The rotation happens only at the line starts.
+//
+// Test cases:
+//
+// A: `rotates_by_size`:
+// 3 lines of 6 bytes with the 10-byte size limit.
+// Assert: `fcl.log` has the 3rd line, `fcl.log.1` has the 2nd line, `fcl.log.2` has the 1st line.
+//
+// B: `keeps_generations`:
+// 4 lines of 6 bytes with the 10-byte size limit and 2 generations.
+// Assert: `fcl.log`, `fcl.log.1`, `fcl.log.2` have the last 3 lines, `fcl.log.3` is absent.
+//
+// C: `does_not_split_lines`:
+// A line written with multiple `write()` calls exceeding the size limit.
+// Assert: The line is written to the same file.
+//
+// D: `rotates_by_age`:
+// A line, a pause longer than the age limit, a line.
+// Assert: The lines are in different files.
+//
+// E: `appends_to_existing`:
+// The writer is created for the existing file.
+// Assert: The file content is preserved and the size counts towards the limit.
+
+/// Creates a unique empty directory for a test case and returns the path of `fcl.log` in it.
+fn log_path(test_case: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "fcl_rotating_file_writer_{}_{}",
+        test_case,
+        std::process::id()
+    ));
+    let _ignore_absence = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir.join("fcl.log")
+}
+
+/// Returns the content of the rotated file of the `generation` (`0` for the current file),
+/// or `None` if the file is absent.
+fn content(path: &Path, generation: usize) -> Option<String> {
+    let mut path = path.to_path_buf().into_os_string();
+    if generation != 0 {
+        path.push(format!(".{}", generation));
+    }
+    fs::read_to_string(path).ok()
+}
+
+/// Removes the test case directory.
+fn clean_up(path: &Path) {
+    let _ignore_error = fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn rotates_by_size() {
+    let path = log_path("rotates_by_size");
+    {
+        let rotation = FileRotation { max_size: Some(10), max_age: None, generations: 5 };
+        let mut writer = RotatingFileWriter::new(&path, rotation).unwrap();
+        writer.write_all(b"line1\n").unwrap();
+        writer.write_all(b"line2\n").unwrap();
+        writer.write_all(b"line3\n").unwrap();
+        writer.flush().unwrap();
+    }
+    assert_eq!(content(&path, 0).as_deref(), Some("line3\n"));
+    assert_eq!(content(&path, 1).as_deref(), Some("line2\n"));
+    assert_eq!(content(&path, 2).as_deref(), Some("line1\n"));
+    assert_eq!(content(&path, 3), None);
+    clean_up(&path);
+}
+
+#[test]
+fn keeps_generations() {
+    let path = log_path("keeps_generations");
+    {
+        let rotation = FileRotation { max_size: Some(10), max_age: None, generations: 2 };
+        let mut writer = RotatingFileWriter::new(&path, rotation).unwrap();
+        for line in ["line1\n", "line2\n", "line3\n", "line4\n"] {
+            writer.write_all(line.as_bytes()).unwrap();
+        }
+        writer.flush().unwrap();
+    }
+    assert_eq!(content(&path, 0).as_deref(), Some("line4\n"));
+    assert_eq!(content(&path, 1).as_deref(), Some("line3\n"));
+    assert_eq!(content(&path, 2).as_deref(), Some("line2\n"));
+    assert_eq!(content(&path, 3), None);
+    clean_up(&path);
+}
+
+#[test]
+fn does_not_split_lines() {
+    let path = log_path("does_not_split_lines");
+    {
+        let rotation = FileRotation { max_size: Some(10), max_age: None, generations: 5 };
+        let mut writer = RotatingFileWriter::new(&path, rotation).unwrap();
+        writer.write_all(b"f() {").unwrap();
+        writer.write_all(b"} // f().").unwrap();
+        writer.write_all(b"\n").unwrap();
+        writer.write_all(b"g() {}\n").unwrap();
+        writer.flush().unwrap();
+    }
+    assert_eq!(content(&path, 0).as_deref(), Some("g() {}\n"));
+    assert_eq!(content(&path, 1).as_deref(), Some("f() {} // f().\n"));
+    assert_eq!(content(&path, 2), None);
+    clean_up(&path);
+}
+
+#[test]
+fn rotates_by_age() {
+    let path = log_path("rotates_by_age");
+    {
+        let rotation = FileRotation {
+            max_size: None,
+            max_age: Some(Duration::from_millis(50)),
+            generations: 5,
+        };
+        let mut writer = RotatingFileWriter::new(&path, rotation).unwrap();
+        writer.write_all(b"line1\n").unwrap();
+        std::thread::sleep(Duration::from_millis(100));
+        writer.write_all(b"line2\n").unwrap();
+        writer.flush().unwrap();
+    }
+    assert_eq!(content(&path, 0).as_deref(), Some("line2\n"));
+    assert_eq!(content(&path, 1).as_deref(), Some("line1\n"));
+    clean_up(&path);
+}
+
+#[test]
+fn appends_to_existing() {
+    let path = log_path("appends_to_existing");
+    fs::write(&path, "line1\n").unwrap();
+    {
+        let rotation = FileRotation { max_size: Some(14), max_age: None, generations: 5 };
+        let mut writer = RotatingFileWriter::new(&path, rotation).unwrap();
+        writer.write_all(b"line2\n").unwrap();
+        writer.write_all(b"line3\n").unwrap();
+        writer.flush().unwrap();
+    }
+    assert_eq!(content(&path, 0).as_deref(), Some("line3\n"));
+    assert_eq!(content(&path, 1).as_deref(), Some("line1\nline2\n"));
+    clean_up(&path);
+}
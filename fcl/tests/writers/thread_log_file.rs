@@ -0,0 +1,117 @@
+use std::{fs, io::Write, path::PathBuf};
+
+use fcl::common::thread_log_file::{ThreadLogFileWriter, thread_log_file_path};
+#[cfg(not(feature = "log_merger"))]
+use fcl_proc_macros::loggable;
+
+// High-level logic to test:
+// If the "log_merger" feature is off then each thread logs to its own file
+// named according to the pattern with the `{name}` and `{id}` placeholders.
+// The file is closed upon the thread's logger removal, after which the written data are discarded.
+//
+// Test cases:
+//
+// A: `expands_name_and_id`:
+// The pattern `fcl_{name}_{id}.log` for a named thread.
+// Assert: The placeholders are replaced with the thread name and the number of the thread ID.
+//
+// B: `unnamed_thread`:
+// The pattern `{name}.log` for a thread without a name.
+// Assert: The `{name}` is replaced with `unnamed`.
+//
+// C: `sanitizes_name`:
+// The pattern `{name}.log` for the thread named `a/b c`.
+// Assert: The characters other than alphanumerics, `-`, `_`, and `.` are replaced with `_`.
+//
+// D: `discards_after_close`:
+// A line written, the writer closed (its clone is still alive), a line written.
+// Assert: The file has the first line only.
+//
+// E: `each_thread_logs_to_own_file` (the "log_merger" feature is off):
+// The pattern in the temporary directory. 2 named threads make different logged calls.
+// Assert: Each thread's `fcl_{name}_{id}.log` file has only the calls of that thread.
+
+#[test]
+fn expands_name_and_id() {
+    let (path, id) = std::thread::Builder::new()
+        .name(String::from("T1"))
+        .spawn(|| {
+            let thread = std::thread::current();
+            let id = format!("{:?}", thread.id()).replace("ThreadId(", "").replace(')', "");
+            (thread_log_file_path("fcl_{name}_{id}.log", &thread), id)
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+    assert_eq!(path, PathBuf::from(format!("fcl_T1_{}.log", id)));
+}
+
+#[test]
+fn unnamed_thread() {
+    let path = std::thread::spawn(|| thread_log_file_path("{name}.log", &std::thread::current()))
+        .join()
+        .unwrap();
+    assert_eq!(path, PathBuf::from("unnamed.log"));
+}
+
+#[test]
+fn sanitizes_name() {
+    let path = std::thread::Builder::new()
+        .name(String::from("a/b c"))
+        .spawn(|| thread_log_file_path("{name}.log", &std::thread::current()))
+        .unwrap()
+        .join()
+        .unwrap();
+    assert_eq!(path, PathBuf::from("a_b_c.log"));
+}
+
+#[test]
+fn discards_after_close() {
+    let path = std::env::temp_dir().join(format!("fcl_thread_log_file_{}.log", std::process::id()));
+    {
+        let writer = ThreadLogFileWriter::create(&path).unwrap();
+        let mut decorator_writer = writer.clone();
+        decorator_writer.write_all(b"f() {}\n").unwrap();
+        writer.close();
+        decorator_writer.write_all(b"g() {}\n").unwrap();
+    }
+    assert_eq!(fs::read_to_string(&path).unwrap(), "f() {}\n");
+    let _ignore_error = fs::remove_file(&path);
+}
+
+#[cfg(not(feature = "log_merger"))]
+#[test]
+fn each_thread_logs_to_own_file() {
+    #[loggable]
+    fn first_thread_work() {}
+    #[loggable]
+    fn second_thread_work() {}
+
+    let pattern = std::env::temp_dir().join("fcl_{name}_{id}.log").to_string_lossy().into_owned();
+    fcl::common::settings::set_thread_log_file_pattern(Some(pattern.clone()));
+
+    // Spawns the thread that logs the work and returns the thread's log file path.
+    let spawn_thread = |name: &str, work: fn()| {
+        let pattern = pattern.clone();
+        std::thread::Builder::new()
+            .name(format!("{}_{}", name, std::process::id()))
+            .spawn(move || {
+                work();
+                thread_log_file_path(&pattern, &std::thread::current())
+            })
+            .unwrap()
+    };
+    let first_thread = spawn_thread("first", first_thread_work);
+    let second_thread = spawn_thread("second", second_thread_work);
+    let first_path = first_thread.join().unwrap();
+    let second_path = second_thread.join().unwrap();
+
+    let first_log = fs::read_to_string(&first_path).unwrap();
+    let second_log = fs::read_to_string(&second_path).unwrap();
+    assert!(first_log.contains("first_thread_work()"), "{}", first_log);
+    assert!(!first_log.contains("second_thread_work()"), "{}", first_log);
+    assert!(second_log.contains("second_thread_work()"), "{}", second_log);
+    assert!(!second_log.contains("first_thread_work()"), "{}", second_log);
+    let _ignore_error = fs::remove_file(&first_path);
+    let _ignore_error = fs::remove_file(&second_path);
+}
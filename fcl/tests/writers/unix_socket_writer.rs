@@ -0,0 +1,155 @@
+use std::{
+    io::{Read, Write},
+    os::unix::net::UnixStream,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use fcl::common::unix_socket_writer::UnixSocketWriter;
+
+// High-level logic to test:
+// The `UnixSocketWriter` streams the log to the viewer connected to its Unix domain socket,
+// keeps the backlog (or drops the log) while no viewer is connected,
+// and accepts the next viewer after the previous one disconnects.
+//
+// Test cases:
+//
+// A: `sends_backlog_then_streams`:
+// A line written before the viewer connects, a line written after.
+// Assert: The viewer gets both lines.
+//
+// B: `drops_without_backlog`:
+// The backlog capacity of 0. A line written before the viewer connects, a line written after.
+// Assert: The viewer gets the second line only.
+//
+// C: `accepts_next_viewer`:
+// A viewer gets a line and disconnects, a line is written, the next viewer connects, a line is written.
+// Assert: The next viewer gets the lines written after the first viewer has disconnected.
+//
+// D: `accepts_viewer_at_line_start`:
+// A line written in two parts with the viewer connected in between.
+// Assert: The viewer gets the whole line (from the backlog).
+//
+// E: `refuses_non_socket_path`:
+// A regular file at the socket path.
+// Assert: The writer creation fails with `AlreadyExists`, the file is preserved.
+//
+// F: `drop_removes_own_socket_only`:
+// The second writer is created at the socket path of the first one, then the first one is dropped.
+// Assert: The second writer's socket is preserved and accepts a viewer. After the second writer is dropped
+// the socket is removed.
+//
+// G: `slow_viewer_gets_whole_log`:
+// A viewer reads (in a separate thread) the log much larger than the socket buffer, written while it's not reading.
+// Assert: The writes don't block, the viewer gets the whole log (from the backlog) in order and without duplicates.
+
+/// Returns a unique socket path for a test case.
+fn socket_path(test_case: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "fcl_unix_socket_writer_{}_{}.sock",
+        test_case,
+        std::process::id()
+    ))
+}
+
+/// Connects a viewer to the socket.
+fn connect(writer: &UnixSocketWriter) -> UnixStream {
+    let viewer = UnixStream::connect(writer.path()).unwrap();
+    viewer
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    viewer
+}
+
+/// Reads from the viewer the number of bytes of the `expected` string and returns them as a string.
+fn read(viewer: &mut UnixStream, expected: &str) -> String {
+    let mut buf = vec![0u8; expected.len()];
+    viewer.read_exact(&mut buf).unwrap();
+    String::from_utf8(buf).unwrap()
+}
+
+#[test]
+fn sends_backlog_then_streams() {
+    let mut writer = UnixSocketWriter::new(socket_path("sends_backlog_then_streams"), 1024).unwrap();
+    writer.write_all(b"f() {}\n").unwrap();
+    let mut viewer = connect(&writer);
+    writer.write_all(b"g() {}\n").unwrap();
+    assert_eq!(read(&mut viewer, "f() {}\ng() {}\n"), "f() {}\ng() {}\n");
+}
+
+#[test]
+fn drops_without_backlog() {
+    let mut writer = UnixSocketWriter::new(socket_path("drops_without_backlog"), 0).unwrap();
+    writer.write_all(b"f() {}\n").unwrap();
+    let mut viewer = connect(&writer);
+    writer.write_all(b"g() {}\n").unwrap();
+    assert_eq!(read(&mut viewer, "g() {}\n"), "g() {}\n");
+}
+
+#[test]
+fn accepts_next_viewer() {
+    let mut writer = UnixSocketWriter::new(socket_path("accepts_next_viewer"), 1024).unwrap();
+    let mut viewer = connect(&writer);
+    writer.write_all(b"f() {}\n").unwrap();
+    assert_eq!(read(&mut viewer, "f() {}\n"), "f() {}\n");
+    drop(viewer);
+
+    writer.write_all(b"g() {}\n").unwrap();
+    let mut viewer = connect(&writer);
+    writer.write_all(b"h() {}\n").unwrap();
+    assert_eq!(read(&mut viewer, "g() {}\nh() {}\n"), "g() {}\nh() {}\n");
+}
+
+#[test]
+fn accepts_viewer_at_line_start() {
+    let mut writer = UnixSocketWriter::new(socket_path("accepts_viewer_at_line_start"), 1024).unwrap();
+    writer.write_all(b"f() {").unwrap();
+    let mut viewer = connect(&writer);
+    writer.write_all(b"}\n").unwrap();
+    writer.flush().unwrap();
+    assert_eq!(read(&mut viewer, "f() {}\n"), "f() {}\n");
+}
+
+#[test]
+fn refuses_non_socket_path() {
+    let path = socket_path("refuses_non_socket_path");
+    std::fs::write(&path, "user data").unwrap();
+    let error = UnixSocketWriter::new(&path, 1024).err().unwrap();
+    assert_eq!(error.kind(), std::io::ErrorKind::AlreadyExists);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "user data");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn drop_removes_own_socket_only() {
+    let path = socket_path("drop_removes_own_socket_only");
+    let first_writer = UnixSocketWriter::new(&path, 1024).unwrap();
+    let mut second_writer = UnixSocketWriter::new(&path, 1024).unwrap();
+    drop(first_writer);
+    assert!(path.exists());
+
+    let mut viewer = connect(&second_writer);
+    second_writer.write_all(b"f() {}\n").unwrap();
+    assert_eq!(read(&mut viewer, "f() {}\n"), "f() {}\n");
+
+    drop(second_writer);
+    assert!(!path.exists());
+}
+
+#[test]
+fn slow_viewer_gets_whole_log() {
+    let mut writer = UnixSocketWriter::new(socket_path("slow_viewer_gets_whole_log"), 16 * 1024 * 1024).unwrap();
+    let mut viewer = connect(&writer);
+    writer.flush().unwrap();
+    let log: String = (0..100_000).map(|line_number| format!("f{}() {{}}\n", line_number)).collect();
+    let write_start = Instant::now();
+    writer.write_all(log.as_bytes()).unwrap();
+    assert!(write_start.elapsed() < Duration::from_millis(500));
+
+    let reader = std::thread::spawn(move || read(&mut viewer, &log) == log);
+    while !reader.is_finished() {
+        writer.flush().unwrap();
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    assert!(reader.join().unwrap());
+}
@@ -0,0 +1,83 @@
+use std::io::Write;
+
+use fcl::common::parallel::{UpdateLog, merge_chunks, next_update_id};
+
+// High-level logic to test:
+// With the "parallel_logging" feature each thread's decorator output is kept in the thread's `UpdateLog`
+// split into chunks by the ID of the call graph update that produced the output,
+// and the chunks of all the threads are merged in the order of the update IDs.
+//
+// Test cases:
+//
+// A: `chunks_by_update_id`:
+// Update 5: writes "a", "b". Update 7: writes "c". Update 5 again: writes "d".
+// Assert: The chunks are (5, "ab"), (7, "c"), (5, "d"), the length is 4; after taking the log is empty.
+//
+// B: `merge_in_update_order`:
+// Thread 0 chunks: (0, "n"), (2, "n+2"), (4, "n+4"). Thread 1 chunks: (1, "n+1"), (3, "n+3").
+// Assert: The merged log is "n n+1 n+2 n+3 n+4".
+//
+// C: `update_ids_grow`:
+// Assert: The subsequent update IDs grow.
+//
+// D: `hold_back_update_in_progress`:
+// An update writes "a" and completes. The next update writes "b" and is in progress.
+// Assert: The update in progress is reported; the chunks before it are taken, its chunk is kept;
+// after the update completes no update is in progress.
+
+#[test]
+fn chunks_by_update_id() {
+    let mut log = UpdateLog::new();
+    log.set_update_id(5);
+    write!(log, "a").unwrap();
+    write!(log, "b").unwrap();
+    log.set_update_id(7);
+    write!(log, "c").unwrap();
+    log.set_update_id(5);
+    write!(log, "d").unwrap();
+    assert_eq!(log.len(), 4);
+
+    assert_eq!(
+        log.take_chunks(),
+        vec![(5, b"ab".to_vec()), (7, b"c".to_vec()), (5, b"d".to_vec())]
+    );
+    assert!(log.is_empty());
+    assert_eq!(log.take_chunks(), vec![]);
+}
+
+#[test]
+fn merge_in_update_order() {
+    let thread_0_chunks = vec![(0, b"n ".to_vec()), (2, b"n+2 ".to_vec()), (4, b"n+4".to_vec())];
+    let thread_1_chunks = vec![(1, b"n+1 ".to_vec()), (3, b"n+3 ".to_vec())];
+    assert_eq!(
+        merge_chunks(vec![thread_0_chunks, thread_1_chunks]),
+        b"n n+1 n+2 n+3 n+4".to_vec()
+    );
+}
+
+#[test]
+fn update_ids_grow() {
+    let first = next_update_id();
+    let second = next_update_id();
+    assert!(first < second);
+}
+
+#[test]
+fn hold_back_update_in_progress() {
+    let mut log = UpdateLog::new();
+    assert_eq!(log.update_in_progress(), None);
+    log.begin_update();
+    let complete_update_id = log.update_in_progress().unwrap();
+    write!(log, "a").unwrap();
+    log.end_update();
+    log.begin_update();
+    write!(log, "b").unwrap();
+    let update_id = log.update_in_progress().unwrap();
+    assert!(complete_update_id < update_id);
+
+    assert_eq!(log.take_chunks_before(update_id), vec![(complete_update_id, b"a".to_vec())]);
+    assert_eq!(log.len(), 1);
+    log.end_update();
+    assert_eq!(log.update_in_progress(), None);
+    assert_eq!(log.take_chunks(), vec![(update_id, b"b".to_vec())]);
+}
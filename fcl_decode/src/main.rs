@@ -0,0 +1,60 @@
+// Decodes the FCL binary trace (logged by the `BinaryTraceDecorator`) to the code-like or tree-like text
+// on the standard output.
+//
+// Usage: fcl_decode [--tree | --tree-unicode] <trace file>
+
+use std::{cell::RefCell, process::ExitCode, rc::Rc};
+
+use fcl::common::binary_trace::decode_binary_trace;
+use fcl::common::decorators::{CodeLikeDecorator, LogDecorator, TreeLikeDecorator, TreeStyle};
+
+/// The usage message.
+const USAGE: &str = "Usage: fcl_decode [--tree | --tree-unicode] <trace file>";
+
+fn main() -> ExitCode {
+    let mut tree_style = None;
+    let mut trace_path = None;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--tree" => tree_style = Some(TreeStyle::Ascii),
+            "--tree-unicode" => tree_style = Some(TreeStyle::Unicode),
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            }
+            _ if trace_path.is_none() && !arg.starts_with('-') => trace_path = Some(arg),
+            _ => {
+                eprintln!("{}", USAGE);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let Some(trace_path) = trace_path else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    let mut trace = match std::fs::File::open(&trace_path) {
+        Ok(trace) => std::io::BufReader::new(trace),
+        Err(error) => {
+            eprintln!("fcl_decode: {}: {}", trace_path, error);
+            return ExitCode::FAILURE;
+        }
+    };
+    let result = decode_binary_trace(&mut trace, |_thread_id, _thread_name| {
+        let decorator: Rc<RefCell<dyn LogDecorator>> = match tree_style {
+            None => Rc::new(RefCell::new(CodeLikeDecorator::new(None, None))),
+            Some(style) => {
+                let mut decorator = TreeLikeDecorator::new(None, None, None, None);
+                decorator.set_style(style);
+                Rc::new(RefCell::new(decorator))
+            }
+        };
+        decorator
+    });
+    if let Err(error) = result {
+        eprintln!("fcl_decode: {}: {}", trace_path, error);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,88 @@
+// Connects to the Unix domain socket of the FCL's `UnixSocketWriter` and shows the log live
+// on the standard output. Reconnects if the logging program restarts (unless `--once`).
+//
+// Usage: fcl-tail [--once] <socket path>
+
+use std::process::ExitCode;
+#[cfg(unix)]
+use std::{
+    io::{Read, Write},
+    time::Duration,
+};
+
+/// The usage message.
+#[cfg(unix)]
+const USAGE: &str = "Usage: fcl-tail [--once] <socket path>";
+
+/// The pause between the connection attempts.
+#[cfg(unix)]
+const RECONNECT_PAUSE: Duration = Duration::from_millis(500);
+
+#[cfg(unix)]
+fn main() -> ExitCode {
+    let mut once = false;
+    let mut socket_path = None;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--once" => once = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            }
+            _ if socket_path.is_none() && !arg.starts_with('-') => socket_path = Some(arg),
+            _ => {
+                eprintln!("{}", USAGE);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let Some(socket_path) = socket_path else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    let mut stdout = std::io::stdout();
+    let mut waiting_reported = false;
+    loop {
+        let mut stream = match std::os::unix::net::UnixStream::connect(&socket_path) {
+            Ok(stream) => stream,
+            Err(error) => {
+                if once {
+                    eprintln!("fcl-tail: {}: {}", socket_path, error);
+                    return ExitCode::FAILURE;
+                }
+                if !waiting_reported {
+                    eprintln!("fcl-tail: Waiting for {}...", socket_path);
+                    waiting_reported = true;
+                }
+                std::thread::sleep(RECONNECT_PAUSE);
+                continue;
+            }
+        };
+        waiting_reported = false;
+
+        // Show the stream until the logging program disconnects:
+        let mut buf = [0u8; 8192];
+        loop {
+            match stream.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(size) => {
+                    if stdout.write_all(&buf[..size]).and_then(|_| stdout.flush()).is_err() {
+                        // The standard output is closed (e.g. `fcl-tail ... | head`).
+                        return ExitCode::SUCCESS;
+                    }
+                }
+            }
+        }
+        if once {
+            return ExitCode::SUCCESS;
+        }
+        eprintln!("fcl-tail: {} disconnected.", socket_path);
+    }
+}
+
+#[cfg(not(unix))]
+fn main() -> ExitCode {
+    eprintln!("fcl-tail: The Unix domain sockets are not supported on this platform.");
+    ExitCode::FAILURE
+}